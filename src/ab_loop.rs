@@ -0,0 +1,227 @@
+use rspotify::spotify::model::audio::AudioAnalysis;
+use std::fmt;
+
+/// What the loop points are moved to when they are marked
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Snap {
+    Off,
+    Bar,
+    Section,
+}
+
+impl Snap {
+    pub fn next(self) -> Snap {
+        match self {
+            Snap::Off => Snap::Bar,
+            Snap::Bar => Snap::Section,
+            Snap::Section => Snap::Off,
+        }
+    }
+}
+
+impl fmt::Display for Snap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Snap::Off => write!(f, "off"),
+            Snap::Bar => write!(f, "bar"),
+            Snap::Section => write!(f, "section"),
+        }
+    }
+}
+
+// What the loop limit key cycles through, before going back to no limit
+const LOOP_LIMITS: [u32; 5] = [1, 2, 3, 5, 10];
+
+pub fn next_loop_limit(limit: Option<u32>) -> Option<u32> {
+    match limit {
+        None => Some(LOOP_LIMITS[0]),
+        Some(limit) => LOOP_LIMITS.iter().find(|next| **next > limit).copied(),
+    }
+}
+
+/// The part of a track that is repeated
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopRegion {
+    pub track_uri: String,
+    pub a_ms: u32,
+    /// `None` until B has been marked
+    pub b_ms: Option<u32>,
+    /// How many times playback went back to A
+    pub count: u32,
+}
+
+pub struct AbLoop {
+    pub region: Option<LoopRegion>,
+    pub snap: Snap,
+    /// How many times to go back to A before playing on, `None` to loop until cleared
+    pub limit: Option<u32>,
+}
+
+impl Default for AbLoop {
+    fn default() -> Self {
+        AbLoop {
+            region: None,
+            snap: Snap::Off,
+            limit: None,
+        }
+    }
+}
+
+impl AbLoop {
+    /// Marks A, then B, then clears the loop. `analysis` has to be for the track of
+    /// `track_uri` and is only used when snapping.
+    pub fn mark(&mut self, track_uri: &str, position_ms: u32, analysis: Option<&AudioAnalysis>) {
+        let snap = self.snap;
+        match &mut self.region {
+            Some(region) if region.track_uri == track_uri && region.b_ms.is_none() => {
+                let b_ms = snap_position(analysis, snap, position_ms, true);
+                if b_ms > region.a_ms {
+                    region.b_ms = Some(b_ms);
+                } else {
+                    // B has to come after A, so start over from here
+                    region.a_ms = snap_position(analysis, snap, position_ms, false);
+                }
+            }
+            Some(region) if region.track_uri == track_uri => {
+                self.region = None;
+            }
+            _ => {
+                self.region = Some(LoopRegion {
+                    track_uri: track_uri.to_owned(),
+                    a_ms: snap_position(analysis, snap, position_ms, false),
+                    b_ms: None,
+                    count: 0,
+                });
+            }
+        }
+    }
+
+    /// The position to go back to once playback of `track_uri` has passed B. The loop is
+    /// cleared when another track plays or the limit has been reached.
+    pub fn next_seek(&mut self, track_uri: &str, progress_ms: u128) -> Option<u32> {
+        let region = self.region.as_mut()?;
+        if region.track_uri != track_uri {
+            self.region = None;
+            return None;
+        }
+
+        let b_ms = region.b_ms?;
+        if progress_ms < u128::from(b_ms) {
+            return None;
+        }
+
+        if let Some(limit) = self.limit {
+            if region.count >= limit {
+                self.region = None;
+                return None;
+            }
+        }
+
+        region.count += 1;
+        Some(region.a_ms)
+    }
+}
+
+/// Moves `position_ms` to the start of the bar or section it is in, or to its end when
+/// `to_end` is set. Positions the analysis doesn't cover are left as they are.
+pub fn snap_position(
+    analysis: Option<&AudioAnalysis>,
+    snap: Snap,
+    position_ms: u32,
+    to_end: bool,
+) -> u32 {
+    let intervals: Vec<(f32, f32)> = match (analysis, snap) {
+        (Some(analysis), Snap::Bar) => analysis
+            .bars
+            .iter()
+            .map(|bar| (bar.start, bar.duration))
+            .collect(),
+        (Some(analysis), Snap::Section) => analysis
+            .sections
+            .iter()
+            .map(|section| (section.start, section.duration))
+            .collect(),
+        _ => return position_ms,
+    };
+
+    snap_to_intervals(&intervals, position_ms, to_end)
+}
+
+// Intervals are (start, duration) in seconds, as in the audio analysis
+fn snap_to_intervals(intervals: &[(f32, f32)], position_ms: u32, to_end: bool) -> u32 {
+    let position = position_ms as f32 / 1000.0;
+    intervals
+        .iter()
+        .find(|(start, duration)| *start <= position && position < start + duration)
+        .map_or(position_ms, |(start, duration)| {
+            let snapped = if to_end { start + duration } else { *start };
+            (snapped * 1000.0) as u32
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BARS: [(f32, f32); 3] = [(0.0, 2.0), (2.0, 2.0), (4.0, 2.5)];
+
+    #[test]
+    fn test_snap_to_intervals() {
+        assert_eq!(snap_to_intervals(&BARS, 3_000, false), 2_000);
+        assert_eq!(snap_to_intervals(&BARS, 3_000, true), 4_000);
+        assert_eq!(snap_to_intervals(&BARS, 4_000, false), 4_000);
+        assert_eq!(snap_to_intervals(&BARS, 5_000, true), 6_500);
+        // Past the last bar
+        assert_eq!(snap_to_intervals(&BARS, 9_000, false), 9_000);
+    }
+
+    #[test]
+    fn test_next_loop_limit() {
+        assert_eq!(next_loop_limit(None), Some(1));
+        assert_eq!(next_loop_limit(Some(3)), Some(5));
+        assert_eq!(next_loop_limit(Some(10)), None);
+    }
+
+    #[test]
+    fn test_mark_without_snap() {
+        let mut ab_loop = AbLoop::default();
+        ab_loop.mark("spotify:track:1", 10_000, None);
+        ab_loop.mark("spotify:track:1", 5_000, None);
+        assert_eq!(ab_loop.region.as_ref().unwrap().a_ms, 5_000);
+
+        ab_loop.mark("spotify:track:1", 20_000, None);
+        let region = ab_loop.region.as_ref().unwrap();
+        assert_eq!((region.a_ms, region.b_ms), (5_000, Some(20_000)));
+
+        ab_loop.mark("spotify:track:1", 30_000, None);
+        assert!(ab_loop.region.is_none());
+    }
+
+    #[test]
+    fn test_next_seek() {
+        let mut ab_loop = AbLoop {
+            limit: Some(2),
+            ..Default::default()
+        };
+        ab_loop.mark("spotify:track:1", 5_000, None);
+        assert_eq!(ab_loop.next_seek("spotify:track:1", 25_000), None);
+
+        ab_loop.mark("spotify:track:1", 20_000, None);
+        assert_eq!(ab_loop.next_seek("spotify:track:1", 19_000), None);
+        assert_eq!(ab_loop.next_seek("spotify:track:1", 20_100), Some(5_000));
+        assert_eq!(ab_loop.next_seek("spotify:track:1", 20_100), Some(5_000));
+
+        // Play on once the limit is reached
+        assert_eq!(ab_loop.next_seek("spotify:track:1", 20_100), None);
+        assert!(ab_loop.region.is_none());
+    }
+
+    #[test]
+    fn test_next_seek_clears_on_other_track() {
+        let mut ab_loop = AbLoop::default();
+        ab_loop.mark("spotify:track:1", 5_000, None);
+        ab_loop.mark("spotify:track:1", 20_000, None);
+        assert_eq!(ab_loop.next_seek("spotify:track:2", 25_000), None);
+        assert!(ab_loop.region.is_none());
+    }
+}
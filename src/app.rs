@@ -1,14 +1,28 @@
-use super::{config::ClientConfig, user_config::UserConfig};
+use super::{
+    ab_loop::{AbLoop, Snap},
+    autoplay::Autoplay,
+    config::ClientConfig,
+    playlist_form::PlaylistForm,
+    playlist_picker::{is_editable, PickerPlaylist, PlaylistPicker},
+    podcasts::{SavedShow, SimplifiedEpisode, SimplifiedShow},
+    preferred_devices::find_preferred_device,
+    queue::{Queue, ResumePoint, ADVANCE_MARGIN_MS},
+    recommendations::{normalize_genre, parse_seed_query, RecommendationsBuilder, Seed, SeedKind},
+    seek::{next_section_start, parse_seek_position, percentage_position, previous_section_start},
+    sleep_timer::{ContextEnd, SleepTimer, SleepTimerEnd, SleepTimerSetting},
+    user_config::{TrackColumn, TrackColumnConfig, TrackTablesConfig, UserConfig},
+    web_api,
+};
 use failure::{err_msg, format_err};
 use rspotify::spotify::{
     client::Spotify,
     model::{
         album::{FullAlbum, SavedAlbum, SimplifiedAlbum},
         artist::FullArtist,
-        audio::AudioAnalysis,
+        audio::{AudioAnalysis, AudioFeatures},
         context::FullPlayingContext,
         device::DevicePayload,
-        offset::{for_position, Offset},
+        offset::{for_position, for_uri, Offset},
         page::{CursorBasedPage, Page},
         playing::PlayHistory,
         playlist::{PlaylistTrack, SimplifiedPlaylist},
@@ -23,10 +37,11 @@ use serde_json::{map::Map, Value};
 use std::str::FromStr;
 use std::{
     cmp::{max, min},
-    collections::HashSet,
-    time::Instant,
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
 };
 use tui::layout::Rect;
+use unicode_width::UnicodeWidthStr;
 
 use clipboard::{ClipboardContext, ClipboardProvider};
 
@@ -45,6 +60,17 @@ const DEFAULT_ROUTE: Route = Route {
     hovered_block: ActiveBlock::Library,
 };
 
+// How long a notification is shown for
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(5);
+
+// Liked songs are played as a list of uris, which Spotify won't take arbitrarily long, and
+// each page of them is another request
+const MAX_SAVED_TRACK_URIS: usize = 500;
+const SAVED_TRACKS_PAGE_LIMIT: u32 = 50;
+
+// The most playlists Spotify returns at once
+const PLAYLISTS_PAGE_LIMIT: u32 = 50;
+
 #[derive(Clone)]
 pub struct ScrollableResultPages<T> {
     index: usize,
@@ -87,6 +113,7 @@ pub struct Library {
     pub made_for_you_playlists: ScrollableResultPages<Page<SimplifiedPlaylist>>,
     pub saved_albums: ScrollableResultPages<Page<SavedAlbum>>,
     pub saved_artists: ScrollableResultPages<CursorBasedPage<FullArtist>>,
+    pub saved_shows: ScrollableResultPages<Page<SavedShow>>,
 }
 
 #[derive(Clone)]
@@ -113,6 +140,22 @@ pub enum ArtistBlock {
     Empty,
 }
 
+struct Notification {
+    message: String,
+    shown_at: Instant,
+}
+
+/// What the text typed into the input is for
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum InputPrompt {
+    Search,
+    Seek,
+    Volume,
+    RecommendationsSeed,
+    RecommendationsValue,
+    PlaylistFormText,
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum ActiveBlock {
     Analysis,
@@ -127,8 +170,13 @@ pub enum ActiveBlock {
     Input,
     Library,
     MyPlaylists,
+    PlaylistForm,
+    PlaylistPicker,
     Podcasts,
+    EpisodeTable,
+    Queue,
     RecentlyPlayed,
+    RecommendationsBuilder,
     SearchResultBlock,
     SelectDevice,
     TrackTable,
@@ -151,7 +199,10 @@ pub enum RouteId {
     MadeForYou,
     Artists,
     Podcasts,
+    PodcastEpisodes,
+    Queue,
     Recommendations,
+    RecommendationsBuilder,
 }
 
 #[derive(Debug)]
@@ -182,6 +233,8 @@ pub enum AlbumTableContext {
 pub enum RecommendationsContext {
     Artist,
     Song,
+    /// The seeds and tuning of the recommendations builder
+    Builder,
 }
 
 pub struct SearchResult {
@@ -197,11 +250,30 @@ pub struct SearchResult {
     pub selected_block: SearchResultBlock,
 }
 
+// When and by whom a track was added, known for liked songs and playlist tracks
+#[derive(Clone)]
+pub struct TrackAdded {
+    pub at: String,
+    pub by: Option<String>,
+}
+
+/// What the tracks in the track table are part of, so playing one carries on through the rest
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrackSource {
+    /// An album or playlist. `offset` is where the first track of the table is in it.
+    Context { uri: String, offset: u32 },
+    /// Liked songs, which Spotify can't play as a context
+    SavedTracks { offset: u32 },
+}
+
 #[derive(Default)]
 pub struct TrackTable {
     pub tracks: Vec<FullTrack>,
+    pub added: Vec<TrackAdded>,
     pub selected_index: usize,
     pub context: Option<TrackTableContext>,
+    /// `None` when the tracks only belong together in the table, like recommendations
+    pub source: Option<TrackSource>,
 }
 
 #[derive(Clone)]
@@ -217,6 +289,14 @@ pub struct SelectedFullAlbum {
     pub selected_index: usize,
 }
 
+/// A show opened from the podcasts in the library
+pub struct SelectedShow {
+    pub show: SimplifiedShow,
+    pub is_saved: bool,
+    pub episodes: ScrollableResultPages<Page<SimplifiedEpisode>>,
+    pub selected_index: usize,
+}
+
 #[derive(Clone)]
 pub struct Artist {
     pub artist_name: String,
@@ -233,7 +313,10 @@ pub struct Artist {
 pub struct App {
     instant_since_last_current_playback_poll: Instant,
     navigation_stack: Vec<Route>,
+    pub ab_loop: AbLoop,
     pub audio_analysis: Option<AudioAnalysis>,
+    /// The track `audio_analysis` is for
+    audio_analysis_uri: Option<String>,
     pub home_scroll: u16,
     pub client_config: ClientConfig,
     pub user_config: UserConfig,
@@ -253,6 +336,17 @@ pub struct App {
     pub input: Vec<char>,
     pub input_idx: usize,
     pub input_cursor_position: u16,
+    pub input_prompt: InputPrompt,
+    notification: Option<Notification>,
+    /// The volume from before muting, `None` when not muted
+    pub muted_volume: Option<u8>,
+    pub playlist_form: Option<PlaylistForm>,
+    // The blocks to go back to when the playlist form closes
+    playlist_form_return: Option<(ActiveBlock, ActiveBlock)>,
+    pub playlist_picker: Option<PlaylistPicker>,
+    playlist_picker_return: Option<(ActiveBlock, ActiveBlock)>,
+    // The blocks to go back to once a prompt has been answered
+    input_prompt_return: Option<(ActiveBlock, ActiveBlock)>,
     pub liked_song_ids_set: HashSet<String>,
     pub large_search_limit: u32,
     pub library: Library,
@@ -262,11 +356,17 @@ pub struct App {
     pub playlist_tracks: Option<Page<PlaylistTrack>>,
     pub made_for_you_tracks: Option<Page<PlaylistTrack>>,
     pub playlists: Option<Page<SimplifiedPlaylist>>,
+    pub queue: Queue,
     pub recently_played: SpotifyResultAndSelectedIndex<Option<CursorBasedPage<PlayHistory>>>,
     pub recommended_tracks: Vec<FullTrack>,
     pub recommendations_seed: String,
     pub recommendations_context: Option<RecommendationsContext>,
+    pub recommendations_builder: RecommendationsBuilder,
+    // The genres recommendations can be seeded with, fetched when first needed
+    recommendation_genres: Vec<String>,
     pub search_results: SearchResult,
+    pub sleep_timer: Option<SleepTimer>,
+    pub autoplay: Autoplay,
     pub selected_album_simplified: Option<SelectedAlbum>,
     pub selected_album_full: Option<SelectedFullAlbum>,
     pub selected_device_index: Option<usize>,
@@ -278,6 +378,8 @@ pub struct App {
     pub track_table: TrackTable,
     pub user: Option<PrivateUser>,
     pub album_list_index: usize,
+    pub shows_list_index: usize,
+    pub selected_show: Option<SelectedShow>,
     pub made_for_you_index: usize,
     pub artists_list_index: usize,
     pub clipboard_context: Option<ClipboardContext>,
@@ -285,14 +387,25 @@ pub struct App {
     pub help_menu_page: u32,
     pub help_menu_max_lines: u32,
     pub help_menu_offset: u32,
+    pub help_menu_filter: String,
+    pub help_menu_filter_active: bool,
+    pub help_menu_context_only: bool,
+    pub help_menu_block: ActiveBlock,
+    pub show_sidebar: bool,
+    // `None` for tracks that Spotify has no audio features for
+    pub track_audio_features: HashMap<String, Option<AudioFeatures>>,
 }
 
 impl App {
     pub fn new() -> App {
         App {
+            ab_loop: Default::default(),
             audio_analysis: None,
+            audio_analysis_uri: None,
             album_table_context: AlbumTableContext::Full,
             album_list_index: 0,
+            shows_list_index: 0,
+            selected_show: None,
             made_for_you_index: 0,
             artists_list_index: 0,
             artists: vec![],
@@ -310,6 +423,7 @@ impl App {
                 made_for_you_playlists: ScrollableResultPages::new(),
                 saved_albums: ScrollableResultPages::new(),
                 saved_artists: ScrollableResultPages::new(),
+                saved_shows: ScrollableResultPages::new(),
                 selected_index: 0,
             },
             liked_song_ids_set: HashSet::new(),
@@ -322,14 +436,27 @@ impl App {
             input: vec![],
             input_idx: 0,
             input_cursor_position: 0,
+            input_prompt: InputPrompt::Search,
+            notification: None,
+            muted_volume: None,
+            playlist_form: None,
+            playlist_form_return: None,
+            playlist_picker: None,
+            playlist_picker_return: None,
+            input_prompt_return: None,
             playlist_offset: 0,
             made_for_you_offset: 0,
             playlist_tracks: None,
             made_for_you_tracks: None,
             playlists: None,
+            queue: Default::default(),
             recommended_tracks: vec![],
             recommendations_context: None,
             recommendations_seed: "".to_string(),
+            recommendations_builder: Default::default(),
+            recommendation_genres: vec![],
+            sleep_timer: None,
+            autoplay: Default::default(),
             search_results: SearchResult {
                 hovered_block: SearchResultBlock::SongSearch,
                 selected_block: SearchResultBlock::Empty,
@@ -359,6 +486,12 @@ impl App {
             help_menu_page: 0,
             help_menu_max_lines: 0,
             help_menu_offset: 0,
+            help_menu_filter: String::new(),
+            help_menu_filter_active: false,
+            help_menu_context_only: false,
+            help_menu_block: ActiveBlock::Empty,
+            show_sidebar: true,
+            track_audio_features: HashMap::new(),
         }
     }
 
@@ -376,16 +509,153 @@ impl App {
     }
 
     pub fn handle_get_devices(&mut self) {
+        if self.refresh_devices() {
+            self.push_navigation_stack(RouteId::SelectedDevice, ActiveBlock::SelectDevice);
+        }
+    }
+
+    /// Fetches the devices again, returning whether that worked
+    pub fn refresh_devices(&mut self) -> bool {
         if let Some(spotify) = &self.spotify {
             if let Ok(result) = spotify.device() {
-                self.push_navigation_stack(RouteId::SelectedDevice, ActiveBlock::SelectDevice);
-                if !result.devices.is_empty() {
+                if result.devices.is_empty() {
+                    self.devices = None;
+                    self.selected_device_index = None;
+                } else {
+                    // Keep the selection on the same device when it's still there
+                    let selected_device_id = self
+                        .devices
+                        .as_ref()
+                        .zip(self.selected_device_index)
+                        .and_then(|(devices, index)| devices.devices.get(index))
+                        .map(|device| device.id.to_owned());
+                    let index = result
+                        .devices
+                        .iter()
+                        .position(|device| Some(&device.id) == selected_device_id.as_ref())
+                        .unwrap_or(0);
+
                     self.devices = Some(result);
-                    // Select the first device in the list
-                    self.selected_device_index = Some(0);
+                    self.selected_device_index = Some(index);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Picks the device to play on at startup: the saved one when it's available, otherwise the
+    /// first available preferred device. Opens the device list when neither is available.
+    pub fn select_startup_device(&mut self) {
+        let has_devices = self.refresh_devices();
+        let is_saved_device_missing = has_devices && !self.is_saved_device_available();
+
+        if self.client_config.device_id.is_none() || is_saved_device_missing {
+            match self.use_preferred_device() {
+                Some(name) => self.notify(format!("Playing on {}", name)),
+                None => self.handle_get_devices(),
+            }
+        }
+    }
+
+    fn is_saved_device_available(&self) -> bool {
+        match (&self.client_config.device_id, &self.devices) {
+            (Some(device_id), Some(devices)) => {
+                devices.devices.iter().any(|device| device.id == *device_id)
+            }
+            _ => false,
+        }
+    }
+
+    // Saves the first available preferred device as the device to play on, returning its name
+    fn use_preferred_device(&mut self) -> Option<String> {
+        let (device_id, name) = self.devices.as_ref().and_then(|devices| {
+            find_preferred_device(
+                &devices.devices,
+                &self.user_config.behavior.preferred_devices,
+            )
+            .map(|device| (device.id.to_owned(), device.name.to_owned()))
+        })?;
+
+        match self.client_config.set_device_id(device_id) {
+            Ok(()) => Some(name),
+            Err(e) => {
+                self.handle_error(e);
+                None
+            }
+        }
+    }
+
+    // Falls back to a preferred device when the saved device is gone, returning whether the
+    // user has been told about it
+    fn handle_missing_device(&mut self) -> bool {
+        if self.client_config.device_id.is_none()
+            || !self.refresh_devices()
+            || self.is_saved_device_available()
+        {
+            return false;
+        }
+
+        let message = match self.use_preferred_device() {
+            Some(name) => format!("The saved device isn't available, switched to {}", name),
+            None => format!(
+                "The saved device isn't available, press {} to choose another one",
+                self.user_config.keys.manage_devices
+            ),
+        };
+        self.notify(message);
+        true
+    }
+
+    /// Makes `device_id` the device to play on, moving playback to it if something is playing
+    pub fn transfer_playback(&mut self, device_id: &str) {
+        let playing_device_id = self
+            .current_playback_context
+            .as_ref()
+            .map(|context| context.device.id.to_owned());
+
+        if let (Some(spotify), Some(playing_device_id)) = (&self.spotify, playing_device_id) {
+            if playing_device_id != device_id {
+                if let Err(e) = web_api::transfer_playback(spotify, device_id, self.is_playing()) {
+                    self.handle_error(e);
+                    return;
                 }
             }
         }
+
+        match self.client_config.set_device_id(device_id.to_owned()) {
+            Ok(()) => self.get_current_playback(),
+            Err(e) => self.handle_error(e),
+        }
+    }
+
+    /// Moves playback to the device after the one playing, skipping restricted devices
+    pub fn transfer_playback_to_next_device(&mut self) {
+        if !self.refresh_devices() {
+            return;
+        }
+
+        let current_device_id = match &self.current_playback_context {
+            Some(context) => Some(context.device.id.to_owned()),
+            None => self.client_config.device_id.clone(),
+        };
+        let next_device_id = self.devices.as_ref().and_then(|devices| {
+            let devices = &devices.devices;
+            let current_index = devices
+                .iter()
+                .position(|device| Some(&device.id) == current_device_id.as_ref());
+            let start = current_index.map_or(0, |index| index + 1);
+            (0..devices.len())
+                .map(|offset| &devices[(start + offset) % devices.len()])
+                .find(|device| {
+                    !device.is_restricted && Some(&device.id) != current_device_id.as_ref()
+                })
+                .map(|device| device.id.to_owned())
+        });
+
+        if let Some(device_id) = next_device_id {
+            self.transfer_playback(&device_id);
+        }
     }
 
     pub fn get_current_playback(&mut self) {
@@ -393,9 +663,20 @@ impl App {
             let context = spotify.current_playback(None);
             if let Ok(ctx) = context {
                 if let Some(c) = ctx {
+                    let previous_device_id = self
+                        .current_playback_context
+                        .as_ref()
+                        .map(|context| context.device.id.to_owned());
                     self.current_playback_context = Some(c.clone());
                     self.instant_since_last_current_playback_poll = Instant::now();
 
+                    // Playback moved to another device
+                    if let Some(previous_device_id) = previous_device_id {
+                        if previous_device_id != c.device.id {
+                            self.apply_device_volume();
+                        }
+                    }
+
                     if let Some(track) = c.item {
                         if let Some(track_id) = track.id {
                             self.current_user_saved_tracks_contains(vec![track_id]);
@@ -466,6 +747,79 @@ impl App {
                 }
             }
         }
+
+        self.update_sleep_timer();
+        self.update_ab_loop();
+
+        // Leave the end of the track to the sleep timer if it's about to pause
+        let is_sleep_timer_ending = match self.get_sleep_timer_remaining() {
+            Some(remaining) => remaining.as_millis() <= ADVANCE_MARGIN_MS,
+            None => false,
+        };
+        if let Some(current_playback_context) = &self.current_playback_context {
+            if let Some(track) = &current_playback_context.item {
+                let remaining_ms =
+                    u128::from(track.duration_ms).saturating_sub(self.song_progress_ms);
+                if self
+                    .queue
+                    .should_advance(current_playback_context.is_playing, remaining_ms)
+                    && !is_sleep_timer_ending
+                {
+                    self.play_next_in_queue();
+                    return;
+                }
+            }
+        }
+
+        if !is_sleep_timer_ending {
+            self.update_autoplay();
+        }
+    }
+
+    /// Fetches the audio features of the tracks in a table that doesn't have them yet, when its
+    /// columns show the BPM or key. `columns` picks the table's columns from the config.
+    pub fn get_missing_audio_features(
+        &mut self,
+        columns: fn(&TrackTablesConfig) -> &Vec<TrackColumnConfig>,
+        ids: Vec<String>,
+    ) {
+        let needs_audio_features = columns(&self.user_config.track_tables)
+            .iter()
+            .any(|config| config.column == TrackColumn::Bpm || config.column == TrackColumn::Key);
+        if !needs_audio_features {
+            return;
+        }
+        let ids: Vec<String> = ids
+            .into_iter()
+            .filter(|id| !self.track_audio_features.contains_key(id))
+            .collect();
+
+        // The endpoint takes at most 100 ids
+        for ids in ids.chunks(100) {
+            let result = match &self.spotify {
+                Some(spotify) => spotify.audios_features(ids),
+                None => return,
+            };
+
+            // Don't ask again for tracks that have no features or failed
+            for id in ids {
+                self.track_audio_features.insert(id.to_owned(), None);
+            }
+
+            match result {
+                Ok(Some(payload)) => {
+                    for audio_features in payload.audio_features {
+                        self.track_audio_features
+                            .insert(audio_features.id.clone(), Some(audio_features));
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    self.handle_error(e);
+                    return;
+                }
+            }
+        }
     }
 
     fn seek(&mut self, position_ms: u32) {
@@ -475,12 +829,125 @@ impl App {
                     self.get_current_playback();
                 }
                 Err(e) => {
-                    self.handle_error(e);
+                    self.handle_playback_error(e);
+                }
+            };
+        }
+    }
+
+    // Unlike `seek` this doesn't fetch the playback straight after, as it can still report
+    // the position from before seeking, which would be past B again
+    fn seek_to_loop_start(&mut self, position_ms: u32) {
+        if let (Some(spotify), Some(device_id)) = (&self.spotify, &self.client_config.device_id) {
+            match spotify.seek_track(position_ms, Some(device_id.to_string())) {
+                Ok(()) => {
+                    if let Some(context) = &mut self.current_playback_context {
+                        context.progress_ms = Some(position_ms);
+                    }
+                    self.instant_since_last_current_playback_poll = Instant::now();
+                    self.song_progress_ms = position_ms.into();
+                }
+                Err(e) => {
+                    self.handle_playback_error(e);
                 }
             };
         }
     }
 
+    fn update_ab_loop(&mut self) {
+        let track_uri = match &self.current_playback_context {
+            Some(context) if context.is_playing => match &context.item {
+                Some(track) => track.uri.to_owned(),
+                None => return,
+            },
+            _ => return,
+        };
+
+        if let Some(position_ms) = self.ab_loop.next_seek(&track_uri, self.song_progress_ms) {
+            self.seek_to_loop_start(position_ms);
+        }
+    }
+
+    /// Marks the current position as A, then as B, then clears the loop
+    pub fn mark_ab_loop(&mut self) {
+        let track_uri = match &self.current_playback_context {
+            Some(context) => match &context.item {
+                Some(track) => track.uri.to_owned(),
+                None => return,
+            },
+            None => return,
+        };
+
+        if self.ab_loop.snap != Snap::Off {
+            self.load_audio_analysis(&track_uri);
+        }
+        let analysis = if self.audio_analysis_uri.as_ref() == Some(&track_uri) {
+            self.audio_analysis.as_ref()
+        } else {
+            None
+        };
+
+        self.ab_loop
+            .mark(&track_uri, self.song_progress_ms as u32, analysis);
+    }
+
+    /// Seeks to `percent` of the playing track
+    pub fn seek_to_percentage(&mut self, percent: u32) {
+        if let Some(track) = self.get_playing_track() {
+            self.seek(percentage_position(track.duration_ms, percent));
+        }
+    }
+
+    /// Seeks to what was typed into the seek prompt, see `parse_seek_position`
+    pub fn seek_to_input(&mut self, input: &str) {
+        if let Some(track) = self.get_playing_track() {
+            match parse_seek_position(input, self.song_progress_ms as u32, track.duration_ms) {
+                Ok(position_ms) => self.seek(position_ms),
+                Err(e) => self.handle_error(e),
+            }
+        }
+    }
+
+    pub fn seek_to_next_section(&mut self) {
+        let section_starts = self.get_section_starts();
+        if let Some(position_ms) = next_section_start(&section_starts, self.song_progress_ms as u32)
+        {
+            self.seek(position_ms);
+        }
+    }
+
+    pub fn seek_to_previous_section(&mut self) {
+        let section_starts = self.get_section_starts();
+        if !section_starts.is_empty() {
+            self.seek(previous_section_start(
+                &section_starts,
+                self.song_progress_ms as u32,
+            ));
+        }
+    }
+
+    fn get_playing_track(&self) -> Option<&FullTrack> {
+        self.current_playback_context.as_ref()?.item.as_ref()
+    }
+
+    // Where the sections of the playing track start, in milliseconds
+    fn get_section_starts(&mut self) -> Vec<u32> {
+        let track_uri = match self.get_playing_track() {
+            Some(track) => track.uri.to_owned(),
+            None => return vec![],
+        };
+
+        self.load_audio_analysis(&track_uri);
+        match &self.audio_analysis {
+            Some(analysis) if self.audio_analysis_uri.as_ref() == Some(&track_uri) => analysis
+                .sections
+                .iter()
+                .map(|section| (section.start * 1000.0) as u32)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
     pub fn seek_forwards(&mut self) {
         if let Some(current_playback_context) = &self.current_playback_context {
             if let Some(track) = &current_playback_context.item {
@@ -514,7 +981,7 @@ impl App {
                     self.get_current_playback();
                 }
                 Err(e) => {
-                    self.handle_error(e);
+                    self.handle_playback_error(e);
                 }
             };
         }
@@ -526,19 +993,55 @@ impl App {
         seed_tracks: Option<Vec<String>>,
         first_track: Option<&FullTrack>,
     ) {
-        if let (Some(spotify), Some(user)) = (&self.spotify, &self.user.to_owned()) {
-            let user_country =
-                Country::from_str(&user.country.to_owned().unwrap_or_else(|| "".to_string())).ok();
-            let empty_payload: Map<String, Value> = Map::new();
-
-            match spotify.recommendations(
-                seed_artists,            // artists
-                None,                    // genres
-                seed_tracks,             // tracks
-                self.large_search_limit, // adjust playlist to screen size
-                user_country,            // country
-                &empty_payload,          // payload
-            ) {
+        // The builder starts from the seed of the last radio, so it can be tuned from there
+        let seed_name = self.recommendations_seed.to_owned();
+        let seeds = seed_artists
+            .iter()
+            .flatten()
+            .map(|id| (SeedKind::Artist, id))
+            .chain(seed_tracks.iter().flatten().map(|id| (SeedKind::Track, id)))
+            .map(|(kind, id)| Seed {
+                kind,
+                id: id.to_owned(),
+                name: seed_name.to_owned(),
+            })
+            .collect();
+        self.recommendations_builder.set_seeds(seeds);
+
+        if self.get_recommendations(seed_artists, None, seed_tracks, &Map::new(), first_track) {
+            self.start_recommendations_playback(Some(0));
+        }
+    }
+
+    /// Shows the recommendations for the seeds and tuning of the recommendations builder
+    /// without playing them
+    pub fn get_builder_recommendations(&mut self) {
+        let builder = &self.recommendations_builder;
+        if builder.seeds.is_empty() {
+            self.notify("Add an artist, track or genre seed first".to_string());
+            return;
+        }
+
+        let seed_artists = builder.get_seed_ids(SeedKind::Artist);
+        let seed_genres = builder.get_seed_ids(SeedKind::Genre);
+        let seed_tracks = builder.get_seed_ids(SeedKind::Track);
+        let payload = builder.get_payload();
+        self.recommendations_seed = builder.get_seed_names();
+        self.recommendations_context = Some(RecommendationsContext::Builder);
+        self.get_recommendations(seed_artists, seed_genres, seed_tracks, &payload, None);
+    }
+
+    // Shows the recommendations in the recommendations table. Returns whether there were any.
+    fn get_recommendations(
+        &mut self,
+        seed_artists: Option<Vec<String>>,
+        seed_genres: Option<Vec<String>>,
+        seed_tracks: Option<Vec<String>>,
+        payload: &Map<String, Value>,
+        first_track: Option<&FullTrack>,
+    ) -> bool {
+        if self.spotify.is_some() && self.user.is_some() {
+            match self.request_recommendations(seed_artists, seed_genres, seed_tracks, payload) {
                 Ok(result) => {
                     if let Some(mut recommended_tracks) = self.extract_recommended_tracks(&result) {
                         //custom first track
@@ -548,6 +1051,7 @@ impl App {
                         self.recommended_tracks = recommended_tracks.clone();
                         self.set_tracks_to_table(recommended_tracks);
                         self.track_table.context = Some(TrackTableContext::RecommendedTracks);
+                        self.track_table.selected_index = 0;
 
                         if self.get_current_route().id != RouteId::Recommendations {
                             self.push_navigation_stack(
@@ -556,13 +1060,110 @@ impl App {
                             );
                         };
                     }
-                    self.start_recommendations_playback(Some(0));
+                    return true;
                 }
                 Err(e) => {
                     self.handle_error(e);
                 }
             }
         }
+        false
+    }
+
+    fn request_recommendations(
+        &self,
+        seed_artists: Option<Vec<String>>,
+        seed_genres: Option<Vec<String>>,
+        seed_tracks: Option<Vec<String>>,
+        payload: &Map<String, Value>,
+    ) -> Result<Recommendations, failure::Error> {
+        let (spotify, user) = match (&self.spotify, &self.user) {
+            (Some(spotify), Some(user)) => (spotify, user),
+            _ => return Err(err_msg("Not logged in to Spotify")),
+        };
+        let user_country =
+            Country::from_str(&user.country.to_owned().unwrap_or_else(|| "".to_string())).ok();
+
+        spotify.recommendations(
+            seed_artists,            // artists
+            seed_genres,             // genres
+            seed_tracks,             // tracks
+            self.large_search_limit, // adjust playlist to screen size
+            user_country,            // country
+            payload,                 // payload
+        )
+    }
+
+    /// Adds the artist, track or genre typed into the seed prompt to the recommendations
+    /// builder
+    pub fn add_recommendations_seed(&mut self, input: &str) {
+        let result = self
+            .find_recommendations_seed(input)
+            .and_then(|seed| self.recommendations_builder.add_seed(seed));
+        if let Err(e) = result {
+            self.handle_error(e);
+        }
+    }
+
+    fn find_recommendations_seed(&mut self, input: &str) -> Result<Seed, failure::Error> {
+        let (kind, query) = parse_seed_query(input)?;
+        let (spotify, user) = match (&self.spotify, &self.user) {
+            (Some(spotify), Some(user)) => (spotify, user),
+            _ => return Err(err_msg("Not logged in to Spotify")),
+        };
+        let country = Country::from_str(&user.country.to_owned().unwrap_or_default()).ok();
+
+        match kind {
+            SeedKind::Artist => {
+                let artist = spotify
+                    .search_artist(&query, 1, 0, country)?
+                    .artists
+                    .items
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| format_err!("No artist found for \"{}\"", query))?;
+                Ok(Seed {
+                    kind,
+                    id: artist.id,
+                    name: artist.name,
+                })
+            }
+            SeedKind::Track => {
+                let track = spotify
+                    .search_track(&query, 1, 0, country)?
+                    .tracks
+                    .items
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| format_err!("No track found for \"{}\"", query))?;
+                match track.id {
+                    Some(id) => Ok(Seed {
+                        kind,
+                        id,
+                        name: track.name,
+                    }),
+                    None => Err(format_err!("\"{}\" can't be used as a seed", track.name)),
+                }
+            }
+            SeedKind::Genre => {
+                if self.recommendation_genres.is_empty() {
+                    self.recommendation_genres = web_api::get_recommendation_genres(spotify)?;
+                }
+                let genre = normalize_genre(&query);
+                if self.recommendation_genres.contains(&genre) {
+                    Ok(Seed {
+                        kind,
+                        id: genre.to_owned(),
+                        name: genre,
+                    })
+                } else {
+                    Err(format_err!(
+                        "Spotify has no recommendations for the genre \"{}\"",
+                        query
+                    ))
+                }
+            }
+        }
     }
 
     pub fn get_recommendations_for_trackid(&mut self, id: &str) {
@@ -573,7 +1174,8 @@ impl App {
         }
     }
 
-    fn change_volume(&mut self, volume_percent: u8) {
+    // Returns whether the volume was changed
+    fn change_volume(&mut self, volume_percent: u8) -> bool {
         if let (Some(spotify), Some(device_id), Some(context)) = (
             &self.spotify,
             &self.client_config.device_id,
@@ -582,12 +1184,65 @@ impl App {
             match spotify.volume(volume_percent, Some(device_id.to_string())) {
                 Ok(()) => {
                     context.device.volume_percent = volume_percent.into();
+                    return true;
                 }
                 Err(e) => {
-                    self.handle_error(e);
+                    self.handle_playback_error(e);
                 }
             };
         }
+        false
+    }
+
+    /// Sets the volume and remembers it for the device
+    pub fn set_volume(&mut self, volume_percent: u8) {
+        if !self.change_volume(volume_percent) {
+            return;
+        }
+        self.muted_volume = None;
+
+        if let Some(device_id) = self.client_config.device_id.clone() {
+            if let Err(e) = self
+                .client_config
+                .set_device_volume(&device_id, volume_percent)
+            {
+                self.handle_error(e);
+            }
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        match self.muted_volume {
+            Some(volume_percent) => self.set_volume(volume_percent),
+            None => {
+                if let Some(context) = &self.current_playback_context {
+                    let volume_percent = context.device.volume_percent as u8;
+                    // Unmuting goes back to the volume from before muting, so it isn't
+                    // remembered for the device
+                    if volume_percent > 0 && self.change_volume(0) {
+                        self.muted_volume = Some(volume_percent);
+                    }
+                }
+            }
+        }
+    }
+
+    // Goes back to the volume last used on the device playback moved to
+    fn apply_device_volume(&mut self) {
+        let device = match &self.current_playback_context {
+            Some(context) => &context.device,
+            None => return,
+        };
+        if self.client_config.device_id.as_ref() != Some(&device.id) {
+            return;
+        }
+
+        if let Some(volume_percent) = self.client_config.device_volumes.get(&device.id).copied() {
+            self.muted_volume = None;
+            if u32::from(volume_percent) != device.volume_percent {
+                self.change_volume(volume_percent);
+            }
+        }
     }
 
     pub fn increase_volume(&mut self) {
@@ -599,7 +1254,7 @@ impl App {
             );
 
             if next_volume != current_volume {
-                self.change_volume(next_volume);
+                self.set_volume(next_volume);
             }
         }
     }
@@ -613,7 +1268,7 @@ impl App {
             );
 
             if next_volume != current_volume {
-                self.change_volume(next_volume as u8);
+                self.set_volume(next_volume as u8);
             }
         }
     }
@@ -623,6 +1278,31 @@ impl App {
         self.api_error = e.to_string();
     }
 
+    // Commands for a device that has gone away fail with "not found", so those fall back to
+    // another device rather than showing the error
+    fn handle_playback_error(&mut self, e: failure::Error) {
+        if web_api::has_status(&e, 404) && self.handle_missing_device() {
+            return;
+        }
+        self.handle_error(e);
+    }
+
+    /// Shows a message over the app for a few seconds
+    pub fn notify(&mut self, message: String) {
+        self.notification = Some(Notification {
+            message,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// The notification to show, if there is one that hasn't expired yet
+    pub fn get_notification(&self) -> Option<&str> {
+        self.notification
+            .as_ref()
+            .filter(|notification| notification.shown_at.elapsed() < NOTIFICATION_DURATION)
+            .map(|notification| notification.message.as_str())
+    }
+
     pub fn toggle_playback(&mut self) {
         if let Some(current_playback_context) = &self.current_playback_context {
             if current_playback_context.is_playing {
@@ -635,13 +1315,17 @@ impl App {
     }
 
     pub fn next_track(&mut self) {
+        if self.queue.has_next() {
+            self.play_next_in_queue();
+            return;
+        }
         if let (Some(spotify), Some(device_id)) = (&self.spotify, &self.client_config.device_id) {
             match spotify.next_track(Some(device_id.to_string())) {
                 Ok(()) => {
                     self.get_current_playback();
                 }
                 Err(e) => {
-                    self.handle_error(e);
+                    self.handle_playback_error(e);
                 }
             };
         }
@@ -657,7 +1341,7 @@ impl App {
                         self.get_current_playback();
                     }
                     Err(e) => {
-                        self.handle_error(e);
+                        self.handle_playback_error(e);
                     }
                 };
             }
@@ -677,12 +1361,82 @@ impl App {
         );
     }
 
+    /// Plays the track table from `index`, within the album or playlist the tracks are from
+    /// when there is one
+    pub fn start_track_table_playback(&mut self, index: usize) {
+        match self.track_table.source.clone() {
+            Some(TrackSource::Context { uri, offset }) => {
+                self.start_playback(Some(uri), None, Some(offset as usize + index));
+            }
+            Some(TrackSource::SavedTracks { offset }) => {
+                self.start_saved_tracks_playback(offset + index as u32);
+            }
+            None => {
+                let uris = self
+                    .track_table
+                    .tracks
+                    .iter()
+                    .map(|track| track.uri.to_owned())
+                    .collect();
+                self.start_playback(None, Some(uris), Some(index));
+            }
+        }
+    }
+
+    /// Plays an album or playlist from the track with `track_uri`
+    pub fn start_context_playback_at_track(&mut self, context_uri: String, track_uri: String) {
+        self.start_playback_at(Some(context_uri), None, for_uri(track_uri), None);
+    }
+
+    // Only the liked songs on screen have been fetched, so page in the ones after them or
+    // playback would stop at the end of the page
+    fn start_saved_tracks_playback(&mut self, index: u32) {
+        match self.get_saved_track_uris(index) {
+            Ok(uris) if !uris.is_empty() => self.start_playback(None, Some(uris), Some(0)),
+            Ok(_) => {}
+            Err(e) => self.handle_error(e),
+        }
+    }
+
+    fn get_saved_track_uris(&self, offset: u32) -> Result<Vec<String>, failure::Error> {
+        let mut uris = vec![];
+        if let Some(spotify) = &self.spotify {
+            let mut offset = offset;
+            while uris.len() < MAX_SAVED_TRACK_URIS {
+                let page = spotify.current_user_saved_tracks(SAVED_TRACKS_PAGE_LIMIT, offset)?;
+                uris.extend(page.items.into_iter().map(|item| item.track.uri));
+                if page.next.is_none() {
+                    break;
+                }
+                offset += SAVED_TRACKS_PAGE_LIMIT;
+            }
+        }
+        uris.truncate(MAX_SAVED_TRACK_URIS);
+        Ok(uris)
+    }
+
     pub fn start_playback(
         &mut self,
         context_uri: Option<String>,
         uris: Option<Vec<String>>,
         offset: Option<usize>,
     ) {
+        self.start_playback_at(
+            context_uri,
+            uris,
+            offset.and_then(|o| for_position(o as u32)),
+            None,
+        );
+    }
+
+    fn start_playback_at(
+        &mut self,
+        context_uri: Option<String>,
+        uris: Option<Vec<String>>,
+        offset: Option<Offset>,
+        position_ms: Option<u32>,
+    ) {
+        let is_new_playback = context_uri.is_some() || uris.is_some();
         let (uris, context_uri) = if context_uri.is_some() {
             (None, context_uri)
         } else if uris.is_some() {
@@ -691,8 +1445,6 @@ impl App {
             (None, None)
         };
 
-        let offset = offset.and_then(|o| for_position(o as u32));
-
         let result = match &self.client_config.device_id {
             Some(device_id) => match &self.spotify {
                 Some(spotify) => spotify.start_playback(
@@ -700,7 +1452,7 @@ impl App {
                     context_uri.clone(),
                     uris.clone(),
                     offset.clone(),
-                    None,
+                    position_ms,
                 ),
                 None => Err(err_msg("Spotify is not ready to be used".to_string())),
             },
@@ -711,6 +1463,9 @@ impl App {
             Ok(()) => {
                 self.get_current_playback();
                 self.song_progress_ms = 0;
+                if is_new_playback {
+                    self.queue.is_playing_queued = false;
+                }
                 self.playback_params = PlaybackParams {
                     context_uri,
                     uris,
@@ -718,23 +1473,336 @@ impl App {
                 }
             }
             Err(e) => {
-                self.handle_error(e);
+                self.handle_playback_error(e);
             }
         }
     }
 
-    pub fn get_playlist_tracks(&mut self, playlist_id: String) {
-        match &self.spotify {
-            Some(spotify) => {
-                if let Ok(playlist_tracks) = spotify.user_playlist_tracks(
-                    "spotify",
-                    &playlist_id,
-                    None,
+    /// Plays the next queued track, or continues the context the queue interrupted once
+    /// the queue has been played
+    pub fn play_next_in_queue(&mut self) {
+        if !self.queue.is_playing_queued {
+            self.queue.resume = self.get_resume_point();
+        }
+
+        match self.queue.pop_next() {
+            Some(track) => {
+                self.start_playback(None, Some(vec![track.uri]), None);
+                self.queue.is_playing_queued = true;
+            }
+            None => {
+                if let Some(resume) = self.queue.resume.take() {
+                    let offset = match resume.position {
+                        Some(position) => for_position(position as u32),
+                        None => for_uri(resume.track_uri),
+                    };
+                    // Starting past the end of the track makes Spotify play the one after it
+                    self.start_playback_at(
+                        resume.context_uri,
+                        resume.uris,
+                        offset,
+                        Some(resume.track_duration_ms + 1),
+                    );
+                }
+            }
+        }
+    }
+
+    fn get_resume_point(&self) -> Option<ResumePoint> {
+        let current_playback_context = self.current_playback_context.as_ref()?;
+        let track = current_playback_context.item.as_ref()?;
+
+        let (context_uri, uris, position) = match &current_playback_context.context {
+            Some(context) => (Some(context.uri.to_owned()), None, None),
+            // Lists of tracks, like recommendations, have no context uri
+            None => {
+                let uris = self.playback_params.uris.as_ref()?;
+                let position = uris.iter().position(|uri| *uri == track.uri)?;
+                (None, Some(uris.to_owned()), Some(position))
+            }
+        };
+
+        Some(ResumePoint {
+            context_uri,
+            uris,
+            position,
+            track_uri: track.uri.to_owned(),
+            track_duration_ms: track.duration_ms,
+        })
+    }
+
+    pub fn toggle_autoplay(&mut self) {
+        self.autoplay.is_enabled = !self.autoplay.is_enabled;
+        // Look at the track that is playing again, it may be the last one
+        self.autoplay.checked_track_uri = None;
+        self.autoplay.next_uris = None;
+        self.notify(format!(
+            "Autoplay {}",
+            if self.autoplay.is_enabled {
+                "on"
+            } else {
+                "off"
+            }
+        ));
+    }
+
+    /// Whether the tracks autoplay picked are playing
+    pub fn is_playing_autoplay(&self) -> bool {
+        let has_context = match &self.current_playback_context {
+            Some(context) => context.context.is_some(),
+            None => true,
+        };
+        !has_context
+            && !self.queue.is_playing_queued
+            && self.autoplay.radio_uris.is_some()
+            && self.playback_params.uris == self.autoplay.radio_uris
+    }
+
+    // Picks recommendations once the last track of the context starts, and plays them when it
+    // is about to end
+    fn update_autoplay(&mut self) {
+        if !self.autoplay.is_enabled {
+            return;
+        }
+        let (track_uri, remaining_ms, is_playing, repeat_state) =
+            match &self.current_playback_context {
+                Some(context) => match &context.item {
+                    Some(track) => (
+                        track.uri.to_owned(),
+                        u128::from(track.duration_ms).saturating_sub(self.song_progress_ms),
+                        context.is_playing,
+                        context.repeat_state,
+                    ),
+                    None => return,
+                },
+                None => return,
+            };
+        self.autoplay.record_played(&track_uri);
+
+        if self.autoplay.checked_track_uri.as_ref() != Some(&track_uri) {
+            self.autoplay.checked_track_uri = Some(track_uri.to_owned());
+            self.autoplay.next_uris = None;
+            // With repeat on the context doesn't end
+            if repeat_state == RepeatState::Off
+                && self.get_context_end(track_uri.to_owned()).last_track_uri == Some(track_uri)
+            {
+                self.autoplay.next_uris = self.get_autoplay_uris();
+            }
+        }
+
+        if is_playing && remaining_ms <= ADVANCE_MARGIN_MS && !self.queue.has_next() {
+            if let Some(uris) = self.autoplay.next_uris.take() {
+                self.start_playback(None, Some(uris.to_owned()), Some(0));
+                self.autoplay.radio_uris = Some(uris);
+            }
+        }
+    }
+
+    // Recommendations seeded from the last tracks that played, leaving out the ones that
+    // played lately
+    fn get_autoplay_uris(&mut self) -> Option<Vec<String>> {
+        let seed_tracks = self.autoplay.get_seed_track_ids();
+        if seed_tracks.is_empty() {
+            return None;
+        }
+
+        match self.request_recommendations(None, None, Some(seed_tracks), &Map::new()) {
+            Ok(recommendations) => {
+                let uris = recommendations
+                    .tracks
+                    .into_iter()
+                    .map(|track| track.uri)
+                    .collect();
+                let uris = self.autoplay.remove_played(uris);
+                if uris.is_empty() {
+                    None
+                } else {
+                    Some(uris)
+                }
+            }
+            Err(e) => {
+                // Not worth interrupting what is playing for
+                self.notify(format!("Autoplay couldn't get recommendations: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Starts the sleep timer, or turns it off with `None`
+    pub fn set_sleep_timer(&mut self, setting: Option<SleepTimerSetting>) {
+        // Undo any fading that already happened
+        if let Some(fade) = self.sleep_timer.take().and_then(|timer| timer.fade_from) {
+            self.change_volume(fade.volume);
+        }
+        self.sleep_timer = setting.map(SleepTimer::new);
+    }
+
+    pub fn cycle_sleep_timer(&mut self) {
+        let current = self.sleep_timer.as_ref().map(|timer| timer.setting);
+        self.set_sleep_timer(SleepTimerSetting::next_preset(current));
+    }
+
+    /// How long until the sleep timer pauses playback, `None` when that isn't known yet
+    pub fn get_sleep_timer_remaining(&self) -> Option<Duration> {
+        let timer = self.sleep_timer.as_ref()?;
+        let current_playback_context = self.current_playback_context.as_ref();
+        let track = current_playback_context.and_then(|context| context.item.as_ref());
+        let track_remaining = track.map(|track| {
+            let remaining_ms = u128::from(track.duration_ms).saturating_sub(self.song_progress_ms);
+            Duration::from_millis(remaining_ms as u64)
+        });
+
+        match &timer.end {
+            SleepTimerEnd::At(at) => Some(at.saturating_duration_since(Instant::now())),
+            SleepTimerEnd::Track(Some(uri)) => match track {
+                Some(track) if track.uri == *uri => track_remaining,
+                // Something else started playing
+                _ => Some(Duration::from_secs(0)),
+            },
+            SleepTimerEnd::Context(Some(end)) => {
+                let context_uri = current_playback_context
+                    .and_then(|context| context.context.as_ref())
+                    .map(|context| &context.uri);
+                match track {
+                    Some(track) if end.last_track_uri.as_ref() == Some(&track.uri) => {
+                        track_remaining
+                    }
+                    Some(_) if context_uri == end.context_uri.as_ref() => None,
+                    _ => Some(Duration::from_secs(0)),
+                }
+            }
+            SleepTimerEnd::Track(None) | SleepTimerEnd::Context(None) => None,
+        }
+    }
+
+    fn update_sleep_timer(&mut self) {
+        if self.sleep_timer.is_none() {
+            return;
+        }
+        self.resolve_sleep_timer_end();
+
+        let remaining = match self.get_sleep_timer_remaining() {
+            Some(remaining) => remaining,
+            None => return,
+        };
+
+        if remaining.as_millis() == 0 {
+            let fade_from = self.sleep_timer.take().and_then(|timer| timer.fade_from);
+            if self.is_playing() {
+                self.pause_playback();
+            }
+            // Leave the volume as it was for the next time something plays
+            if let Some(fade) = fade_from {
+                self.change_volume(fade.volume);
+            }
+            return;
+        }
+
+        if !self.is_playing() {
+            return;
+        }
+        if let (Some(timer), Some(context)) =
+            (&mut self.sleep_timer, &self.current_playback_context)
+        {
+            let volume = context.device.volume_percent as u8;
+            if let Some(next_volume) = timer.next_fade_volume(volume, remaining) {
+                self.change_volume(next_volume);
+            }
+        }
+    }
+
+    fn is_playing(&self) -> bool {
+        match &self.current_playback_context {
+            Some(context) => context.is_playing,
+            None => false,
+        }
+    }
+
+    // The end of the track and context timers depends on what is playing when they start
+    fn resolve_sleep_timer_end(&mut self) {
+        let track_uri = match &self.current_playback_context {
+            Some(context) => match &context.item {
+                Some(track) => track.uri.to_owned(),
+                None => return,
+            },
+            None => return,
+        };
+
+        let end = match self.sleep_timer.as_ref().map(|timer| &timer.end) {
+            Some(SleepTimerEnd::Track(None)) => SleepTimerEnd::Track(Some(track_uri)),
+            Some(SleepTimerEnd::Context(None)) => {
+                SleepTimerEnd::Context(Some(self.get_context_end(track_uri)))
+            }
+            _ => return,
+        };
+
+        if let Some(timer) = &mut self.sleep_timer {
+            timer.end = end;
+        }
+    }
+
+    fn get_context_end(&self, track_uri: String) -> ContextEnd {
+        let context_uri = self
+            .current_playback_context
+            .as_ref()
+            .and_then(|context| context.context.as_ref())
+            .map(|context| context.uri.to_owned());
+
+        let last_track_uri = match &context_uri {
+            Some(context_uri) => self.get_last_track_uri(context_uri),
+            // Lists of tracks, like recommendations, have no context uri
+            None => match &self.playback_params.uris {
+                Some(uris) if uris.contains(&track_uri) => uris.last().cloned(),
+                _ => Some(track_uri),
+            },
+        };
+
+        ContextEnd {
+            context_uri,
+            last_track_uri,
+        }
+    }
+
+    // NOTE: this is the last track in the order of the album or playlist, shuffle isn't
+    // taken into account
+    fn get_last_track_uri(&self, context_uri: &str) -> Option<String> {
+        let spotify = self.spotify.as_ref()?;
+        let id = context_uri.rsplit(':').next()?;
+
+        if context_uri.contains(":album:") {
+            let total = spotify.album_track(id, 1, 0).ok()?.total;
+            let last = spotify.album_track(id, 1, total.checked_sub(1)?).ok()?;
+            last.items.first().map(|track| track.uri.to_owned())
+        } else if context_uri.contains(":playlist:") {
+            let total = spotify
+                .user_playlist_tracks("spotify", id, None, 1, 0, None)
+                .ok()?
+                .total;
+            let last = spotify
+                .user_playlist_tracks("spotify", id, None, 1, total.checked_sub(1)?, None)
+                .ok()?;
+            last.items.first().map(|item| item.track.uri.to_owned())
+        } else {
+            None
+        }
+    }
+
+    pub fn get_playlist_tracks(&mut self, playlist_id: String) {
+        match &self.spotify {
+            Some(spotify) => {
+                if let Ok(playlist_tracks) = spotify.user_playlist_tracks(
+                    "spotify",
+                    &playlist_id,
+                    None,
                     Some(self.large_search_limit),
                     Some(self.playlist_offset),
                     None,
                 ) {
                     self.set_playlist_tracks_to_table(&playlist_tracks);
+                    self.track_table.source = Some(TrackSource::Context {
+                        uri: format!("spotify:playlist:{}", playlist_id),
+                        offset: playlist_tracks.offset,
+                    });
 
                     self.playlist_tracks = Some(playlist_tracks);
                     if self.get_current_route().id != RouteId::TrackTable {
@@ -757,6 +1825,10 @@ impl App {
                 None,
             ) {
                 self.set_playlist_tracks_to_table(&made_for_you_tracks);
+                self.track_table.source = Some(TrackSource::Context {
+                    uri: format!("spotify:playlist:{}", playlist_id),
+                    offset: made_for_you_tracks.offset,
+                });
 
                 self.made_for_you_tracks = Some(made_for_you_tracks);
                 if self.get_current_route().id != RouteId::TrackTable {
@@ -813,6 +1885,60 @@ impl App {
         }
     }
 
+    // Whether the block is drawn with the current layout config and sidebar toggle
+    pub fn is_block_visible(&self, block: ActiveBlock) -> bool {
+        let layout = &self.user_config.layout;
+        match block {
+            ActiveBlock::Library => self.show_sidebar && layout.show_library,
+            ActiveBlock::MyPlaylists => self.show_sidebar && layout.show_playlists,
+            // A hidden search input is still shown while typing into it
+            ActiveBlock::Input => {
+                layout.show_search || self.get_current_route().active_block == ActiveBlock::Input
+            }
+            _ => true,
+        }
+    }
+
+    /// Asks for input in the search input, which goes back to the block it was opened from
+    /// once answered
+    pub fn open_input_prompt(&mut self, prompt: InputPrompt) {
+        let current_route = self.get_current_route();
+        if current_route.active_block != ActiveBlock::Input {
+            self.input_prompt_return =
+                Some((current_route.active_block, current_route.hovered_block));
+        }
+        self.input_prompt = prompt;
+        self.clear_input();
+        self.set_current_route_state(Some(ActiveBlock::Input), Some(ActiveBlock::Input));
+    }
+
+    /// Like `open_input_prompt`, with `text` already typed in
+    pub fn open_input_prompt_with(&mut self, prompt: InputPrompt, text: &str) {
+        self.open_input_prompt(prompt);
+        self.input = text.chars().collect();
+        self.input_idx = self.input.len();
+        self.input_cursor_position = UnicodeWidthStr::width(text) as u16;
+    }
+
+    pub fn close_input_prompt(&mut self) {
+        self.input_prompt = InputPrompt::Search;
+        self.clear_input();
+        if let Some((active_block, hovered_block)) = self.input_prompt_return.take() {
+            self.set_current_route_state(Some(active_block), Some(hovered_block));
+        }
+    }
+
+    fn clear_input(&mut self) {
+        self.input = vec![];
+        self.input_idx = 0;
+        self.input_cursor_position = 0;
+    }
+
+    pub fn is_sidebar_visible(&self) -> bool {
+        self.is_block_visible(ActiveBlock::Library)
+            || self.is_block_visible(ActiveBlock::MyPlaylists)
+    }
+
     pub fn copy_song_url(&mut self) {
         let clipboard = match &mut self.clipboard_context {
             Some(ctx) => ctx,
@@ -862,6 +1988,17 @@ impl App {
                 .map(|item| item.track)
                 .collect::<Vec<FullTrack>>(),
         );
+        self.track_table.added = saved_track_page
+            .items
+            .iter()
+            .map(|item| TrackAdded {
+                at: item.added_at.format("%Y-%m-%d").to_string(),
+                by: None,
+            })
+            .collect();
+        self.track_table.source = Some(TrackSource::SavedTracks {
+            offset: saved_track_page.offset,
+        });
     }
 
     fn set_playlist_tracks_to_table(&mut self, playlist_track_page: &Page<PlaylistTrack>) {
@@ -873,6 +2010,18 @@ impl App {
                 .map(|item| item.track)
                 .collect::<Vec<FullTrack>>(),
         );
+        self.track_table.added = playlist_track_page
+            .items
+            .iter()
+            .map(|item| TrackAdded {
+                at: item.added_at.format("%Y-%m-%d").to_string(),
+                by: item.added_by.as_ref().map(|user| {
+                    user.display_name
+                        .clone()
+                        .unwrap_or_else(|| user.id.to_owned())
+                }),
+            })
+            .collect();
     }
 
     fn extract_recommended_tracks(
@@ -905,13 +2054,15 @@ impl App {
 
     pub fn set_tracks_to_table(&mut self, tracks: Vec<FullTrack>) {
         self.track_table.tracks = tracks.clone();
-
-        self.current_user_saved_tracks_contains(
-            tracks
-                .into_iter()
-                .filter_map(|item| item.id)
-                .collect::<Vec<String>>(),
-        );
+        self.track_table.added = vec![];
+        self.track_table.source = None;
+
+        let ids = tracks
+            .into_iter()
+            .filter_map(|item| item.id)
+            .collect::<Vec<String>>();
+        self.get_missing_audio_features(|tables| &tables.song, ids.clone());
+        self.current_user_saved_tracks_contains(ids);
     }
 
     pub fn get_current_user_saved_tracks(&mut self, offset: Option<u32>) {
@@ -971,13 +2122,13 @@ impl App {
                         selected_index: 0,
                     });
 
-                    self.current_user_saved_tracks_contains(
-                        tracks
-                            .items
-                            .into_iter()
-                            .filter_map(|item| item.id)
-                            .collect::<Vec<String>>(),
-                    );
+                    let ids = tracks
+                        .items
+                        .into_iter()
+                        .filter_map(|item| item.id)
+                        .collect::<Vec<String>>();
+                    self.get_missing_audio_features(|tables| &tables.album, ids.clone());
+                    self.current_user_saved_tracks_contains(ids);
 
                     self.album_table_context = AlbumTableContext::Simplified;
                     self.push_navigation_stack(RouteId::AlbumTracks, ActiveBlock::AlbumTracks);
@@ -1181,6 +2332,184 @@ impl App {
         }
     }
 
+    pub fn get_current_user_saved_shows(&mut self, offset: Option<u32>) {
+        if let Some(spotify) = &self.spotify {
+            match web_api::get_saved_shows(spotify, self.large_search_limit, offset.unwrap_or(0)) {
+                Ok(saved_shows) => {
+                    // not to show a blank page
+                    if !saved_shows.items.is_empty() {
+                        self.library.saved_shows.add_pages(saved_shows);
+                    }
+                }
+                Err(e) => {
+                    self.handle_error(e);
+                }
+            }
+        }
+    }
+
+    /// Loads the saved shows again from the first page
+    pub fn refresh_saved_shows(&mut self) {
+        self.library.saved_shows = ScrollableResultPages::new();
+        self.get_current_user_saved_shows(None);
+        let shows_len = self
+            .library
+            .saved_shows
+            .get_results(None)
+            .map(|shows| shows.items.len())
+            .unwrap_or(0);
+        self.shows_list_index = min(self.shows_list_index, shows_len.saturating_sub(1));
+    }
+
+    pub fn get_current_user_saved_shows_next(&mut self) {
+        match self
+            .library
+            .saved_shows
+            .get_results(Some(self.library.saved_shows.index + 1))
+        {
+            Some(_) => self.library.saved_shows.index += 1,
+            None => {
+                if let Some(saved_shows) = &self.library.saved_shows.get_results(None) {
+                    let offset = Some(saved_shows.offset + saved_shows.limit);
+                    self.get_current_user_saved_shows(offset);
+                }
+            }
+        }
+    }
+
+    pub fn get_current_user_saved_shows_previous(&mut self) {
+        if self.library.saved_shows.index > 0 {
+            self.library.saved_shows.index -= 1;
+        }
+    }
+
+    pub fn current_user_saved_show_delete(&mut self) {
+        let show_id = match self.library.saved_shows.get_results(None) {
+            Some(shows) => match shows.items.get(self.shows_list_index) {
+                Some(saved_show) => saved_show.show.id.to_owned(),
+                None => return,
+            },
+            None => return,
+        };
+        if let Some(spotify) = &self.spotify {
+            match web_api::remove_saved_shows(spotify, &[show_id]) {
+                Ok(_) => self.refresh_saved_shows(),
+                Err(e) => self.handle_error(e),
+            }
+        }
+    }
+
+    /// Shows the episodes of `show`, the latest first
+    pub fn open_show(&mut self, show: SimplifiedShow, is_saved: bool) {
+        self.selected_show = Some(SelectedShow {
+            show,
+            is_saved,
+            episodes: ScrollableResultPages::new(),
+            selected_index: 0,
+        });
+        self.get_show_episodes(0);
+        self.push_navigation_stack(RouteId::PodcastEpisodes, ActiveBlock::EpisodeTable);
+    }
+
+    fn get_show_episodes(&mut self, offset: u32) {
+        if let (Some(spotify), Some(selected_show)) = (&self.spotify, &mut self.selected_show) {
+            match web_api::get_show_episodes(
+                spotify,
+                &selected_show.show.id,
+                self.large_search_limit,
+                offset,
+            ) {
+                Ok(episodes) => {
+                    // not to show a blank page
+                    if !episodes.items.is_empty() {
+                        selected_show.episodes.add_pages(episodes);
+                        selected_show.selected_index = 0;
+                    }
+                }
+                Err(e) => {
+                    self.handle_error(e);
+                }
+            }
+        }
+    }
+
+    pub fn get_show_episodes_next(&mut self) {
+        let next_offset = match &mut self.selected_show {
+            Some(selected_show) => {
+                let episodes = &mut selected_show.episodes;
+                if episodes.get_results(Some(episodes.index + 1)).is_some() {
+                    episodes.index += 1;
+                    selected_show.selected_index = 0;
+                    None
+                } else {
+                    episodes
+                        .get_results(None)
+                        .filter(|page| page.next.is_some())
+                        .map(|page| page.offset + page.limit)
+                }
+            }
+            None => None,
+        };
+        if let Some(offset) = next_offset {
+            self.get_show_episodes(offset);
+        }
+    }
+
+    pub fn get_show_episodes_previous(&mut self) {
+        if let Some(selected_show) = &mut self.selected_show {
+            if selected_show.episodes.index > 0 {
+                selected_show.episodes.index -= 1;
+                selected_show.selected_index = 0;
+            }
+        }
+    }
+
+    /// Saves the selected show to the library, or removes it when it is already saved
+    pub fn toggle_selected_show_saved(&mut self) {
+        let (show_id, show_name, is_saved) = match &self.selected_show {
+            Some(selected_show) => (
+                selected_show.show.id.to_owned(),
+                selected_show.show.name.to_owned(),
+                selected_show.is_saved,
+            ),
+            None => return,
+        };
+        let result = match &self.spotify {
+            Some(spotify) if is_saved => web_api::remove_saved_shows(spotify, &[show_id]),
+            Some(spotify) => web_api::save_shows(spotify, &[show_id]),
+            None => return,
+        };
+
+        match result {
+            Ok(()) => {
+                if let Some(selected_show) = &mut self.selected_show {
+                    selected_show.is_saved = !is_saved;
+                }
+                self.refresh_saved_shows();
+                self.notify(if is_saved {
+                    format!("Removed {} from your podcasts", show_name)
+                } else {
+                    format!("Saved {} to your podcasts", show_name)
+                });
+            }
+            Err(e) => self.handle_error(e),
+        }
+    }
+
+    /// Plays the episode at `index` of the selected show's page, from where it was left off
+    pub fn start_episode_playback(&mut self, index: usize) {
+        let episode = self
+            .selected_show
+            .as_ref()
+            .and_then(|selected_show| selected_show.episodes.get_results(None))
+            .and_then(|episodes| episodes.items.get(index));
+        if let Some(episode) = episode {
+            let uris = vec![episode.uri.to_owned()];
+            let position_ms = episode.get_start_position_ms();
+            self.start_playback_at(None, Some(uris), None, Some(position_ms));
+        }
+    }
+
     pub fn user_unfollow_artists(&mut self) {
         if let Some(artists) = self.library.saved_artists.get_results(None) {
             if let Some(selected_artist) = artists.items.get(self.artists_list_index) {
@@ -1229,6 +2558,299 @@ impl App {
         }
     }
 
+    /// Opens the playlist form to create a playlist with `track_uris` in it
+    pub fn open_new_playlist_form(&mut self, track_uris: Vec<String>) {
+        self.open_playlist_form(PlaylistForm {
+            track_uris,
+            ..Default::default()
+        });
+    }
+
+    /// Opens the playlist form with the details of the selected playlist. Only its owner can
+    /// change them.
+    pub fn edit_selected_playlist(&mut self) {
+        let playlist = match (&self.playlists, self.selected_playlist_index) {
+            (Some(playlists), Some(index)) => match playlists.items.get(index) {
+                Some(playlist) => playlist.clone(),
+                None => return,
+            },
+            _ => return,
+        };
+        let (spotify, user) = match (&self.spotify, &self.user) {
+            (Some(spotify), Some(user)) => (spotify, user),
+            _ => return,
+        };
+        if playlist.owner.id != user.id {
+            self.notify(format!(
+                "Only the owner of \"{}\" can edit it",
+                playlist.name
+            ));
+            return;
+        }
+
+        match web_api::get_playlist_description(spotify, &playlist.id) {
+            Ok(description) => self.open_playlist_form(PlaylistForm {
+                playlist_id: Some(playlist.id),
+                name: playlist.name,
+                description,
+                public: playlist.public.unwrap_or(false),
+                collaborative: playlist.collaborative,
+                ..Default::default()
+            }),
+            Err(e) => self.handle_error(e),
+        }
+    }
+
+    fn open_playlist_form(&mut self, form: PlaylistForm) {
+        let current_route = self.get_current_route();
+        if current_route.active_block != ActiveBlock::PlaylistForm {
+            self.playlist_form_return =
+                Some((current_route.active_block, current_route.hovered_block));
+        }
+        self.playlist_form = Some(form);
+        self.set_current_route_state(Some(ActiveBlock::PlaylistForm), None);
+    }
+
+    pub fn close_playlist_form(&mut self) {
+        self.playlist_form = None;
+        if let Some((active_block, hovered_block)) = self.playlist_form_return.take() {
+            self.set_current_route_state(Some(active_block), Some(hovered_block));
+        }
+    }
+
+    /// Creates the playlist in the playlist form, or saves the changes made to it
+    pub fn submit_playlist_form(&mut self) {
+        let form = match &self.playlist_form {
+            Some(form) => form.clone(),
+            None => return,
+        };
+        if let Err(e) = form.validate() {
+            self.notify(e.to_string());
+            return;
+        }
+        let (playlist_id, finished) = match self.save_playlist_form(&form) {
+            Ok(saved) => saved,
+            Err(e) => {
+                // The form stays open to try again
+                self.handle_error(e);
+                return;
+            }
+        };
+
+        // Submitting again would create the playlist again, so once it exists the form closes
+        // whether or not the rest works
+        self.close_playlist_form();
+        self.refresh_playlists();
+        self.select_playlist(&playlist_id);
+        self.notify(match (form.playlist_id, finished) {
+            (_, Err(e)) => format!("Created \"{}\", but {}", form.name, e),
+            (Some(_), _) => format!("Saved the changes to \"{}\"", form.name),
+            (None, _) if form.track_uris.is_empty() => format!("Created \"{}\"", form.name),
+            (None, _) => format!(
+                "Saved {} tracks to \"{}\"",
+                form.track_uris.len(),
+                form.name
+            ),
+        });
+    }
+
+    /// Creates the playlist in the form with its tracks, or saves the changes made to it.
+    /// Returns the id of the playlist and, as it exists by then, whether the tracks could be
+    /// added and the playlist made collaborative.
+    pub fn save_playlist_form(
+        &self,
+        form: &PlaylistForm,
+    ) -> Result<(String, Result<(), failure::Error>), failure::Error> {
+        let (spotify, user) = match (&self.spotify, &self.user) {
+            (Some(spotify), Some(user)) => (spotify, user),
+            _ => return Err(err_msg("Not logged in to Spotify")),
+        };
+
+        let playlist_id = match &form.playlist_id {
+            Some(playlist_id) => {
+                spotify.user_playlist_change_detail(
+                    &user.id,
+                    playlist_id,
+                    Some(&form.name),
+                    Some(form.public),
+                    form.get_description(),
+                    Some(form.collaborative),
+                )?;
+                return Ok((playlist_id.to_owned(), Ok(())));
+            }
+            None => web_api::create_playlist(
+                spotify,
+                &user.id,
+                &form.name,
+                form.public,
+                form.get_description(),
+            )?,
+        };
+
+        let finished = web_api::add_tracks_to_playlist(spotify, &playlist_id, &form.track_uris)
+            .map_err(|e| format_err!("adding the tracks failed: {}", e))
+            .and_then(|_| {
+                // Playlists can't be made collaborative when they are created
+                if form.collaborative {
+                    spotify
+                        .user_playlist_change_detail(
+                            &user.id,
+                            &playlist_id,
+                            None,
+                            None,
+                            None,
+                            Some(true),
+                        )
+                        .map_err(|e| format_err!("making it collaborative failed: {}", e))?;
+                }
+                Ok(())
+            });
+        Ok((playlist_id, finished))
+    }
+
+    /// Opens the popup to add a track to some of the playlists the user can edit
+    pub fn open_playlist_picker(&mut self, track_uri: &str, track_name: &str) {
+        // Local files can't be added to playlists through the API
+        if !track_uri.starts_with("spotify:track:") {
+            self.notify(format!("\"{}\" can't be added to playlists", track_name));
+            return;
+        }
+        let editable_playlists = match self.get_editable_playlists() {
+            Ok(playlists) => playlists,
+            Err(e) => {
+                self.handle_error(e);
+                return;
+            }
+        };
+        if editable_playlists.is_empty() {
+            self.notify("You have no playlists you can add tracks to".to_string());
+            return;
+        }
+
+        let current_route = self.get_current_route();
+        if current_route.active_block != ActiveBlock::PlaylistPicker {
+            self.playlist_picker_return =
+                Some((current_route.active_block, current_route.hovered_block));
+        }
+        self.playlist_picker = Some(PlaylistPicker::new(
+            track_uri,
+            track_name,
+            editable_playlists,
+        ));
+        self.set_current_route_state(Some(ActiveBlock::PlaylistPicker), None);
+    }
+
+    // The sidebar only has a page of the user's playlists, so fetch all of them
+    fn get_editable_playlists(&self) -> Result<Vec<PickerPlaylist>, failure::Error> {
+        let mut playlists = vec![];
+        if let (Some(spotify), Some(user)) = (&self.spotify, &self.user) {
+            let mut offset = 0;
+            loop {
+                let page = spotify.current_user_playlists(PLAYLISTS_PAGE_LIMIT, offset)?;
+                playlists.extend(
+                    page.items
+                        .iter()
+                        .filter(|playlist| is_editable(playlist, &user.id))
+                        .map(|playlist| PickerPlaylist {
+                            id: playlist.id.to_owned(),
+                            name: playlist.name.to_owned(),
+                        }),
+                );
+                if page.next.is_none() {
+                    break;
+                }
+                offset += PLAYLISTS_PAGE_LIMIT;
+            }
+        }
+        Ok(playlists)
+    }
+
+    pub fn close_playlist_picker(&mut self) {
+        self.playlist_picker = None;
+        if let Some((active_block, hovered_block)) = self.playlist_picker_return.take() {
+            self.set_current_route_state(Some(active_block), Some(hovered_block));
+        }
+    }
+
+    /// Adds the track of the playlist picker to the playlists chosen in it, leaving out the
+    /// ones that have it already
+    pub fn add_picked_track_to_playlists(&mut self) {
+        let picker = match &self.playlist_picker {
+            Some(picker) => picker.clone(),
+            None => return,
+        };
+        let (spotify, user) = match (&self.spotify, &self.user) {
+            (Some(spotify), Some(user)) => (spotify, user),
+            _ => return,
+        };
+
+        let mut added = vec![];
+        let mut already_in = vec![];
+        let mut error = None;
+        for playlist in picker.get_target_playlists() {
+            let result = web_api::get_playlist_track_uris(spotify, &playlist.id).and_then(|uris| {
+                if uris.contains(&picker.track_uri) {
+                    return Ok(false);
+                }
+                spotify.user_playlist_add_tracks(
+                    &user.id,
+                    &playlist.id,
+                    &[picker.track_uri.to_owned()],
+                    None,
+                )?;
+                Ok(true)
+            });
+            match result {
+                Ok(true) => added.push(format!("\"{}\"", playlist.name)),
+                Ok(false) => already_in.push(format!("\"{}\"", playlist.name)),
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+
+        self.close_playlist_picker();
+        let mut messages = vec![];
+        if !added.is_empty() {
+            messages.push(format!(
+                "Added \"{}\" to {}",
+                picker.track_name,
+                added.join(", ")
+            ));
+        }
+        if !already_in.is_empty() {
+            messages.push(format!(
+                "\"{}\" is already in {}",
+                picker.track_name,
+                already_in.join(", ")
+            ));
+        }
+        if !messages.is_empty() {
+            self.notify(messages.join(". "));
+        }
+        if let Some(e) = error {
+            self.handle_error(e);
+        }
+    }
+
+    fn select_playlist(&mut self, playlist_id: &str) {
+        if let Some(playlists) = &self.playlists {
+            if let Some(index) = playlists.items.iter().position(|p| p.id == playlist_id) {
+                self.selected_playlist_index = Some(index);
+            }
+        }
+    }
+
+    pub fn refresh_playlists(&mut self) {
+        if let Some(spotify) = &self.spotify {
+            match spotify.current_user_playlists(self.large_search_limit, None) {
+                Ok(playlists) => self.playlists = Some(playlists),
+                Err(e) => self.handle_error(e),
+            }
+        }
+    }
+
     pub fn user_unfollow_playlists(&mut self) {
         if let (Some(playlists), Some(selected_index), Some(user), Some(spotify)) = (
             &self.playlists,
@@ -1306,6 +2928,7 @@ impl App {
                 match spotify.audio_analysis(uri) {
                     Ok(result) => {
                         self.audio_analysis = Some(result);
+                        self.audio_analysis_uri = Some(uri.to_owned());
                         self.push_navigation_stack(RouteId::Analysis, ActiveBlock::Analysis);
                     }
                     Err(e) => {
@@ -1316,6 +2939,24 @@ impl App {
         }
     }
 
+    // Fetches the analysis of `uri` without opening the analysis view, unless it's there already
+    fn load_audio_analysis(&mut self, uri: &str) {
+        if self.audio_analysis_uri.as_deref() == Some(uri) {
+            return;
+        }
+        if let Some(spotify) = &self.spotify {
+            match spotify.audio_analysis(uri) {
+                Ok(result) => {
+                    self.audio_analysis = Some(result);
+                    self.audio_analysis_uri = Some(uri.to_owned());
+                }
+                Err(e) => {
+                    self.handle_error(e);
+                }
+            }
+        }
+    }
+
     pub fn calculate_help_menu_offset(&mut self) {
         let old_offset = self.help_menu_offset;
 
@@ -0,0 +1,744 @@
+use crate::config::{write_private_file, AuthFlow, ClientConfig};
+use crate::qr_code::QrCode;
+use crate::redirect_uri::parse_target;
+use failure::{err_msg, format_err};
+use reqwest::{blocking::Client, Url};
+use rspotify::spotify::{
+    oauth2::{SpotifyOAuth, TokenInfo},
+    util::{datetime_to_timestamp, generate_random_string, get_token},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The permissions spotify-tui asks for. A new scope also needs an entry in `scope_feature`,
+/// which tells users with an older login why they are asked to log in again.
+pub const SCOPES: [&str; 14] = [
+    "playlist-read-collaborative",
+    "playlist-read-private",
+    "playlist-modify-private",
+    "playlist-modify-public",
+    "user-follow-read",
+    "user-follow-modify",
+    "user-library-modify",
+    "user-library-read",
+    "user-modify-playback-state",
+    "user-read-currently-playing",
+    "user-read-playback-position",
+    "user-read-playback-state",
+    "user-read-private",
+    "user-read-recently-played",
+];
+
+pub const ACCOUNTS_URL: &str = "https://accounts.spotify.com";
+const ACCOUNT_APPS_URL: &str = "https://www.spotify.com/account/apps/";
+
+// RFC 7636 allows verifiers of 43 to 128 characters
+const CODE_VERIFIER_LENGTH: usize = 64;
+
+/// The authorization flow selected in `client.yml`
+pub enum OAuth {
+    Code(SpotifyOAuth),
+    Pkce(PkceOAuth),
+}
+
+impl OAuth {
+    pub fn new(client_config: &ClientConfig, cache_path: PathBuf, scope: &str) -> OAuth {
+        match client_config.get_auth_flow() {
+            AuthFlow::Code => OAuth::Code(
+                SpotifyOAuth::default()
+                    .client_id(&client_config.client_id)
+                    .client_secret(&client_config.client_secret)
+                    .redirect_uri(&client_config.get_redirect_uri())
+                    .cache_path(cache_path)
+                    .scope(scope)
+                    .build(),
+            ),
+            AuthFlow::Pkce => OAuth::Pkce(PkceOAuth::new(
+                &client_config.client_id,
+                &client_config.get_redirect_uri(),
+                scope,
+                cache_path,
+            )),
+        }
+    }
+
+    /// Returns the cached token, refreshing it when it has expired
+    pub fn get_cached_token(&mut self) -> Option<TokenInfo> {
+        match self {
+            OAuth::Code(spotify_oauth) => {
+                let token_info = spotify_oauth.get_cached_token();
+                save_token_cache(&spotify_oauth.cache_path, token_info)
+            }
+            OAuth::Pkce(pkce_oauth) => pkce_oauth.get_cached_token(),
+        }
+    }
+
+    /// Like `get_cached_token`, but the code flow falls back to asking for a new token
+    pub fn get_token(&mut self) -> Option<TokenInfo> {
+        match self {
+            OAuth::Code(spotify_oauth) => {
+                let token_info = get_token(spotify_oauth);
+                save_token_cache(&spotify_oauth.cache_path, token_info)
+            }
+            OAuth::Pkce(pkce_oauth) => pkce_oauth.get_cached_token(),
+        }
+    }
+
+    pub fn cache_path(&self) -> &Path {
+        match self {
+            OAuth::Code(spotify_oauth) => &spotify_oauth.cache_path,
+            OAuth::Pkce(pkce_oauth) => &pkce_oauth.cache_path,
+        }
+    }
+
+    pub fn get_authorize_url(&self, state: &str) -> String {
+        match self {
+            OAuth::Code(spotify_oauth) => spotify_oauth.get_authorize_url(Some(state), None),
+            OAuth::Pkce(pkce_oauth) => pkce_oauth.get_authorize_url(state),
+        }
+    }
+
+    /// Exchanges the code in the redirect url for a token
+    pub fn process_token(&self, url: &str) -> Option<TokenInfo> {
+        let code = parse_response_code(url)?;
+        self.get_access_token(&code)
+    }
+
+    pub fn get_access_token(&self, code: &str) -> Option<TokenInfo> {
+        match self {
+            OAuth::Code(spotify_oauth) => save_token_cache(
+                &spotify_oauth.cache_path,
+                spotify_oauth.get_access_token(code),
+            ),
+            OAuth::Pkce(pkce_oauth) => pkce_oauth.get_access_token(code),
+        }
+    }
+}
+
+/// Logs in without a local server or browser: the authorization URL is printed (and
+/// optionally shown as a QR code) to be opened on any device, and the URL the browser is
+/// redirected to is pasted back.
+pub fn login_headless(oauth: &OAuth, show_qr: bool) -> Result<TokenInfo, failure::Error> {
+    let state = generate_random_string(16);
+    let auth_url = oauth.get_authorize_url(&state);
+
+    println!("Open this URL in a browser on any device and log in to Spotify:\n");
+    println!("{}\n", auth_url);
+    if show_qr {
+        match QrCode::encode(auth_url.as_bytes()) {
+            Some(qr_code) => println!("{}", qr_code.to_terminal_string()),
+            None => println!("The URL is too long to show as a QR code\n"),
+        }
+    }
+    println!("The browser will then fail to load a localhost page. That is expected:");
+    println!("copy the full URL from its address bar and paste it here.\n");
+    println!("Enter the URL you were redirected to: ");
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let code = parse_redirect_url(&input, &state)?;
+
+    oauth
+        .get_access_token(&code)
+        .ok_or_else(|| err_msg("Failed to exchange the authorization code for a token"))
+}
+
+/// Checks a pasted redirect URL against the login attempt and returns its code
+pub fn parse_redirect_url(url: &str, state: &str) -> Result<String, failure::Error> {
+    let (path, mut params) =
+        parse_target(url).ok_or_else(|| format_err!("`{}` is not a valid URL", url.trim()))?;
+
+    if path != "/callback" {
+        return Err(format_err!(
+            "Expected the URL of the `/callback` page, got `{}`",
+            path
+        ));
+    }
+    if params.get("state").map(String::as_str) != Some(state) {
+        return Err(err_msg(
+            "The URL is not from this login attempt (state mismatch), please try again",
+        ));
+    }
+    if let Some(error) = params.get("error") {
+        return Err(format_err!("Authorization failed: {}", error));
+    }
+    params
+        .remove("code")
+        .ok_or_else(|| err_msg("The URL does not contain an authorization code"))
+}
+
+/// Prints whether there is a cached token and whether it is still usable
+pub fn print_status(cache_path: &Path, scopes: &[&str]) -> bool {
+    println!("Token cache: {}", cache_path.display());
+
+    let token_info = match read_token_cache(cache_path) {
+        Some(token_info) => token_info,
+        None => {
+            println!("Not logged in. Run `spt` or `spt auth --headless` to log in.");
+            return false;
+        }
+    };
+
+    match token_info.expires_at {
+        Some(expires_at) if expires_at > datetime_to_timestamp(0) => println!(
+            "Logged in, the access token expires in {} minutes",
+            (expires_at - datetime_to_timestamp(0)) / 60
+        ),
+        _ => println!("Logged in, the access token has expired"),
+    }
+    if token_info.refresh_token.is_some() {
+        println!("The access token is refreshed automatically");
+    } else {
+        println!("There is no refresh token, you will need to log in again once it expires");
+    }
+
+    let missing_scopes = missing_scopes(&token_info, scopes);
+    if !missing_scopes.is_empty() {
+        println!(
+            "Missing scopes: {}. You will be asked to log in again when starting spt.",
+            missing_scopes.join(", ")
+        );
+    }
+
+    true
+}
+
+/// The required scopes that the cached token was not granted, empty if there is no cached token
+pub fn get_missing_scopes<'a>(cache_path: &Path, scopes: &[&'a str]) -> Vec<&'a str> {
+    read_token_cache(cache_path)
+        .map(|token_info| missing_scopes(&token_info, scopes))
+        .unwrap_or_default()
+}
+
+fn missing_scopes<'a>(token_info: &TokenInfo, scopes: &[&'a str]) -> Vec<&'a str> {
+    let granted: HashSet<&str> = token_info.scope.split_whitespace().collect();
+    scopes
+        .iter()
+        .filter(|scope| !granted.contains(*scope))
+        .cloned()
+        .collect()
+}
+
+pub fn print_missing_scopes(missing_scopes: &[&str]) {
+    println!(
+        "This version of spotify-tui needs permissions that your saved login doesn't grant:\n"
+    );
+    for scope in missing_scopes {
+        println!("  - {}: to {}", scope, scope_feature(scope));
+    }
+    println!("\nPlease log in again to grant them.\n");
+}
+
+/// What spotify-tui uses a scope for
+fn scope_feature(scope: &str) -> &'static str {
+    match scope {
+        "playlist-read-collaborative" => "list the collaborative playlists you are part of",
+        "playlist-read-private" => "list your private playlists",
+        "playlist-modify-private" => "edit your private playlists",
+        "playlist-modify-public" => "edit your public playlists",
+        "user-follow-read" => "list the artists you follow",
+        "user-follow-modify" => "follow and unfollow artists",
+        "user-library-modify" => "save and remove liked songs, albums and podcasts",
+        "user-library-read" => "list your liked songs, saved albums and podcasts",
+        "user-modify-playback-state" => "control playback, volume and devices",
+        "user-read-currently-playing" => "show the track that is playing",
+        "user-read-playback-position" => "resume podcast episodes where you left off",
+        "user-read-playback-state" => "show the playback state and your devices",
+        "user-read-private" => "use your country for search results and the Made For You playlists",
+        "user-read-recently-played" => "show your recently played tracks",
+        _ => "use new features",
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CredentialsError {
+    /// Spotify rejected the client id or secret
+    Rejected(String),
+    /// Spotify could not be reached, so the credentials could not be checked
+    Unreachable(String),
+}
+
+/// Checks a client id and secret with the client credentials flow, which needs no login
+pub fn check_client_credentials(
+    accounts_url: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<(), CredentialsError> {
+    let response = Client::new()
+        .post(&format!("{}/api/token", accounts_url))
+        .basic_auth(client_id, Some(client_secret))
+        .form(&[("grant_type", "client_credentials")])
+        .send()
+        .map_err(|e| CredentialsError::Unreachable(e.to_string()))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else if response.status().is_client_error() {
+        let body: serde_json::Value = response.json().unwrap_or_default();
+        let description = body["error_description"]
+            .as_str()
+            .or_else(|| body["error"].as_str())
+            .unwrap_or("unknown error");
+        Err(CredentialsError::Rejected(description.to_string()))
+    } else {
+        Err(CredentialsError::Unreachable(format!(
+            "Spotify responded with {}",
+            response.status()
+        )))
+    }
+}
+
+/// Deletes the cached token
+pub fn logout(cache_path: &Path) -> Result<(), failure::Error> {
+    if cache_path.exists() {
+        fs::remove_file(cache_path)?;
+        println!("Logged out, deleted {}", cache_path.display());
+    } else {
+        println!(
+            "Not logged in, there is no token at {}",
+            cache_path.display()
+        );
+    }
+    // Spotify has no endpoint for revoking tokens, access can only be removed by the user
+    println!(
+        "To revoke spotify-tui's access to your account, remove it at {}",
+        ACCOUNT_APPS_URL
+    );
+    Ok(())
+}
+
+/// rspotify writes the token cache in place with default permissions, so write it again
+/// the same way as our own files
+fn save_token_cache(cache_path: &Path, token_info: Option<TokenInfo>) -> Option<TokenInfo> {
+    if let Some(token_info_string) = token_info
+        .as_ref()
+        .and_then(|token_info| serde_json::to_string(token_info).ok())
+    {
+        if let Err(e) = write_private_file(cache_path, &token_info_string) {
+            println!("Failed to cache access token: {}", e);
+        }
+    }
+    token_info
+}
+
+fn read_token_cache(cache_path: &Path) -> Option<TokenInfo> {
+    let token_info_string = fs::read_to_string(cache_path).ok()?;
+    serde_json::from_str(&token_info_string).ok()
+}
+
+/// Opens the authorization page in the browser, printing the url as a fallback
+pub fn request_token(auth_url: &str) {
+    match webbrowser::open(auth_url) {
+        Ok(_) => println!("Opened {} in your browser", auth_url),
+        Err(why) => println!(
+            "Error {:?}; please navigate here [{:?}] ",
+            why.to_string(),
+            auth_url
+        ),
+    }
+}
+
+fn parse_response_code(url: &str) -> Option<String> {
+    let (_, mut params) = parse_target(url)?;
+    params.remove("code")
+}
+
+/// Authorization code flow with a Proof Key for Code Exchange, as described in RFC 7636.
+///
+/// Instead of authenticating with the client secret, a random code verifier is generated
+/// for every authorization and its hash is sent along with the authorization request.
+pub struct PkceOAuth {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub cache_path: PathBuf,
+    pub accounts_url: String,
+    code_verifier: String,
+}
+
+impl PkceOAuth {
+    pub fn new(client_id: &str, redirect_uri: &str, scope: &str, cache_path: PathBuf) -> PkceOAuth {
+        PkceOAuth {
+            client_id: client_id.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            scope: scope.to_string(),
+            cache_path,
+            accounts_url: ACCOUNTS_URL.to_string(),
+            code_verifier: generate_random_string(CODE_VERIFIER_LENGTH),
+        }
+    }
+
+    pub fn accounts_url(mut self, accounts_url: &str) -> PkceOAuth {
+        self.accounts_url = accounts_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn code_verifier(&self) -> &str {
+        &self.code_verifier
+    }
+
+    pub fn get_authorize_url(&self, state: &str) -> String {
+        let authorize_url = format!("{}/authorize", self.accounts_url);
+        let params = [
+            ("client_id", self.client_id.as_str()),
+            ("response_type", "code"),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("scope", self.scope.as_str()),
+            ("state", state),
+            ("code_challenge_method", "S256"),
+            ("code_challenge", &code_challenge(&self.code_verifier)),
+        ];
+
+        match Url::parse_with_params(&authorize_url, params.iter()) {
+            Ok(url) => url.to_string(),
+            Err(_) => authorize_url,
+        }
+    }
+
+    pub fn get_cached_token(&self) -> Option<TokenInfo> {
+        let token_info = read_token_cache(&self.cache_path)?;
+
+        if !is_scope_subset(&self.scope, &token_info.scope) {
+            None
+        } else if is_token_expired(&token_info) {
+            token_info
+                .refresh_token
+                .and_then(|refresh_token| self.refresh_access_token(&refresh_token))
+        } else {
+            Some(token_info)
+        }
+    }
+
+    pub fn get_access_token(&self, code: &str) -> Option<TokenInfo> {
+        let mut payload = HashMap::new();
+        payload.insert("grant_type", "authorization_code");
+        payload.insert("code", code);
+        payload.insert("redirect_uri", &self.redirect_uri);
+        payload.insert("client_id", &self.client_id);
+        payload.insert("code_verifier", &self.code_verifier);
+
+        self.fetch_access_token(&payload)
+    }
+
+    pub fn refresh_access_token(&self, refresh_token: &str) -> Option<TokenInfo> {
+        let mut payload = HashMap::new();
+        payload.insert("grant_type", "refresh_token");
+        payload.insert("refresh_token", refresh_token);
+        payload.insert("client_id", &self.client_id);
+
+        self.fetch_access_token(&payload)
+    }
+
+    fn fetch_access_token(&self, payload: &HashMap<&str, &str>) -> Option<TokenInfo> {
+        let response = Client::new()
+            .post(&format!("{}/api/token", self.accounts_url))
+            .form(payload)
+            .send();
+
+        let response = match response {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                println!(
+                    "Failed to fetch access token: {}",
+                    response.text().unwrap_or_default()
+                );
+                return None;
+            }
+            Err(e) => {
+                println!("Failed to fetch access token: {}", e);
+                return None;
+            }
+        };
+
+        let mut token_info: TokenInfo = response.json().ok()?;
+        token_info.set_expires_at(datetime_to_timestamp(token_info.expires_in));
+        // A refresh response only includes a refresh token when it has been rotated
+        if token_info.refresh_token.is_none() {
+            if let Some(refresh_token) = payload.get("refresh_token") {
+                token_info.set_refresh_token(refresh_token);
+            }
+        }
+
+        if let Ok(token_info_string) = serde_json::to_string(&token_info) {
+            if let Err(e) = write_private_file(&self.cache_path, &token_info_string) {
+                println!("Failed to cache access token: {}", e);
+            }
+        }
+
+        Some(token_info)
+    }
+}
+
+fn is_scope_subset(needle_scope: &str, haystack_scope: &str) -> bool {
+    let haystack: HashSet<&str> = haystack_scope.split_whitespace().collect();
+    needle_scope
+        .split_whitespace()
+        .all(|scope| haystack.contains(scope))
+}
+
+fn is_token_expired(token_info: &TokenInfo) -> bool {
+    // 10s as buffer time
+    match token_info.expires_at {
+        Some(expires_at) => datetime_to_timestamp(0) > expires_at - 10,
+        None => true,
+    }
+}
+
+/// The base64url encoded SHA-256 hash of the verifier, without padding
+pub fn code_challenge(code_verifier: &str) -> String {
+    base64::encode_config(
+        &Sha256::digest(code_verifier.as_bytes()),
+        base64::URL_SAFE_NO_PAD,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_server;
+    use std::net::TcpListener;
+
+    fn temp_cache_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("spt-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_parse_redirect_url() {
+        assert_eq!(
+            parse_redirect_url(
+                "http://localhost:8888/callback?code=the-code&state=the-state\n",
+                "the-state"
+            )
+            .unwrap(),
+            "the-code"
+        );
+        assert!(parse_redirect_url(
+            "http://localhost:8888/callback?code=the-code&state=other",
+            "the-state"
+        )
+        .is_err());
+        assert!(parse_redirect_url(
+            "http://localhost:8888/callback?error=access_denied&state=the-state",
+            "the-state"
+        )
+        .is_err());
+        assert!(parse_redirect_url(
+            "http://localhost:8888/callback?state=the-state",
+            "the-state"
+        )
+        .is_err());
+        assert!(parse_redirect_url(
+            "http://localhost:8888/other?code=a&state=the-state",
+            "the-state"
+        )
+        .is_err());
+        assert!(parse_redirect_url("", "the-state").is_err());
+    }
+
+    #[test]
+    fn test_get_missing_scopes() {
+        let cache_path = temp_cache_path("scopes");
+        let scopes = ["user-read-private", "user-library-read", "user-follow-read"];
+
+        // Without a cached token there is nothing to re-authorize
+        assert!(get_missing_scopes(&cache_path, &scopes).is_empty());
+
+        fs::write(
+            &cache_path,
+            r#"{"access_token":"a","token_type":"Bearer","expires_in":3600,"scope":"user-follow-read user-read-private"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            get_missing_scopes(&cache_path, &scopes),
+            vec!["user-library-read"]
+        );
+        assert!(get_missing_scopes(&cache_path, &scopes[..1]).is_empty());
+        let _ = fs::remove_file(&cache_path);
+    }
+
+    #[test]
+    fn test_every_scope_has_a_feature() {
+        for scope in SCOPES.iter() {
+            assert_ne!(scope_feature(scope), scope_feature("unknown-scope"));
+        }
+    }
+
+    #[test]
+    fn test_check_client_credentials() {
+        let (accounts_url, requests) = fake_server::start(vec![(
+            "POST /api/token ",
+            "200 OK",
+            r#"{"access_token":"a","token_type":"Bearer","expires_in":3600}"#,
+        )]);
+        assert_eq!(
+            check_client_credentials(&accounts_url, "client-id", "client-secret"),
+            Ok(())
+        );
+        let request = requests.recv().unwrap();
+        assert!(request.contains("grant_type=client_credentials"));
+        // Basic auth of "client-id:client-secret"
+        assert!(request.contains("Y2xpZW50LWlkOmNsaWVudC1zZWNyZXQ="));
+
+        let (accounts_url, _) = fake_server::start(vec![(
+            "POST /api/token ",
+            "400 Bad Request",
+            r#"{"error":"invalid_client","error_description":"Invalid client secret"}"#,
+        )]);
+        assert_eq!(
+            check_client_credentials(&accounts_url, "client-id", "wrong"),
+            Err(CredentialsError::Rejected(
+                "Invalid client secret".to_string()
+            ))
+        );
+
+        // Nothing is listening on this port once the listener is dropped
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let accounts_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        match check_client_credentials(&accounts_url, "client-id", "client-secret") {
+            Err(CredentialsError::Unreachable(_)) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_logout_deletes_token_cache() {
+        let cache_path = temp_cache_path("logout");
+        fs::write(&cache_path, "{}").unwrap();
+
+        logout(&cache_path).unwrap();
+        assert!(!cache_path.exists());
+        // Logging out twice is fine
+        logout(&cache_path).unwrap();
+    }
+
+    #[test]
+    fn test_code_challenge() {
+        // Example from RFC 7636 appendix B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_get_authorize_url() {
+        let oauth = PkceOAuth::new(
+            "client-id",
+            "http://localhost:8888/callback",
+            "user-read-private user-library-read",
+            temp_cache_path("authorize"),
+        );
+        let url = Url::parse(&oauth.get_authorize_url("some-state")).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.path(), "/authorize");
+        assert_eq!(params["client_id"], "client-id");
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["redirect_uri"], "http://localhost:8888/callback");
+        assert_eq!(params["scope"], "user-read-private user-library-read");
+        assert_eq!(params["state"], "some-state");
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(
+            params["code_challenge"],
+            code_challenge(oauth.code_verifier())
+        );
+        assert!(oauth.code_verifier().len() >= 43);
+    }
+
+    #[test]
+    fn test_get_access_token_without_client_secret() {
+        let (accounts_url, requests) = fake_server::start(vec![(
+            "POST /api/token ",
+            "200 OK",
+            r#"{"access_token":"access","token_type":"Bearer","expires_in":3600,"refresh_token":"refresh","scope":"user-read-private"}"#,
+        )]);
+        let cache_path = temp_cache_path("access-token");
+        let oauth = PkceOAuth::new(
+            "client-id",
+            "http://localhost:8888/callback",
+            "user-read-private",
+            cache_path.clone(),
+        )
+        .accounts_url(&accounts_url);
+
+        let token_info = oauth.get_access_token("the-code").unwrap();
+        let request = requests.recv().unwrap();
+
+        assert!(request.starts_with("POST /api/token "));
+        assert!(!request.to_lowercase().contains("authorization:"));
+        assert!(request.contains("grant_type=authorization_code"));
+        assert!(request.contains("code=the-code"));
+        assert!(request.contains("client_id=client-id"));
+        assert!(request.contains(&format!("code_verifier={}", oauth.code_verifier())));
+
+        assert_eq!(token_info.access_token, "access");
+        assert_eq!(token_info.refresh_token, Some("refresh".to_string()));
+        assert!(token_info.expires_at.is_some());
+
+        // The token is cached and still valid
+        let cached = oauth.get_cached_token().unwrap();
+        assert_eq!(cached.access_token, "access");
+        let _ = fs::remove_file(&cache_path);
+    }
+
+    #[test]
+    fn test_refresh_access_token_keeps_refresh_token() {
+        let (accounts_url, requests) = fake_server::start(vec![(
+            "POST /api/token ",
+            "200 OK",
+            r#"{"access_token":"new-access","token_type":"Bearer","expires_in":3600,"scope":"user-read-private"}"#,
+        )]);
+        let cache_path = temp_cache_path("refresh-token");
+        let oauth = PkceOAuth::new(
+            "client-id",
+            "http://localhost:8888/callback",
+            "user-read-private",
+            cache_path.clone(),
+        )
+        .accounts_url(&accounts_url);
+
+        // An expired token in the cache triggers a refresh
+        fs::write(
+      &cache_path,
+      r#"{"access_token":"old","token_type":"Bearer","expires_in":3600,"expires_at":0,"refresh_token":"refresh","scope":"user-read-private"}"#,
+    )
+    .unwrap();
+
+        let token_info = oauth.get_cached_token().unwrap();
+        let request = requests.recv().unwrap();
+
+        assert!(request.contains("grant_type=refresh_token"));
+        assert!(request.contains("refresh_token=refresh"));
+        assert!(request.contains("client_id=client-id"));
+        assert_eq!(token_info.access_token, "new-access");
+        assert_eq!(token_info.refresh_token, Some("refresh".to_string()));
+        let _ = fs::remove_file(&cache_path);
+    }
+
+    #[test]
+    fn test_cached_token_with_missing_scope_is_ignored() {
+        let cache_path = temp_cache_path("missing-scope");
+        let oauth = PkceOAuth::new(
+            "client-id",
+            "http://localhost:8888/callback",
+            "user-read-private user-library-read",
+            cache_path.clone(),
+        );
+        fs::write(
+      &cache_path,
+      r#"{"access_token":"old","token_type":"Bearer","expires_in":3600,"expires_at":99999999999,"scope":"user-read-private"}"#,
+    )
+    .unwrap();
+
+        assert!(oauth.get_cached_token().is_none());
+        let _ = fs::remove_file(&cache_path);
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+
+/// Spotify takes at most five seeds, so the radio is based on this many of the last tracks
+pub const SEED_TRACK_COUNT: usize = 5;
+// Tracks played this recently aren't picked again
+const HISTORY_LENGTH: usize = 200;
+
+/// Keeps playback going with recommendations once an album or playlist ends
+#[derive(Default)]
+pub struct Autoplay {
+    pub is_enabled: bool,
+    /// The tracks autoplay played last, to tell when they are what is playing
+    pub radio_uris: Option<Vec<String>>,
+    /// What to play when the context ends, picked once its last track starts
+    pub next_uris: Option<Vec<String>>,
+    /// The track it was last checked for whether it ends the context
+    pub checked_track_uri: Option<String>,
+    // Uris of the tracks that played, the latest last
+    history: VecDeque<String>,
+}
+
+impl Autoplay {
+    pub fn record_played(&mut self, track_uri: &str) {
+        if self.history.back().map(String::as_str) == Some(track_uri) {
+            return;
+        }
+        self.history.push_back(track_uri.to_owned());
+        if self.history.len() > HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+    }
+
+    /// Ids of the last tracks that played, the latest first
+    pub fn get_seed_track_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = vec![];
+        for uri in self.history.iter().rev() {
+            if let Some(id) = uri.strip_prefix("spotify:track:") {
+                if !ids.iter().any(|seed| seed == id) {
+                    ids.push(id.to_owned());
+                }
+            }
+            if ids.len() == SEED_TRACK_COUNT {
+                break;
+            }
+        }
+        ids
+    }
+
+    /// Leaves out the tracks that played recently
+    pub fn remove_played(&self, uris: Vec<String>) -> Vec<String> {
+        uris.into_iter()
+            .filter(|uri| !self.history.contains(uri))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_seed_track_ids() {
+        let mut autoplay = Autoplay::default();
+        for id in &["1", "2", "2", "3", "1", "4", "5", "6"] {
+            autoplay.record_played(&format!("spotify:track:{}", id));
+        }
+        autoplay.record_played("spotify:episode:7");
+
+        assert_eq!(autoplay.get_seed_track_ids(), vec!["6", "5", "4", "1", "3"]);
+    }
+
+    #[test]
+    fn test_remove_played() {
+        let mut autoplay = Autoplay::default();
+        autoplay.record_played("spotify:track:1");
+        autoplay.record_played("spotify:track:2");
+
+        let uris = vec!["spotify:track:2".to_string(), "spotify:track:3".to_string()];
+        assert_eq!(autoplay.remove_played(uris), vec!["spotify:track:3"]);
+    }
+
+    #[test]
+    fn test_history_is_limited() {
+        let mut autoplay = Autoplay::default();
+        for id in 0..=HISTORY_LENGTH {
+            autoplay.record_played(&format!("spotify:track:{}", id));
+        }
+        let uris = vec!["spotify:track:0".to_string()];
+        assert_eq!(autoplay.remove_played(uris.clone()), uris);
+    }
+}
@@ -1,26 +1,46 @@
-use super::banner::BANNER;
+use super::{setup, user_config::Theme};
 use dirs;
-use failure::err_msg;
+use failure::{err_msg, format_err};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
+    ffi::OsString,
     fs,
-    io::{stdin, Write},
+    io::{self, Write},
     path::{Path, PathBuf},
+    process::Command,
 };
 
-const DEFAULT_PORT: u16 = 8888;
+pub const DEFAULT_PORT: u16 = 8888;
 const FILE_NAME: &str = "client.yml";
 const CONFIG_DIR: &str = ".config";
 const APP_CONFIG_DIR: &str = "spotify-tui";
 const TOKEN_CACHE_FILE: &str = ".spotify_token_cache.json";
 
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthFlow {
+    /// Authorization code flow, which needs the client secret
+    Code,
+    /// Authorization code flow with PKCE, which only needs the client id
+    Pkce,
+}
+
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ClientConfig {
     pub client_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub client_secret: String,
+    /// Command that prints the client secret, so it doesn't have to be stored in plain text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_command: Option<String>,
     pub device_id: Option<String>,
     // FIXME: port should be defined in `user_config` not in here
     pub port: Option<u16>,
+    pub auth_flow: Option<AuthFlow>,
+    /// The volume last set on each device, by device id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub device_volumes: BTreeMap<String, u8>,
 }
 
 pub struct ConfigPaths {
@@ -33,8 +53,11 @@ impl ClientConfig {
         ClientConfig {
             client_id: "".to_string(),
             client_secret: "".to_string(),
+            secret_command: None,
             device_id: None,
             port: None,
+            auth_flow: None,
+            device_volumes: BTreeMap::new(),
         }
     }
 
@@ -46,6 +69,10 @@ impl ClientConfig {
         self.port.unwrap_or(DEFAULT_PORT)
     }
 
+    pub fn get_auth_flow(&self) -> AuthFlow {
+        self.auth_flow.unwrap_or(AuthFlow::Code)
+    }
+
     pub fn get_or_build_paths(&self) -> Result<ConfigPaths, failure::Error> {
         match dirs::home_dir() {
             Some(home) => {
@@ -77,86 +104,295 @@ impl ClientConfig {
 
     pub fn set_device_id(&mut self, device_id: String) -> Result<(), failure::Error> {
         let paths = self.get_or_build_paths()?;
-        let config_string = fs::read_to_string(&paths.config_file_path)?;
-        let mut config_yml: ClientConfig = serde_yaml::from_str(&config_string)?;
+        update_config_file(&paths.config_file_path, |config_yml| {
+            config_yml.device_id = Some(device_id.clone());
+        })?;
+
+        self.device_id = Some(device_id);
+        Ok(())
+    }
+
+    /// Remembers the volume of a device, to go back to when playback moves to it
+    pub fn set_device_volume(&mut self, device_id: &str, volume: u8) -> Result<(), failure::Error> {
+        if self.device_volumes.get(device_id) == Some(&volume) {
+            return Ok(());
+        }
 
-        self.device_id = Some(device_id.clone());
-        config_yml.device_id = Some(device_id);
+        let paths = self.get_or_build_paths()?;
+        update_config_file(&paths.config_file_path, |config_yml| {
+            config_yml
+                .device_volumes
+                .insert(device_id.to_owned(), volume);
+        })?;
 
-        let new_config = serde_yaml::to_string(&config_yml)?;
-        let mut config_file = fs::File::create(&paths.config_file_path)?;
-        write!(config_file, "{}", new_config)?;
+        self.device_volumes.insert(device_id.to_owned(), volume);
         Ok(())
     }
 
-    pub fn load_config(&mut self) -> Result<(), failure::Error> {
+    /// Loads `client.yml`, running the setup wizard when it doesn't exist yet
+    pub fn load_config(&mut self, theme: &Theme) -> Result<(), failure::Error> {
         let paths = self.get_or_build_paths()?;
         if paths.config_file_path.exists() {
-            let config_string = fs::read_to_string(&paths.config_file_path)?;
-            let config_yml: ClientConfig = serde_yaml::from_str(&config_string)?;
+            warn_if_accessible_by_others(&paths.config_file_path);
+            warn_if_accessible_by_others(&paths.token_cache_path);
+            self.read_config(&paths.config_file_path)
+        } else {
+            let config_yml = setup::run(theme, &paths.config_file_path)?;
+
+            let content_yml = serde_yaml::to_string(&config_yml)?;
+            write_private_file(&paths.config_file_path, &content_yml)?;
+            println!("Config saved to {}", paths.config_file_path.display());
 
             self.client_id = config_yml.client_id;
             self.client_secret = config_yml.client_secret;
             self.device_id = config_yml.device_id;
             self.port = config_yml.port;
+            self.auth_flow = config_yml.auth_flow;
+            self.device_volumes = config_yml.device_volumes;
 
             Ok(())
-        } else {
-            println!("{}", BANNER);
-
-            println!(
-                "Config will be saved to {}",
-                paths.config_file_path.display()
-            );
-
-            println!("\nHow to get setup:\n");
-
-            let instructions = [
-               "Go to the Spotify dashboard - https://developer.spotify.com/dashboard/applications",
-               "Click `Create a Client ID` and create an app",
-               "Now click `Edit Settings`",
-               &format!("Add `http://localhost:{}/callback` to the Redirect URIs", DEFAULT_PORT),
-               "You are now ready to authenticate with Spotify!",
-            ];
-
-            let mut number = 1;
-            for item in instructions.iter() {
-                println!("  {}. {}", number, item);
-                number += 1;
+        }
+    }
+
+    fn read_config(&mut self, path: &Path) -> Result<(), failure::Error> {
+        let config_string = fs::read_to_string(path)?;
+        let config_yml: ClientConfig = serde_yaml::from_str(&config_string)?;
+
+        self.client_id = config_yml.client_id;
+        self.client_secret = config_yml.client_secret;
+        self.secret_command = config_yml.secret_command;
+        self.device_id = config_yml.device_id;
+        self.port = config_yml.port;
+        self.auth_flow = config_yml.auth_flow;
+        self.device_volumes = config_yml.device_volumes;
+
+        if let Some(secret_command) = &self.secret_command {
+            if !self.client_secret.is_empty() {
+                return Err(err_msg(
+                    "Set either client_secret or secret_command in client.yml, not both",
+                ));
             }
+            self.client_secret = run_secret_command(secret_command)?;
+        }
 
-            // TODO: Handle empty input?
-            let mut client_id = String::new();
-            println!("\nEnter your Client ID: ");
-            stdin().read_line(&mut client_id)?;
+        if self.get_auth_flow() == AuthFlow::Code && self.client_secret.is_empty() {
+            return Err(err_msg(
+                "client_secret or secret_command is required unless `auth_flow: pkce` is set in client.yml",
+            ));
+        }
 
-            let mut client_secret = String::new();
-            println!("\nEnter your Client Secret: ");
-            stdin().read_line(&mut client_secret)?;
+        Ok(())
+    }
+}
 
-            let mut port = String::new();
-            println!("\nEnter port of redirect uri (default {}): ", DEFAULT_PORT);
-            stdin().read_line(&mut port)?;
-            let port = port.trim().parse::<u16>().unwrap_or(DEFAULT_PORT);
+// Changes `client.yml` as it is on disk, so a secret from `secret_command` is never written out
+fn update_config_file<F>(path: &Path, update: F) -> Result<(), failure::Error>
+where
+    F: FnOnce(&mut ClientConfig),
+{
+    let config_string = fs::read_to_string(path)?;
+    let mut config_yml: ClientConfig = serde_yaml::from_str(&config_string)?;
 
-            let config_yml = ClientConfig {
-                client_id: client_id.trim().to_string(),
-                client_secret: client_secret.trim().to_string(),
-                device_id: None,
-                port: Some(port),
-            };
+    update(&mut config_yml);
 
-            let content_yml = serde_yaml::to_string(&config_yml)?;
+    let new_config = serde_yaml::to_string(&config_yml)?;
+    write_private_file(path, &new_config)?;
+    Ok(())
+}
 
-            let mut new_config = fs::File::create(&paths.config_file_path)?;
-            write!(new_config, "{}", content_yml)?;
+/// Writes a file that only the current user can read, as it holds credentials. The
+/// contents go to a temporary file first, which then replaces `path`, so a crash never
+/// leaves a truncated file behind.
+pub fn write_private_file(path: &Path, contents: &str) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut temp_file_name = OsString::from(".");
+    temp_file_name.push(file_name);
+    temp_file_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_file_name);
 
-            self.client_id = config_yml.client_id;
-            self.client_secret = config_yml.client_secret;
-            self.device_id = config_yml.device_id;
-            self.port = config_yml.port;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
 
-            Ok(())
+    let result = options.open(&temp_path).and_then(|mut file| {
+        // The mode only applies to new files, a stale temporary file may have another one
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
         }
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Whether users other than the owner can access the file
+#[cfg(unix)]
+fn is_accessible_by_others(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|metadata| metadata.permissions().mode() & 0o077 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_accessible_by_others(_path: &Path) -> bool {
+    false
+}
+
+/// Warns about credential files that were created with default permissions
+pub fn warn_if_accessible_by_others(path: &Path) {
+    if is_accessible_by_others(path) {
+        eprintln!(
+            "Warning: {} can be read by other users. Run `chmod 600 {}` to fix this.",
+            path.display(),
+            path.display()
+        );
+    }
+}
+
+/// Runs `secret_command` and returns the first line it prints
+fn run_secret_command(secret_command: &str) -> Result<String, failure::Error> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", secret_command]).output()
+    } else {
+        Command::new("sh").args(["-c", secret_command]).output()
+    }
+    .map_err(|e| format_err!("Failed to run secret_command `{}`: {}", secret_command, e))?;
+
+    if !output.status.success() {
+        return Err(format_err!(
+            "secret_command `{}` failed ({}): {}",
+            secret_command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| format_err!("secret_command `{}` printed invalid UTF-8", secret_command))?;
+    match stdout.lines().next().map(str::trim) {
+        Some(secret) if !secret.is_empty() => Ok(secret.to_string()),
+        _ => Err(format_err!(
+            "secret_command `{}` did not print a secret",
+            secret_command
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("spotify-tui-config-test-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("client.yml")
+    }
+
+    #[test]
+    fn test_write_private_file_replaces_contents() {
+        let path = temp_path("replace");
+        fs::write(
+            &path,
+            "client_id: old\nclient_secret: a-much-longer-old-secret\n",
+        )
+        .unwrap();
+
+        write_private_file(&path, "client_id: new\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "client_id: new\n");
+        let dir = path.parent().unwrap();
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path("permissions");
+        fs::write(&path, "client_id: abc\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(is_accessible_by_others(&path));
+
+        write_private_file(&path, "client_id: abc\n").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!is_accessible_by_others(&path));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_update_config_file_keeps_other_settings() {
+        let path = temp_path("update");
+        fs::write(&path, "client_id: abc\nsecret_command: pass spotify\n").unwrap();
+
+        update_config_file(&path, |config_yml| {
+            config_yml.device_volumes.insert("device-1".to_string(), 40);
+        })
+        .unwrap();
+
+        let config_string = fs::read_to_string(&path).unwrap();
+        let config_yml: ClientConfig = serde_yaml::from_str(&config_string).unwrap();
+        assert_eq!(config_yml.client_id, "abc");
+        assert_eq!(config_yml.client_secret, "");
+        assert_eq!(config_yml.secret_command, Some("pass spotify".to_string()));
+        assert_eq!(config_yml.device_volumes.get("device-1"), Some(&40));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_secret_command() {
+        assert_eq!(
+            run_secret_command("printf 'abc123\\nsecond line\\n'").unwrap(),
+            "abc123"
+        );
+        assert!(run_secret_command("printf ''").is_err());
+        assert!(run_secret_command("echo oops >&2; exit 1")
+            .unwrap_err()
+            .to_string()
+            .contains("oops"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_secret_command_sets_client_secret() {
+        let path = temp_path("secret-command");
+        fs::write(
+            &path,
+            "client_id: abc\nsecret_command: echo from-command\ndevice_id: ~\nport: ~\n",
+        )
+        .unwrap();
+
+        let mut client_config = ClientConfig::new();
+        client_config.read_config(&path).unwrap();
+        assert_eq!(client_config.client_secret, "from-command");
+        assert_eq!(client_config.get_auth_flow(), AuthFlow::Code);
+
+        fs::write(
+            &path,
+            "client_id: abc\nclient_secret: def\nsecret_command: echo from-command\n",
+        )
+        .unwrap();
+        assert!(ClientConfig::new().read_config(&path).is_err());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
     }
 }
@@ -1,4 +1,5 @@
-use crossterm::event;
+use crossterm::event::{self, KeyCode, KeyModifiers};
+use std::fmt;
 
 /// Represents an key.
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
@@ -7,6 +8,8 @@ pub enum Key {
     Enter,
     /// Tabulation key
     Tab,
+    /// Shift + Tabulation key
+    BackTab,
     /// Backspace key
     Backspace,
     /// Escape key
@@ -63,6 +66,8 @@ pub enum Key {
     Char(char),
     Ctrl(char),
     Alt(char),
+    /// Any other combination of modifiers and key, e.g. `ctrl-alt-x` or `shift-left`
+    Modified(KeyModifiers, KeyCode),
     Unkown,
 }
 
@@ -92,88 +97,188 @@ impl Key {
             _ => panic!("unknown function key: F{}", n),
         }
     }
+
+    /// Returns the key for `code` pressed together with `modifiers`.
+    ///
+    /// A combination always maps to the same variant, whether it was read from the terminal or
+    /// parsed from the config, so keys can be compared with `==`. Single-modifier characters
+    /// stay `Ctrl(c)`/`Alt(c)` and everything else that has modifiers becomes `Modified`.
+    pub fn with_modifiers(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        let (code, modifiers) = match code {
+            // Shifted characters are already reported in their shifted form (`A`, `?`)
+            KeyCode::Char(_) | KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                (KeyCode::BackTab, modifiers - KeyModifiers::SHIFT)
+            }
+            _ => (code, modifiers),
+        };
+
+        if modifiers.is_empty() {
+            return Key::from_code(code);
+        }
+
+        match code {
+            KeyCode::Char(c) if modifiers == KeyModifiers::CONTROL => Key::Ctrl(c),
+            KeyCode::Char(c) if modifiers == KeyModifiers::ALT => Key::Alt(c),
+            KeyCode::F(n) if n > 12 => Key::Unkown,
+            KeyCode::Null => Key::Unkown,
+            _ => Key::Modified(modifiers, code),
+        }
+    }
+
+    fn from_code(code: KeyCode) -> Key {
+        match code {
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Insert => Key::Ins,
+            KeyCode::F(n) if n <= 12 => Key::from_f(n),
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::BackTab => Key::BackTab,
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::F(_) | KeyCode::Null => Key::Unkown,
+        }
+    }
 }
 
 impl From<event::KeyEvent> for Key {
     fn from(key_event: event::KeyEvent) -> Self {
-        match key_event {
-            event::KeyEvent {
-                code: event::KeyCode::Esc,
-                ..
-            } => Key::Esc,
-            event::KeyEvent {
-                code: event::KeyCode::Backspace,
-                ..
-            } => Key::Backspace,
-            event::KeyEvent {
-                code: event::KeyCode::Left,
-                ..
-            } => Key::Left,
-            event::KeyEvent {
-                code: event::KeyCode::Right,
-                ..
-            } => Key::Right,
-            event::KeyEvent {
-                code: event::KeyCode::Up,
-                ..
-            } => Key::Up,
-            event::KeyEvent {
-                code: event::KeyCode::Down,
-                ..
-            } => Key::Down,
-            event::KeyEvent {
-                code: event::KeyCode::Home,
-                ..
-            } => Key::Home,
-            event::KeyEvent {
-                code: event::KeyCode::End,
-                ..
-            } => Key::End,
-            event::KeyEvent {
-                code: event::KeyCode::PageUp,
-                ..
-            } => Key::PageUp,
-            event::KeyEvent {
-                code: event::KeyCode::PageDown,
-                ..
-            } => Key::PageDown,
-            event::KeyEvent {
-                code: event::KeyCode::Delete,
-                ..
-            } => Key::Delete,
-            event::KeyEvent {
-                code: event::KeyCode::Insert,
-                ..
-            } => Key::Ins,
-            event::KeyEvent {
-                code: event::KeyCode::F(n),
-                ..
-            } => Key::from_f(n),
-            event::KeyEvent {
-                code: event::KeyCode::Enter,
-                ..
-            } => Key::Enter,
-            event::KeyEvent {
-                code: event::KeyCode::Tab,
-                ..
-            } => Key::Tab,
-
-            // First check for char + modifier
-            event::KeyEvent {
-                code: event::KeyCode::Char(c),
-                modifiers: event::KeyModifiers::ALT,
-            } => Key::Alt(c),
-            event::KeyEvent {
-                code: event::KeyCode::Char(c),
-                modifiers: event::KeyModifiers::CONTROL,
-            } => Key::Ctrl(c),
-
-            event::KeyEvent {
-                code: event::KeyCode::Char(c),
-                ..
-            } => Key::Char(c),
-
-            _ => Key::Unkown,
+        Key::with_modifiers(key_event.code, key_event.modifiers)
+    }
+}
+
+fn fmt_char(f: &mut fmt::Formatter, c: char) -> fmt::Result {
+    match c {
+        ' ' => write!(f, "space"),
+        _ => write!(f, "{}", c),
+    }
+}
+
+/// Formats the key using the same grammar that the keybindings config accepts, so the output
+/// can be pasted straight into `config.yml`.
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Key::Enter => write!(f, "enter"),
+            Key::Tab => write!(f, "tab"),
+            Key::BackTab => write!(f, "shift-tab"),
+            Key::Backspace => write!(f, "backspace"),
+            Key::Esc => write!(f, "esc"),
+            Key::Left => write!(f, "left"),
+            Key::Right => write!(f, "right"),
+            Key::Up => write!(f, "up"),
+            Key::Down => write!(f, "down"),
+            Key::Ins => write!(f, "ins"),
+            Key::Delete => write!(f, "del"),
+            Key::Home => write!(f, "home"),
+            Key::End => write!(f, "end"),
+            Key::PageUp => write!(f, "pageup"),
+            Key::PageDown => write!(f, "pagedown"),
+            Key::F0 => write!(f, "f0"),
+            Key::F1 => write!(f, "f1"),
+            Key::F2 => write!(f, "f2"),
+            Key::F3 => write!(f, "f3"),
+            Key::F4 => write!(f, "f4"),
+            Key::F5 => write!(f, "f5"),
+            Key::F6 => write!(f, "f6"),
+            Key::F7 => write!(f, "f7"),
+            Key::F8 => write!(f, "f8"),
+            Key::F9 => write!(f, "f9"),
+            Key::F10 => write!(f, "f10"),
+            Key::F11 => write!(f, "f11"),
+            Key::F12 => write!(f, "f12"),
+            Key::Char(c) => fmt_char(f, c),
+            Key::Ctrl(c) => {
+                write!(f, "ctrl-")?;
+                fmt_char(f, c)
+            }
+            Key::Alt(c) => {
+                write!(f, "alt-")?;
+                fmt_char(f, c)
+            }
+            Key::Modified(modifiers, code) => {
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    write!(f, "ctrl-")?;
+                }
+                if modifiers.contains(KeyModifiers::ALT) {
+                    write!(f, "alt-")?;
+                }
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    write!(f, "shift-")?;
+                }
+                write!(f, "{}", Key::from_code(code))
+            }
+            Key::Unkown => write!(f, "unknown"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    #[test]
+    fn test_from_key_event() {
+        let key = |code, modifiers| Key::from(KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            key(KeyCode::Char('j'), KeyModifiers::empty()),
+            Key::Char('j')
+        );
+        assert_eq!(key(KeyCode::Char('J'), KeyModifiers::SHIFT), Key::Char('J'));
+        assert_eq!(
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Key::Ctrl('c')
+        );
+        assert_eq!(key(KeyCode::Char('x'), KeyModifiers::ALT), Key::Alt('x'));
+        assert_eq!(key(KeyCode::BackTab, KeyModifiers::SHIFT), Key::BackTab);
+        assert_eq!(key(KeyCode::Tab, KeyModifiers::SHIFT), Key::BackTab);
+        assert_eq!(key(KeyCode::F(5), KeyModifiers::empty()), Key::F5);
+        assert_eq!(key(KeyCode::F(20), KeyModifiers::empty()), Key::Unkown);
+        assert_eq!(
+            key(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ),
+            Key::Modified(
+                KeyModifiers::CONTROL | KeyModifiers::ALT,
+                KeyCode::Char('x')
+            )
+        );
+        assert_eq!(
+            key(KeyCode::Left, KeyModifiers::SHIFT),
+            Key::Modified(KeyModifiers::SHIFT, KeyCode::Left)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Key::Char('a').to_string(), "a");
+        assert_eq!(Key::Char(' ').to_string(), "space");
+        assert_eq!(Key::Ctrl('s').to_string(), "ctrl-s");
+        assert_eq!(Key::BackTab.to_string(), "shift-tab");
+        assert_eq!(Key::F11.to_string(), "f11");
+        assert_eq!(
+            Key::Modified(
+                KeyModifiers::CONTROL | KeyModifiers::ALT,
+                KeyCode::Char('x')
+            )
+            .to_string(),
+            "ctrl-alt-x"
+        );
+        assert_eq!(
+            Key::Modified(KeyModifiers::CONTROL, KeyCode::Up).to_string(),
+            "ctrl-up"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! A stand-in for Spotify's servers on localhost, for testing the code that talks to them
+
+use rspotify::spotify::client::Spotify;
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+const NOT_FOUND: (&str, &str) = (
+    "404 Not Found",
+    r#"{"error": {"status": 404, "message": "Not found"}}"#,
+);
+
+/// Answers each request with the status and body of the first response whose request line
+/// starts with the given one, like `GET /me/shows`, or a 404 when none do. Returns the url
+/// of the server, like `http://127.0.0.1:1234`, and the raw requests it receives.
+pub fn start(
+    responses: Vec<(&'static str, &'static str, &'static str)>,
+) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let request = read_request(&mut stream);
+
+            let (status, body) = responses
+                .iter()
+                .find(|(request_line, _, _)| request.starts_with(request_line))
+                .map_or(NOT_FOUND, |(_, status, body)| (*status, *body));
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+
+            // The test may not be waiting for the requests
+            let _ = sender.send(request);
+        }
+    });
+
+    (url, receiver)
+}
+
+/// A client for a stand-in of the Web API that answers `responses` with `200 OK`, see `start`.
+/// Only the requests made through `web_api` go to the stand-in.
+pub fn start_api(responses: Vec<(&'static str, &'static str)>) -> (Spotify, Receiver<String>) {
+    let responses = responses
+        .into_iter()
+        .map(|(request_line, body)| (request_line, "200 OK", body))
+        .collect();
+    let (url, requests) = start(responses);
+    let spotify = Spotify::default()
+        .prefix(&format!("{}/", url))
+        .access_token("token")
+        .build();
+    (spotify, requests)
+}
+
+// Reads the headers and, going by its length, the body
+fn read_request(stream: &mut TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+    loop {
+        let read = stream.read(&mut buffer).unwrap();
+        request.extend_from_slice(&buffer[..read]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let content_length = text
+                .lines()
+                .find(|line| line.to_lowercase().starts_with("content-length:"))
+                .and_then(|line| line[15..].trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if request.len() >= header_end + 4 + content_length {
+                break;
+            }
+        }
+        if read == 0 {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&request).to_string()
+}
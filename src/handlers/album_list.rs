@@ -45,9 +45,19 @@ pub fn handler(key: Key, app: &mut App) {
         }
         Key::Enter => {
             if let Some(albums) = app.library.saved_albums.get_results(None) {
-                if let Some(selected_album) = albums.items.get(app.album_list_index) {
+                if let Some(selected_album) = albums.items.get(app.album_list_index).cloned() {
+                    app.get_missing_audio_features(
+                        |tables| &tables.album,
+                        selected_album
+                            .album
+                            .tracks
+                            .items
+                            .iter()
+                            .filter_map(|track| track.id.clone())
+                            .collect(),
+                    );
                     app.selected_album_full = Some(SelectedFullAlbum {
-                        album: selected_album.album.clone(),
+                        album: selected_album.album,
                         selected_index: 0,
                     });
                     app.album_table_context = AlbumTableContext::Full;
@@ -98,78 +98,57 @@ pub fn on_low_press_handler<T>(selection_data: &[T]) -> usize {
     selection_data.len() - 1
 }
 
+// The block that shows the content of the route, next to the sidebar
+pub fn get_main_block(route_id: &RouteId) -> Option<ActiveBlock> {
+    match route_id {
+        RouteId::AlbumTracks => Some(ActiveBlock::AlbumTracks),
+        RouteId::TrackTable => Some(ActiveBlock::TrackTable),
+        RouteId::Podcasts => Some(ActiveBlock::Podcasts),
+        RouteId::PodcastEpisodes => Some(ActiveBlock::EpisodeTable),
+        RouteId::Recommendations => Some(ActiveBlock::TrackTable),
+        RouteId::AlbumList => Some(ActiveBlock::AlbumList),
+        RouteId::MadeForYou => Some(ActiveBlock::MadeForYou),
+        RouteId::Artists => Some(ActiveBlock::Artists),
+        RouteId::RecentlyPlayed => Some(ActiveBlock::RecentlyPlayed),
+        RouteId::Search => Some(ActiveBlock::SearchResultBlock),
+        RouteId::Artist => Some(ActiveBlock::ArtistBlock),
+        RouteId::Home => Some(ActiveBlock::Home),
+        RouteId::Queue => Some(ActiveBlock::Queue),
+        RouteId::RecommendationsBuilder => Some(ActiveBlock::RecommendationsBuilder),
+        RouteId::SelectedDevice => None,
+        RouteId::Error => None,
+        RouteId::Analysis => None,
+    }
+}
+
 pub fn handle_right_event(app: &mut App) {
     match app.get_current_route().hovered_block {
-        ActiveBlock::MyPlaylists | ActiveBlock::Library => match app.get_current_route().id {
-            RouteId::AlbumTracks => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::AlbumTracks),
-                    Some(ActiveBlock::AlbumTracks),
-                );
-            }
-            RouteId::TrackTable => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::TrackTable),
-                    Some(ActiveBlock::TrackTable),
-                );
-            }
-            RouteId::Podcasts => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::Podcasts),
-                    Some(ActiveBlock::Podcasts),
-                );
-            }
-            RouteId::Recommendations => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::TrackTable),
-                    Some(ActiveBlock::TrackTable),
-                );
-            }
-            RouteId::AlbumList => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::AlbumList),
-                    Some(ActiveBlock::AlbumList),
-                );
-            }
-            RouteId::MadeForYou => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::MadeForYou),
-                    Some(ActiveBlock::MadeForYou),
-                );
-            }
-            RouteId::Artists => {
-                app.set_current_route_state(Some(ActiveBlock::Artists), Some(ActiveBlock::Artists));
+        ActiveBlock::MyPlaylists | ActiveBlock::Library => {
+            if let Some(main_block) = get_main_block(&app.get_current_route().id) {
+                app.set_current_route_state(Some(main_block), Some(main_block));
             }
-            RouteId::RecentlyPlayed => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::RecentlyPlayed),
-                    Some(ActiveBlock::RecentlyPlayed),
-                );
-            }
-            RouteId::Search => {
-                app.set_current_route_state(
-                    Some(ActiveBlock::SearchResultBlock),
-                    Some(ActiveBlock::SearchResultBlock),
-                );
-            }
-            RouteId::Artist => app.set_current_route_state(
-                Some(ActiveBlock::ArtistBlock),
-                Some(ActiveBlock::ArtistBlock),
-            ),
-            RouteId::Home => {
-                app.set_current_route_state(Some(ActiveBlock::Home), Some(ActiveBlock::Home));
-            }
-            RouteId::SelectedDevice => {}
-            RouteId::Error => {}
-            RouteId::Analysis => {}
-        },
+        }
         _ => {}
     };
 }
 
 pub fn handle_left_event(app: &mut App) {
     // TODO: This should send you back to either library or playlist based on last selection
-    app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::Library));
+    hover_sidebar(app, ActiveBlock::Library);
+}
+
+// Hovers `preferred` or the other sidebar block when that one is hidden. Without a sidebar the
+// main block of the route is hovered instead.
+pub fn hover_sidebar(app: &mut App, preferred: ActiveBlock) {
+    let sidebar = [preferred, ActiveBlock::Library, ActiveBlock::MyPlaylists];
+    let hovered_block = match sidebar.iter().find(|block| app.is_block_visible(**block)) {
+        Some(block) => Some(*block),
+        None => get_main_block(&app.get_current_route().id),
+    };
+
+    if let Some(hovered_block) = hovered_block {
+        app.set_current_route_state(Some(ActiveBlock::Empty), Some(hovered_block));
+    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,7 @@ use super::common_key_events;
 use crate::{
     app::{ActiveBlock, App},
     event::Key,
+    user_config::PlaybarPosition,
 };
 
 // When no block is actively selected, just handle regular event
@@ -12,28 +13,52 @@ pub fn handler(key: Key, app: &mut App) {
             app.set_current_route_state(Some(current_hovered), None);
         }
         k if common_key_events::down_event(k) => match app.get_current_route().hovered_block {
-            ActiveBlock::Library => {
+            ActiveBlock::Library if app.is_block_visible(ActiveBlock::MyPlaylists) => {
                 app.set_current_route_state(None, Some(ActiveBlock::MyPlaylists));
             }
+            ActiveBlock::PlayBar if is_playbar_at_top(app) => {
+                common_key_events::hover_sidebar(app, ActiveBlock::Library);
+            }
             ActiveBlock::ArtistBlock
             | ActiveBlock::AlbumList
             | ActiveBlock::AlbumTracks
             | ActiveBlock::Artists
             | ActiveBlock::Home
             | ActiveBlock::MadeForYou
+            | ActiveBlock::Library
             | ActiveBlock::MyPlaylists
+            | ActiveBlock::Podcasts
+            | ActiveBlock::EpisodeTable
             | ActiveBlock::RecentlyPlayed
-            | ActiveBlock::TrackTable => {
+            | ActiveBlock::TrackTable
+                if !is_playbar_at_top(app) =>
+            {
                 app.set_current_route_state(None, Some(ActiveBlock::PlayBar));
             }
             _ => {}
         },
         k if common_key_events::up_event(k) => match app.get_current_route().hovered_block {
-            ActiveBlock::MyPlaylists => {
+            ActiveBlock::MyPlaylists if app.is_block_visible(ActiveBlock::Library) => {
                 app.set_current_route_state(None, Some(ActiveBlock::Library));
             }
-            ActiveBlock::PlayBar => {
-                app.set_current_route_state(None, Some(ActiveBlock::MyPlaylists));
+            ActiveBlock::PlayBar if !is_playbar_at_top(app) => {
+                common_key_events::hover_sidebar(app, ActiveBlock::MyPlaylists);
+            }
+            ActiveBlock::ArtistBlock
+            | ActiveBlock::AlbumList
+            | ActiveBlock::AlbumTracks
+            | ActiveBlock::Artists
+            | ActiveBlock::Home
+            | ActiveBlock::MadeForYou
+            | ActiveBlock::Library
+            | ActiveBlock::MyPlaylists
+            | ActiveBlock::Podcasts
+            | ActiveBlock::EpisodeTable
+            | ActiveBlock::RecentlyPlayed
+            | ActiveBlock::TrackTable
+                if is_playbar_at_top(app) =>
+            {
+                app.set_current_route_state(None, Some(ActiveBlock::PlayBar));
             }
             _ => {}
         },
@@ -44,9 +69,11 @@ pub fn handler(key: Key, app: &mut App) {
             | ActiveBlock::Artists
             | ActiveBlock::Home
             | ActiveBlock::MadeForYou
+            | ActiveBlock::Podcasts
+            | ActiveBlock::EpisodeTable
             | ActiveBlock::RecentlyPlayed
             | ActiveBlock::TrackTable => {
-                app.set_current_route_state(None, Some(ActiveBlock::Library));
+                common_key_events::hover_sidebar(app, ActiveBlock::Library);
             }
             _ => {}
         },
@@ -55,6 +82,10 @@ pub fn handler(key: Key, app: &mut App) {
     };
 }
 
+fn is_playbar_at_top(app: &App) -> bool {
+    app.user_config.layout.playbar_position == PlaybarPosition::Top
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,4 +195,57 @@ mod tests {
         assert_eq!(current_route.active_block, ActiveBlock::Home);
         assert_eq!(current_route.hovered_block, ActiveBlock::Home);
     }
+
+    #[test]
+    fn on_navigation_with_hidden_playlists() {
+        let mut app = App::new();
+        app.user_config.layout.show_playlists = false;
+
+        app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::Library));
+        handler(Key::Down, &mut app);
+        assert_eq!(app.get_current_route().hovered_block, ActiveBlock::PlayBar);
+
+        handler(Key::Up, &mut app);
+        assert_eq!(app.get_current_route().hovered_block, ActiveBlock::Library);
+    }
+
+    #[test]
+    fn on_navigation_with_playbar_at_top() {
+        let mut app = App::new();
+        app.user_config.layout.playbar_position = PlaybarPosition::Top;
+
+        app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::Library));
+        handler(Key::Down, &mut app);
+        assert_eq!(
+            app.get_current_route().hovered_block,
+            ActiveBlock::MyPlaylists
+        );
+
+        handler(Key::Down, &mut app);
+        assert_eq!(
+            app.get_current_route().hovered_block,
+            ActiveBlock::MyPlaylists
+        );
+
+        handler(Key::Up, &mut app);
+        handler(Key::Up, &mut app);
+        assert_eq!(app.get_current_route().hovered_block, ActiveBlock::PlayBar);
+
+        handler(Key::Down, &mut app);
+        assert_eq!(app.get_current_route().hovered_block, ActiveBlock::Library);
+    }
+
+    #[test]
+    fn on_left_press_without_sidebar() {
+        let mut app = App::new();
+        app.show_sidebar = false;
+
+        app.push_navigation_stack(RouteId::TrackTable, ActiveBlock::Empty);
+        app.set_current_route_state(None, Some(ActiveBlock::TrackTable));
+        handler(Key::Left, &mut app);
+        assert_eq!(
+            app.get_current_route().hovered_block,
+            ActiveBlock::TrackTable
+        );
+    }
 }
@@ -0,0 +1,101 @@
+use super::{super::app::App, common_key_events};
+use crate::event::Key;
+
+pub fn handler(key: Key, app: &mut App) {
+    let selected_show = match &mut app.selected_show {
+        Some(selected_show) => selected_show,
+        None => {
+            if common_key_events::left_event(key) {
+                common_key_events::handle_left_event(app);
+            }
+            return;
+        }
+    };
+    let episodes = match selected_show.episodes.get_results(None) {
+        Some(episodes) => &episodes.items,
+        None => &[][..],
+    };
+
+    match key {
+        k if common_key_events::left_event(k) => common_key_events::handle_left_event(app),
+        k if common_key_events::down_event(k) => {
+            selected_show.selected_index = common_key_events::on_down_press_handler(
+                episodes,
+                Some(selected_show.selected_index),
+            );
+        }
+        k if common_key_events::up_event(k) => {
+            selected_show.selected_index = common_key_events::on_up_press_handler(
+                episodes,
+                Some(selected_show.selected_index),
+            );
+        }
+        k if common_key_events::high_event(k) => {
+            selected_show.selected_index = common_key_events::on_high_press_handler();
+        }
+        k if common_key_events::middle_event(k) && !episodes.is_empty() => {
+            selected_show.selected_index = common_key_events::on_middle_press_handler(episodes);
+        }
+        k if common_key_events::low_event(k) && !episodes.is_empty() => {
+            selected_show.selected_index = common_key_events::on_low_press_handler(episodes);
+        }
+        Key::Enter => {
+            let index = selected_show.selected_index;
+            app.start_episode_playback(index);
+        }
+        Key::Ctrl('d') => app.get_show_episodes_next(),
+        Key::Ctrl('u') => app.get_show_episodes_previous(),
+        Key::Char('w') if !selected_show.is_saved => app.toggle_selected_show_saved(),
+        Key::Char('D') if selected_show.is_saved => app.toggle_selected_show_saved(),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::{ActiveBlock, RouteId},
+        podcasts::{tests::episode, SimplifiedShow},
+    };
+    use rspotify::spotify::model::page::Page;
+
+    #[test]
+    fn test_move_through_episodes() {
+        let mut app = App::new();
+        let show = SimplifiedShow {
+            id: "show".to_string(),
+            name: "Show".to_string(),
+            publisher: String::new(),
+            total_episodes: 3,
+        };
+        // Without a connection no episodes are loaded, so add a page by hand
+        app.open_show(show, true);
+        assert_eq!(app.get_current_route().id, RouteId::PodcastEpisodes);
+        let selected_show = app.selected_show.as_mut().unwrap();
+        selected_show.episodes.add_pages(Page {
+            href: String::new(),
+            items: vec![episode("1", None), episode("2", None), episode("3", None)],
+            limit: 20,
+            next: None,
+            offset: 0,
+            previous: None,
+            total: 3,
+        });
+
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Char('j'), &mut app);
+        assert_eq!(app.selected_show.as_ref().unwrap().selected_index, 2);
+        handler(Key::Char('j'), &mut app);
+        assert_eq!(app.selected_show.as_ref().unwrap().selected_index, 0);
+        handler(Key::Char('L'), &mut app);
+        assert_eq!(app.selected_show.as_ref().unwrap().selected_index, 2);
+
+        // There is no next page
+        handler(Key::Ctrl('d'), &mut app);
+        assert_eq!(app.selected_show.as_ref().unwrap().selected_index, 2);
+
+        handler(Key::Left, &mut app);
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::Empty);
+    }
+}
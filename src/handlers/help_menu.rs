@@ -1,5 +1,9 @@
 use super::common_key_events;
-use crate::{app::App, event::Key};
+use crate::{
+    app::{ActiveBlock, App},
+    event::Key,
+    ui::help::get_help_rows,
+};
 
 #[derive(PartialEq)]
 enum Direction {
@@ -7,8 +11,34 @@ enum Direction {
     DOWN,
 }
 
+pub fn open(app: &mut App) {
+    let current_route = app.get_current_route();
+    // In hover mode the keys of the hovered block are the interesting ones
+    app.help_menu_block = match current_route.active_block {
+        ActiveBlock::Empty => current_route.hovered_block,
+        active_block => active_block,
+    };
+    app.help_menu_filter.clear();
+    app.help_menu_filter_active = false;
+    app.help_menu_context_only = false;
+    reset_help_menu(app);
+    app.set_current_route_state(Some(ActiveBlock::HelpMenu), None);
+}
+
 pub fn handler(key: Key, app: &mut App) {
+    if app.help_menu_filter_active {
+        handle_filter_input(key, app);
+        return;
+    }
+
     match key {
+        _ if key == app.user_config.keys.search => {
+            app.help_menu_filter_active = true;
+        }
+        Key::Tab => {
+            app.help_menu_context_only = !app.help_menu_context_only;
+            reset_help_menu(app);
+        }
         k if common_key_events::down_event(k) => {
             move_page(Direction::DOWN, app);
         }
@@ -25,6 +55,34 @@ pub fn handler(key: Key, app: &mut App) {
     };
 }
 
+fn handle_filter_input(key: Key, app: &mut App) {
+    match key {
+        Key::Enter | Key::Esc => {
+            app.help_menu_filter_active = false;
+        }
+        Key::Ctrl('u') => {
+            app.help_menu_filter.clear();
+            reset_help_menu(app);
+        }
+        Key::Backspace => {
+            app.help_menu_filter.pop();
+            reset_help_menu(app);
+        }
+        Key::Char(c) => {
+            app.help_menu_filter.push(c);
+            reset_help_menu(app);
+        }
+        _ => {}
+    }
+}
+
+// The number of rows changes with the filter, so start again from the first page
+fn reset_help_menu(app: &mut App) {
+    app.help_docs_size = get_help_rows(app).len() as u32;
+    app.help_menu_page = 0;
+    app.help_menu_offset = 0;
+}
+
 fn move_page(direction: Direction, app: &mut App) {
     if direction == Direction::UP {
         if app.help_menu_page > 0 {
@@ -35,3 +93,62 @@ fn move_page(direction: Direction, app: &mut App) {
     }
     app.calculate_help_menu_offset();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_remembers_hovered_block() {
+        let mut app = App::new();
+        app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::MyPlaylists));
+
+        open(&mut app);
+
+        assert_eq!(app.help_menu_block, ActiveBlock::MyPlaylists);
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::HelpMenu);
+        assert!(app.help_docs_size > 0);
+    }
+
+    #[test]
+    fn test_filter_input() {
+        let mut app = App::new();
+        open(&mut app);
+        let all_rows = app.help_docs_size;
+
+        handler(app.user_config.keys.search, &mut app);
+        assert!(app.help_menu_filter_active);
+
+        handler(Key::Char('q'), &mut app);
+        handler(Key::Char('x'), &mut app);
+        assert_eq!(app.help_menu_filter, "qx");
+        assert_eq!(app.help_docs_size, 0);
+
+        handler(Key::Backspace, &mut app);
+        assert_eq!(app.help_menu_filter, "q");
+
+        handler(Key::Enter, &mut app);
+        assert!(!app.help_menu_filter_active);
+
+        // Keys are no longer typed into the filter once editing stopped
+        handler(Key::Char('j'), &mut app);
+        assert_eq!(app.help_menu_filter, "q");
+
+        handler(app.user_config.keys.search, &mut app);
+        handler(Key::Ctrl('u'), &mut app);
+        assert_eq!(app.help_docs_size, all_rows);
+    }
+
+    #[test]
+    fn test_toggle_context_only() {
+        let mut app = App::new();
+        app.set_current_route_state(Some(ActiveBlock::AlbumList), None);
+        open(&mut app);
+        let all_rows = app.help_docs_size;
+
+        handler(Key::Tab, &mut app);
+
+        assert!(app.help_menu_context_only);
+        assert!(app.help_docs_size < all_rows);
+    }
+}
@@ -1,7 +1,11 @@
 extern crate unicode_width;
 
-use super::super::app::{ActiveBlock, AlbumTableContext, App, RouteId, SelectedFullAlbum};
+use super::{
+    super::app::{ActiveBlock, AlbumTableContext, App, InputPrompt, RouteId, SelectedFullAlbum},
+    common_key_events,
+};
 use crate::event::Key;
+use failure::format_err;
 use rspotify::spotify::senum::Country;
 use std::convert::TryInto;
 use std::str::FromStr;
@@ -40,8 +44,14 @@ pub fn handler(key: Key, app: &mut App) {
                 app.input_cursor_position += compute_character_width(next_c);
             }
         }
+        Key::Esc if app.input_prompt != InputPrompt::Search => {
+            app.close_input_prompt();
+        }
+        Key::Enter if app.input_prompt != InputPrompt::Search => {
+            submit_prompt(app);
+        }
         Key::Esc => {
-            app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::Library));
+            common_key_events::hover_sidebar(app, ActiveBlock::Library);
         }
         Key::Enter => {
             if let (Some(spotify), Some(user)) = (app.spotify.clone(), app.user.clone()) {
@@ -55,6 +65,15 @@ pub fn handler(key: Key, app: &mut App) {
                     let album_id = input_str.trim_start_matches(album_url_prefix);
                     match spotify.album(&album_id) {
                         Ok(album) => {
+                            app.get_missing_audio_features(
+                                |tables| &tables.album,
+                                album
+                                    .tracks
+                                    .items
+                                    .iter()
+                                    .filter_map(|track| track.id.clone())
+                                    .collect(),
+                            );
                             let selected_album = SelectedFullAlbum {
                                 album,
                                 selected_index: 0,
@@ -147,6 +166,46 @@ pub fn handler(key: Key, app: &mut App) {
     }
 }
 
+// Answers a prompt opened with `App::open_input_prompt`
+fn submit_prompt(app: &mut App) {
+    let input: String = app.input.iter().collect();
+    let prompt = app.input_prompt;
+    app.close_input_prompt();
+
+    match prompt {
+        InputPrompt::Seek => app.seek_to_input(&input),
+        InputPrompt::Volume => match parse_volume(&input) {
+            Ok(volume_percent) => app.set_volume(volume_percent),
+            Err(e) => app.handle_error(e),
+        },
+        InputPrompt::RecommendationsSeed => app.add_recommendations_seed(&input),
+        InputPrompt::PlaylistFormText => {
+            if let Some(form) = &mut app.playlist_form {
+                if let Err(e) = form.set_selected_text(&input) {
+                    app.notify(e.to_string());
+                }
+            }
+        }
+        InputPrompt::RecommendationsValue => {
+            if let Err(e) = app.recommendations_builder.set_selected_value(&input) {
+                app.handle_error(e);
+            }
+        }
+        InputPrompt::Search => {}
+    }
+}
+
+fn parse_volume(input: &str) -> Result<u8, failure::Error> {
+    let volume = input.trim().trim_end_matches('%').trim();
+    match volume.parse::<u8>() {
+        Ok(volume_percent) if volume_percent <= 100 => Ok(volume_percent),
+        _ => Err(format_err!(
+            "Invalid volume \"{}\": use a number from 0 to 100",
+            input
+        )),
+    }
+}
+
 fn compute_character_width(character: char) -> u16 {
     UnicodeWidthChar::width(character)
         .unwrap()
@@ -191,6 +250,43 @@ mod tests {
         assert_eq!(current_route.active_block, ActiveBlock::Empty);
     }
 
+    #[test]
+    fn test_input_handler_prompt_goes_back() {
+        let mut app = App::new();
+
+        app.set_current_route_state(
+            Some(ActiveBlock::MyPlaylists),
+            Some(ActiveBlock::MyPlaylists),
+        );
+        app.open_input_prompt(InputPrompt::Seek);
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::Input);
+
+        handler(Key::Char('5'), &mut app);
+        handler(Key::Esc, &mut app);
+
+        let current_route = app.get_current_route();
+        assert_eq!(current_route.active_block, ActiveBlock::MyPlaylists);
+        assert_eq!(app.input_prompt, InputPrompt::Search);
+        assert!(app.input.is_empty());
+
+        // Nothing is playing, so there is nothing to seek in
+        app.open_input_prompt(InputPrompt::Seek);
+        handler(Key::Char('5'), &mut app);
+        handler(Key::Enter, &mut app);
+        assert_eq!(
+            app.get_current_route().active_block,
+            ActiveBlock::MyPlaylists
+        );
+    }
+
+    #[test]
+    fn test_parse_volume() {
+        assert_eq!(parse_volume("40").unwrap(), 40);
+        assert_eq!(parse_volume(" 100 % ").unwrap(), 100);
+        assert!(parse_volume("101").is_err());
+        assert!(parse_volume("loud").is_err());
+    }
+
     #[test]
     fn test_input_handler_on_enter_text() {
         let mut app = App::new();
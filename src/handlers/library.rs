@@ -53,13 +53,16 @@ pub fn handler(key: Key, app: &mut App) {
                         Ok(result) => {
                             app.recently_played.result = Some(result.clone());
 
-                            app.current_user_saved_tracks_contains(
-                                result
-                                    .items
-                                    .iter()
-                                    .filter_map(|item| item.track.id.clone())
-                                    .collect::<Vec<String>>(),
+                            let ids = result
+                                .items
+                                .iter()
+                                .filter_map(|item| item.track.id.clone())
+                                .collect::<Vec<String>>();
+                            app.get_missing_audio_features(
+                                |tables| &tables.recently_played,
+                                ids.clone(),
                             );
+                            app.current_user_saved_tracks_contains(ids);
 
                             app.push_navigation_stack(
                                 RouteId::RecentlyPlayed,
@@ -89,6 +92,7 @@ pub fn handler(key: Key, app: &mut App) {
             }
             // Podcasts,
             5 => {
+                app.refresh_saved_shows();
                 app.push_navigation_stack(RouteId::Podcasts, ActiveBlock::Podcasts);
             }
             // This is required because Rust can't tell if this pattern in exhaustive
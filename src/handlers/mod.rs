@@ -5,24 +5,33 @@ mod artist;
 mod artists;
 mod common_key_events;
 mod empty;
+mod episode_table;
 mod error_screen;
 mod help_menu;
 mod home;
 mod input;
 mod library;
 mod made_for_you;
+mod new_playlist;
 mod playbar;
 mod playlist;
+mod playlist_form;
+mod playlist_picker;
 mod podcasts;
+mod queue;
 mod recently_played;
+mod recommendations_builder;
 mod search_results;
 mod select_device;
 mod track_table;
 
-use super::app::{ActiveBlock, App, ArtistBlock, RouteId, SearchResultBlock};
+use super::app::{ActiveBlock, App, ArtistBlock, InputPrompt, RouteId, SearchResultBlock};
 use crate::event::Key;
 
+pub use help_menu::handler as help_menu_handler;
 pub use input::handler as input_handler;
+pub use playlist_form::handler as playlist_form_handler;
+pub use playlist_picker::handler as playlist_picker_handler;
 
 pub fn handle_app(key: Key, app: &mut App) {
     // First handle any global event and then move to block event
@@ -39,12 +48,21 @@ pub fn handle_app(key: Key, app: &mut App) {
         _ if key == app.user_config.keys.manage_devices => {
             app.handle_get_devices();
         }
+        _ if key == app.user_config.keys.next_device => {
+            app.transfer_playback_to_next_device();
+        }
         _ if key == app.user_config.keys.decrease_volume => {
             app.decrease_volume();
         }
         _ if key == app.user_config.keys.increase_volume => {
             app.increase_volume();
         }
+        _ if key == app.user_config.keys.toggle_mute => {
+            app.toggle_mute();
+        }
+        _ if key == app.user_config.keys.volume_prompt => {
+            app.open_input_prompt(InputPrompt::Volume);
+        }
         // Press space to toggle playback
         _ if key == app.user_config.keys.toggle_playback => {
             app.toggle_playback();
@@ -62,7 +80,7 @@ pub fn handle_app(key: Key, app: &mut App) {
             app.previous_track();
         }
         _ if key == app.user_config.keys.help => {
-            app.set_current_route_state(Some(ActiveBlock::HelpMenu), None);
+            help_menu::open(app);
         }
 
         _ if key == app.user_config.keys.shuffle => {
@@ -71,6 +89,12 @@ pub fn handle_app(key: Key, app: &mut App) {
         _ if key == app.user_config.keys.repeat => {
             app.repeat();
         }
+        // The help menu uses the search key to filter its own docs
+        _ if key == app.user_config.keys.search
+            && app.get_current_route().active_block == ActiveBlock::HelpMenu =>
+        {
+            help_menu::handler(key, app);
+        }
         _ if key == app.user_config.keys.search => {
             app.set_current_route_state(Some(ActiveBlock::Input), Some(ActiveBlock::Input));
         }
@@ -83,6 +107,51 @@ pub fn handle_app(key: Key, app: &mut App) {
         _ if key == app.user_config.keys.audio_analysis => {
             app.get_audio_analysis();
         }
+        _ if key == app.user_config.keys.toggle_sidebar => {
+            handle_toggle_sidebar(app);
+        }
+        _ if key == app.user_config.keys.add_to_queue => {
+            queue::add_selected_track(app, false);
+        }
+        _ if key == app.user_config.keys.play_next => {
+            queue::add_selected_track(app, true);
+        }
+        _ if key == app.user_config.keys.show_queue => {
+            queue::show(app);
+        }
+        _ if key == app.user_config.keys.save_as_playlist => {
+            new_playlist::open(app);
+        }
+        _ if key == app.user_config.keys.toggle_autoplay => {
+            app.toggle_autoplay();
+        }
+        _ if key == app.user_config.keys.add_to_playlist => {
+            playlist_picker::open(app);
+        }
+        _ if key == app.user_config.keys.recommendations_builder => {
+            recommendations_builder::show(app);
+        }
+        _ if key == app.user_config.keys.sleep_timer => {
+            app.cycle_sleep_timer();
+        }
+        _ if key == app.user_config.keys.ab_loop => {
+            app.mark_ab_loop();
+        }
+        _ if key == app.user_config.keys.seek_prompt => {
+            app.open_input_prompt(InputPrompt::Seek);
+        }
+        _ if key == app.user_config.keys.next_section => {
+            app.seek_to_next_section();
+        }
+        _ if key == app.user_config.keys.previous_section => {
+            app.seek_to_previous_section();
+        }
+        // Number keys seek to 0-90% of the track
+        Key::Char(c) if c.is_ascii_digit() => {
+            if let Some(digit) = c.to_digit(10) {
+                app.seek_to_percentage(digit * 10);
+            }
+        }
         _ => handle_block_events(key, app),
     }
 }
@@ -103,6 +172,12 @@ fn handle_block_events(key: Key, app: &mut App) {
         ActiveBlock::MyPlaylists => {
             playlist::handler(key, app);
         }
+        ActiveBlock::PlaylistForm => {
+            playlist_form::handler(key, app);
+        }
+        ActiveBlock::PlaylistPicker => {
+            playlist_picker::handler(key, app);
+        }
         ActiveBlock::TrackTable => {
             track_table::handler(key, app);
         }
@@ -145,9 +220,30 @@ fn handle_block_events(key: Key, app: &mut App) {
         ActiveBlock::Podcasts => {
             podcasts::handler(key, app);
         }
+        ActiveBlock::EpisodeTable => {
+            episode_table::handler(key, app);
+        }
         ActiveBlock::PlayBar => {
             playbar::handler(key, app);
         }
+        ActiveBlock::Queue => {
+            queue::handler(key, app);
+        }
+        ActiveBlock::RecommendationsBuilder => {
+            recommendations_builder::handler(key, app);
+        }
+    }
+}
+
+fn handle_toggle_sidebar(app: &mut App) {
+    app.show_sidebar = !app.show_sidebar;
+
+    // Don't leave the selection on a block that just disappeared
+    let current_route = app.get_current_route();
+    if !app.is_block_visible(current_route.active_block)
+        || !app.is_block_visible(current_route.hovered_block)
+    {
+        common_key_events::hover_sidebar(app, current_route.hovered_block);
     }
 }
 
@@ -0,0 +1,101 @@
+use super::super::app::{ActiveBlock, App, SearchResultBlock};
+
+/// Opens the playlist form to save the tracks of the list in the active block as a new
+/// playlist
+pub fn open(app: &mut App) {
+    let track_uris = get_track_list_uris(app);
+    if track_uris.is_empty() {
+        app.notify("There are no tracks here to save as a playlist".to_string());
+        return;
+    }
+
+    app.open_new_playlist_form(track_uris);
+}
+
+// Local files can't be added to playlists through the API, and a track that is in the list
+// twice is only added once
+fn get_track_list_uris(app: &App) -> Vec<String> {
+    let uris: Vec<&String> = match app.get_current_route().active_block {
+        ActiveBlock::TrackTable => app.track_table.tracks.iter().map(|t| &t.uri).collect(),
+        ActiveBlock::RecentlyPlayed => match &app.recently_played.result {
+            Some(recently_played) => recently_played
+                .items
+                .iter()
+                .map(|item| &item.track.uri)
+                .collect(),
+            None => vec![],
+        },
+        ActiveBlock::SearchResultBlock
+            if app.search_results.hovered_block == SearchResultBlock::SongSearch =>
+        {
+            match &app.search_results.tracks {
+                Some(result) => result.tracks.items.iter().map(|t| &t.uri).collect(),
+                None => vec![],
+            }
+        }
+        ActiveBlock::Queue => app.queue.tracks.iter().map(|t| &t.uri).collect(),
+        _ => vec![],
+    };
+
+    let mut track_uris: Vec<String> = vec![];
+    for uri in uris {
+        if uri.starts_with("spotify:track:") && !track_uris.contains(uri) {
+            track_uris.push(uri.to_owned());
+        }
+    }
+    track_uris
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::RouteId, queue::QueuedTrack};
+
+    fn queued_track(uri: &str) -> QueuedTrack {
+        QueuedTrack {
+            id: None,
+            uri: uri.to_string(),
+            name: uri.to_string(),
+            artists: vec![],
+            album: None,
+            duration_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn test_open_form_with_track_list() {
+        let mut app = App::new();
+        for uri in &[
+            "spotify:track:1",
+            "spotify:local:artist:album:song:100",
+            "spotify:track:2",
+            "spotify:track:1",
+        ] {
+            app.queue.enqueue(queued_track(uri));
+        }
+        app.push_navigation_stack(RouteId::Queue, ActiveBlock::Queue);
+
+        open(&mut app);
+        assert_eq!(
+            app.get_current_route().active_block,
+            ActiveBlock::PlaylistForm
+        );
+        assert_eq!(
+            app.playlist_form.as_ref().unwrap().track_uris,
+            vec!["spotify:track:1", "spotify:track:2"]
+        );
+
+        // Closing the form goes back to the list
+        app.close_playlist_form();
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::Queue);
+    }
+
+    #[test]
+    fn test_nothing_to_save() {
+        let mut app = App::new();
+        app.push_navigation_stack(RouteId::Queue, ActiveBlock::Queue);
+
+        open(&mut app);
+        assert!(app.playlist_form.is_none());
+    }
+}
@@ -2,12 +2,19 @@ use super::{
     super::app::{ActiveBlock, App},
     common_key_events,
 };
-use crate::event::Key;
+use crate::{ab_loop::next_loop_limit, event::Key, user_config::PlaybarPosition};
 
 pub fn handler(key: Key, app: &mut App) {
     match key {
-        k if common_key_events::up_event(k) => {
-            app.set_current_route_state(Some(ActiveBlock::Empty), Some(ActiveBlock::MyPlaylists));
+        k if common_key_events::up_event(k)
+            && app.user_config.layout.playbar_position == PlaybarPosition::Bottom =>
+        {
+            common_key_events::hover_sidebar(app, ActiveBlock::MyPlaylists);
+        }
+        k if common_key_events::down_event(k)
+            && app.user_config.layout.playbar_position == PlaybarPosition::Top =>
+        {
+            common_key_events::hover_sidebar(app, ActiveBlock::Library);
         }
         Key::Char('s') => {
             if let Some(playing_context) = &app.current_playback_context {
@@ -18,6 +25,12 @@ pub fn handler(key: Key, app: &mut App) {
                 }
             }
         }
+        Key::Char('g') => {
+            app.ab_loop.snap = app.ab_loop.snap.next();
+        }
+        Key::Char('x') => {
+            app.ab_loop.limit = next_loop_limit(app.ab_loop.limit);
+        }
         _ => {}
     };
 }
@@ -25,6 +38,7 @@ pub fn handler(key: Key, app: &mut App) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ab_loop::Snap;
 
     #[test]
     fn on_left_press() {
@@ -36,4 +50,16 @@ mod tests {
         assert_eq!(current_route.active_block, ActiveBlock::Empty);
         assert_eq!(current_route.hovered_block, ActiveBlock::MyPlaylists);
     }
+
+    #[test]
+    fn test_ab_loop_settings() {
+        let mut app = App::new();
+        app.set_current_route_state(Some(ActiveBlock::PlayBar), Some(ActiveBlock::PlayBar));
+
+        handler(Key::Char('g'), &mut app);
+        assert_eq!(app.ab_loop.snap, Snap::Bar);
+
+        handler(Key::Char('x'), &mut app);
+        assert_eq!(app.ab_loop.limit, Some(1));
+    }
 }
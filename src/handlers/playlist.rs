@@ -76,15 +76,10 @@ pub fn handler(key: Key, app: &mut App) {
         }
         Key::Char('D') => {
             app.user_unfollow_playlists();
-            if let Some(spotify) = &app.spotify {
-                let playlists = spotify.current_user_playlists(app.large_search_limit, None);
-
-                match playlists {
-                    Ok(p) => app.playlists = Some(p),
-                    Err(e) => app.handle_error(e),
-                };
-            }
+            app.refresh_playlists();
         }
+        Key::Char('N') => app.open_new_playlist_form(vec![]),
+        Key::Char('e') => app.edit_selected_playlist(),
         _ => {}
     }
 }
@@ -0,0 +1,133 @@
+use super::{
+    super::app::{App, InputPrompt},
+    common_key_events,
+};
+use crate::{
+    event::Key,
+    playlist_form::{FormRow, FORM_ROWS},
+};
+
+pub fn handler(key: Key, app: &mut App) {
+    let form = match &mut app.playlist_form {
+        Some(form) => form,
+        None => return,
+    };
+
+    match key {
+        Key::Esc => app.close_playlist_form(),
+        k if k == app.user_config.keys.back => app.close_playlist_form(),
+        k if common_key_events::down_event(k) => {
+            form.selected_index =
+                common_key_events::on_down_press_handler(&FORM_ROWS, Some(form.selected_index));
+        }
+        k if common_key_events::up_event(k) => {
+            form.selected_index =
+                common_key_events::on_up_press_handler(&FORM_ROWS, Some(form.selected_index));
+        }
+        k if common_key_events::high_event(k) => {
+            form.selected_index = common_key_events::on_high_press_handler();
+        }
+        k if common_key_events::low_event(k) => {
+            form.selected_index = common_key_events::on_low_press_handler(&FORM_ROWS);
+        }
+        Key::Enter => match form.selected_row() {
+            FormRow::Name | FormRow::Description => {
+                let text = form.get_selected_text().unwrap_or_default().to_owned();
+                app.open_input_prompt_with(InputPrompt::PlaylistFormText, &text);
+            }
+            FormRow::Public | FormRow::Collaborative => form.toggle_selected(),
+            FormRow::Submit => app.submit_playlist_form(),
+        },
+        Key::Char(' ') => form.toggle_selected(),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::{ActiveBlock, RouteId},
+        fake_server,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_fill_in_form() {
+        let mut app = App::new();
+        app.push_navigation_stack(RouteId::Home, ActiveBlock::MyPlaylists);
+        app.open_new_playlist_form(vec![]);
+        assert_eq!(
+            app.get_current_route().active_block,
+            ActiveBlock::PlaylistForm
+        );
+
+        handler(Key::Enter, &mut app);
+        assert_eq!(app.input_prompt, InputPrompt::PlaylistFormText);
+        app.close_input_prompt();
+        assert_eq!(
+            app.get_current_route().active_block,
+            ActiveBlock::PlaylistForm
+        );
+
+        // Down to collaborative
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Enter, &mut app);
+        assert!(app.playlist_form.as_ref().unwrap().collaborative);
+
+        // Down to submit, which needs a name first
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Enter, &mut app);
+        assert!(app.playlist_form.is_some());
+        assert_eq!(app.get_notification(), Some("A playlist needs a name"));
+
+        handler(Key::Char('q'), &mut app);
+        assert!(app.playlist_form.is_none());
+        assert_eq!(
+            app.get_current_route().active_block,
+            ActiveBlock::MyPlaylists
+        );
+    }
+
+    #[test]
+    fn test_failing_to_add_tracks_keeps_the_created_playlist() {
+        // Adding the tracks isn't answered, so it fails with a 404
+        let (spotify, requests) = fake_server::start_api(vec![(
+            "POST /users/user/playlists ",
+            r#"{"id": "playlist1"}"#,
+        )]);
+        let mut app = App::new();
+        app.spotify = Some(spotify);
+        app.user = Some(
+            serde_json::from_value(json!({
+                "external_urls": {},
+                "href": "",
+                "id": "user",
+                "type": "user",
+                "uri": "spotify:user:user",
+            }))
+            .unwrap(),
+        );
+        app.open_new_playlist_form(vec!["spotify:track:1".to_string()]);
+        app.playlist_form.as_mut().unwrap().name = "Road trip".to_string();
+
+        let form = app.playlist_form.clone().unwrap();
+        let (playlist_id, finished) = app.save_playlist_form(&form).unwrap();
+        assert_eq!(playlist_id, "playlist1");
+        assert_eq!(
+            finished.unwrap_err().to_string(),
+            "adding the tracks failed: Not found (status 404)"
+        );
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("POST /users/user/playlists "));
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("POST /playlists/playlist1/tracks "));
+        assert!(requests.try_recv().is_err());
+    }
+}
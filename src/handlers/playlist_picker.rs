@@ -0,0 +1,109 @@
+use super::{super::app::App, common_key_events, queue::get_selected_track};
+use crate::{event::Key, playlist_picker::PickerPlaylist};
+
+/// Opens the playlist picker for the track selected in the active block, or for the one that
+/// is playing
+pub fn open(app: &mut App) {
+    let track = get_selected_track(app).map(|track| (track.uri, track.name));
+    let track = track.or_else(|| {
+        app.current_playback_context
+            .as_ref()
+            .and_then(|context| context.item.as_ref())
+            .map(|track| (track.uri.to_owned(), track.name.to_owned()))
+    });
+    if let Some((uri, name)) = track {
+        app.open_playlist_picker(&uri, &name);
+    }
+}
+
+pub fn handler(key: Key, app: &mut App) {
+    let picker = match &mut app.playlist_picker {
+        Some(picker) => picker,
+        None => return,
+    };
+
+    if picker.is_filter_active {
+        match key {
+            Key::Enter | Key::Esc => picker.is_filter_active = false,
+            Key::Ctrl('u') => picker.set_filter(String::new()),
+            Key::Backspace => {
+                let mut filter = picker.filter.to_owned();
+                filter.pop();
+                picker.set_filter(filter);
+            }
+            Key::Char(c) => {
+                let filter = format!("{}{}", picker.filter, c);
+                picker.set_filter(filter);
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    let rows: Vec<PickerPlaylist> = picker.get_filtered().into_iter().cloned().collect();
+    match key {
+        Key::Esc => app.close_playlist_picker(),
+        k if k == app.user_config.keys.back => app.close_playlist_picker(),
+        k if k == app.user_config.keys.search => picker.is_filter_active = true,
+        k if common_key_events::down_event(k) => {
+            picker.selected_index =
+                common_key_events::on_down_press_handler(&rows, Some(picker.selected_index));
+        }
+        k if common_key_events::up_event(k) => {
+            picker.selected_index =
+                common_key_events::on_up_press_handler(&rows, Some(picker.selected_index));
+        }
+        k if common_key_events::high_event(k) => {
+            picker.selected_index = common_key_events::on_high_press_handler();
+        }
+        k if common_key_events::middle_event(k) && !rows.is_empty() => {
+            picker.selected_index = common_key_events::on_middle_press_handler(&rows);
+        }
+        k if common_key_events::low_event(k) && !rows.is_empty() => {
+            picker.selected_index = common_key_events::on_low_press_handler(&rows);
+        }
+        Key::Char(' ') => picker.toggle_selected(),
+        Key::Enter => app.add_picked_track_to_playlists(),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::ActiveBlock, playlist_picker::PlaylistPicker};
+
+    fn open_picker(app: &mut App) {
+        let playlists = ["Road trip", "Focus"]
+            .iter()
+            .map(|name| PickerPlaylist {
+                id: name.to_lowercase(),
+                name: name.to_string(),
+            })
+            .collect();
+        app.playlist_picker = Some(PlaylistPicker::new("spotify:track:1", "Song", playlists));
+        app.set_current_route_state(Some(ActiveBlock::PlaylistPicker), None);
+    }
+
+    #[test]
+    fn test_filter_and_choose() {
+        let mut app = App::new();
+        open_picker(&mut app);
+
+        handler(Key::Char('/'), &mut app);
+        // Keys that move the selection are typed into the filter
+        handler(Key::Char('f'), &mut app);
+        handler(Key::Char('o'), &mut app);
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Backspace, &mut app);
+        handler(Key::Enter, &mut app);
+        handler(Key::Char(' '), &mut app);
+
+        let picker = app.playlist_picker.as_ref().unwrap();
+        assert_eq!(picker.filter, "fo");
+        assert_eq!(picker.chosen_ids, vec!["focus"]);
+
+        handler(Key::Esc, &mut app);
+        assert!(app.playlist_picker.is_none());
+    }
+}
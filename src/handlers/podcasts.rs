@@ -4,6 +4,74 @@ use crate::event::Key;
 pub fn handler(key: Key, app: &mut App) {
     match key {
         k if common_key_events::left_event(k) => common_key_events::handle_left_event(app),
+        k if common_key_events::down_event(k) => {
+            if let Some(shows) = app.library.saved_shows.get_results(None) {
+                app.shows_list_index = common_key_events::on_down_press_handler(
+                    &shows.items,
+                    Some(app.shows_list_index),
+                );
+            }
+        }
+        k if common_key_events::up_event(k) => {
+            if let Some(shows) = app.library.saved_shows.get_results(None) {
+                app.shows_list_index = common_key_events::on_up_press_handler(
+                    &shows.items,
+                    Some(app.shows_list_index),
+                );
+            }
+        }
+        k if common_key_events::high_event(k)
+            && app.library.saved_shows.get_results(None).is_some() =>
+        {
+            app.shows_list_index = common_key_events::on_high_press_handler();
+        }
+        k if common_key_events::middle_event(k) => {
+            if let Some(shows) = app.library.saved_shows.get_results(None) {
+                app.shows_list_index = common_key_events::on_middle_press_handler(&shows.items);
+            }
+        }
+        k if common_key_events::low_event(k) => {
+            if let Some(shows) = app.library.saved_shows.get_results(None) {
+                app.shows_list_index = common_key_events::on_low_press_handler(&shows.items);
+            }
+        }
+        Key::Enter => {
+            let selected_show = app
+                .library
+                .saved_shows
+                .get_results(None)
+                .and_then(|shows| shows.items.get(app.shows_list_index))
+                .map(|saved_show| saved_show.show.to_owned());
+            if let Some(show) = selected_show {
+                app.open_show(show, true);
+            }
+        }
+        Key::Ctrl('d') => {
+            app.get_current_user_saved_shows_next();
+            app.shows_list_index = 0;
+        }
+        Key::Ctrl('u') => {
+            app.get_current_user_saved_shows_previous();
+            app.shows_list_index = 0;
+        }
+        Key::Char('D') => app.current_user_saved_show_delete(),
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ActiveBlock;
+
+    #[test]
+    fn on_left_press() {
+        let mut app = App::new();
+        app.set_current_route_state(Some(ActiveBlock::Podcasts), Some(ActiveBlock::Podcasts));
+
+        handler(Key::Left, &mut app);
+        let current_route = app.get_current_route();
+        assert_eq!(current_route.active_block, ActiveBlock::Empty);
+        assert_eq!(current_route.hovered_block, ActiveBlock::Library);
+    }
+}
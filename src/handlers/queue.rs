@@ -0,0 +1,189 @@
+use super::{
+    super::app::{ActiveBlock, AlbumTableContext, App, ArtistBlock, RouteId, SearchResultBlock},
+    common_key_events,
+};
+use crate::{event::Key, queue::QueuedTrack};
+
+pub fn handler(key: Key, app: &mut App) {
+    match key {
+        k if common_key_events::left_event(k) => common_key_events::handle_left_event(app),
+        k if common_key_events::down_event(k) => {
+            app.queue.selected_index = common_key_events::on_down_press_handler(
+                &app.queue.tracks,
+                Some(app.queue.selected_index),
+            );
+        }
+        k if common_key_events::up_event(k) => {
+            app.queue.selected_index = common_key_events::on_up_press_handler(
+                &app.queue.tracks,
+                Some(app.queue.selected_index),
+            );
+        }
+        k if common_key_events::high_event(k) => {
+            app.queue.selected_index = common_key_events::on_high_press_handler();
+        }
+        k if common_key_events::middle_event(k) => {
+            app.queue.selected_index =
+                common_key_events::on_middle_press_handler(&app.queue.tracks);
+        }
+        k if common_key_events::low_event(k) && !app.queue.tracks.is_empty() => {
+            app.queue.selected_index = common_key_events::on_low_press_handler(&app.queue.tracks);
+        }
+        // Play the selected track now
+        Key::Enter => {
+            if let Some(track) = app.queue.remove_selected() {
+                app.queue.play_next(track);
+                app.play_next_in_queue();
+            }
+        }
+        Key::Char('D') => {
+            app.queue.remove_selected();
+        }
+        Key::Char('K') => app.queue.move_selected_up(),
+        Key::Char('J') => app.queue.move_selected_down(),
+        _ => {}
+    }
+}
+
+pub fn show(app: &mut App) {
+    if app.get_current_route().id != RouteId::Queue {
+        app.push_navigation_stack(RouteId::Queue, ActiveBlock::Queue);
+    }
+}
+
+/// Adds the track selected in the active block to the end of the queue, or to the front
+/// when `play_next` is set
+pub fn add_selected_track(app: &mut App, play_next: bool) {
+    if let Some(track) = get_selected_track(app) {
+        if play_next {
+            app.queue.play_next(track);
+        } else {
+            app.queue.enqueue(track);
+        }
+    }
+}
+
+/// The track selected in the active block, if it is a list of tracks
+pub fn get_selected_track(app: &App) -> Option<QueuedTrack> {
+    match app.get_current_route().active_block {
+        ActiveBlock::TrackTable => app
+            .track_table
+            .tracks
+            .get(app.track_table.selected_index)
+            .map(QueuedTrack::from_full_track),
+        ActiveBlock::AlbumTracks => match app.album_table_context {
+            AlbumTableContext::Full => app.selected_album_full.as_ref().and_then(|selected| {
+                selected
+                    .album
+                    .tracks
+                    .items
+                    .get(app.saved_album_tracks_index)
+                    .map(|track| {
+                        QueuedTrack::from_simplified_track(track, Some(&selected.album.name))
+                    })
+            }),
+            AlbumTableContext::Simplified => {
+                app.selected_album_simplified.as_ref().and_then(|selected| {
+                    selected
+                        .tracks
+                        .items
+                        .get(selected.selected_index)
+                        .map(|track| {
+                            QueuedTrack::from_simplified_track(track, Some(&selected.album.name))
+                        })
+                })
+            }
+        },
+        ActiveBlock::SearchResultBlock
+            if app.search_results.selected_block == SearchResultBlock::SongSearch =>
+        {
+            let tracks = app.search_results.tracks.as_ref()?;
+            let index = app.search_results.selected_tracks_index?;
+            tracks
+                .tracks
+                .items
+                .get(index)
+                .map(QueuedTrack::from_full_track)
+        }
+        ActiveBlock::ArtistBlock => {
+            let artist = app.artist.as_ref()?;
+            if artist.artist_selected_block != ArtistBlock::TopTracks {
+                return None;
+            }
+            artist
+                .top_tracks
+                .get(artist.selected_top_track_index)
+                .map(QueuedTrack::from_full_track)
+        }
+        ActiveBlock::RecentlyPlayed => app
+            .recently_played
+            .result
+            .as_ref()?
+            .items
+            .get(app.recently_played.index)
+            .map(|item| QueuedTrack::from_simplified_track(&item.track, None)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspotify::spotify::{model::track::SimplifiedTrack, senum::Type};
+    use std::collections::HashMap;
+
+    fn simplified_track(name: &str) -> SimplifiedTrack {
+        SimplifiedTrack {
+            artists: vec![],
+            available_markets: None,
+            disc_number: 1,
+            duration_ms: 1000,
+            explicit: false,
+            external_urls: HashMap::new(),
+            href: None,
+            id: Some(name.to_string()),
+            is_local: false,
+            name: name.to_string(),
+            preview_url: None,
+            track_number: 1,
+            _type: Type::Track,
+            uri: format!("spotify:track:{}", name),
+        }
+    }
+
+    fn queued_names(app: &App) -> Vec<&str> {
+        app.queue
+            .tracks
+            .iter()
+            .map(|track| track.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_add_selected_track_without_selection() {
+        let mut app = App::new();
+        add_selected_track(&mut app, false);
+        assert!(app.queue.tracks.is_empty());
+    }
+
+    #[test]
+    fn test_queue_view_reorders_and_removes() {
+        let mut app = App::new();
+        for name in &["a", "b", "c"] {
+            app.queue.enqueue(QueuedTrack::from_simplified_track(
+                &simplified_track(name),
+                None,
+            ));
+        }
+        show(&mut app);
+        assert_eq!(app.get_current_route().active_block, ActiveBlock::Queue);
+
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Char('K'), &mut app);
+        assert_eq!(queued_names(&app), vec!["b", "a", "c"]);
+        assert_eq!(app.queue.selected_index, 0);
+
+        handler(Key::Char('D'), &mut app);
+        assert_eq!(queued_names(&app), vec!["a", "c"]);
+    }
+}
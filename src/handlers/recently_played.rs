@@ -1,5 +1,6 @@
 use super::{super::app::App, common_key_events};
 use crate::{app::RecommendationsContext, event::Key};
+use rspotify::spotify::senum::Type;
 
 pub fn handler(key: Key, app: &mut App) {
     match key {
@@ -55,13 +56,27 @@ pub fn handler(key: Key, app: &mut App) {
         }
         Key::Enter => {
             if let Some(recently_played_result) = &app.recently_played.result.clone() {
-                let track_uris: Vec<String> = recently_played_result
-                    .items
-                    .iter()
-                    .map(|item| item.track.uri.to_owned())
-                    .collect();
+                let selected_item = recently_played_result.items.get(app.recently_played.index);
+                // Play the track where it was played from, when that was an album or playlist
+                let context_uri = selected_item
+                    .and_then(|item| item.context.as_ref())
+                    .filter(|context| matches!(context._type, Type::Album | Type::Playlist))
+                    .map(|context| context.uri.to_owned());
 
-                app.start_playback(None, Some(track_uris), Some(app.recently_played.index));
+                match (context_uri, selected_item) {
+                    (Some(context_uri), Some(item)) => {
+                        app.start_context_playback_at_track(context_uri, item.track.uri.to_owned())
+                    }
+                    _ => {
+                        let track_uris: Vec<String> = recently_played_result
+                            .items
+                            .iter()
+                            .map(|item| item.track.uri.to_owned())
+                            .collect();
+
+                        app.start_playback(None, Some(track_uris), Some(app.recently_played.index));
+                    }
+                }
             };
         }
         Key::Char('r') => {
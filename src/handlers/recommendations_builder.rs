@@ -0,0 +1,123 @@
+use super::{
+    super::app::{ActiveBlock, App, InputPrompt, RouteId},
+    common_key_events,
+};
+use crate::{event::Key, recommendations::BuilderRow};
+
+pub fn handler(key: Key, app: &mut App) {
+    let rows = app.recommendations_builder.rows();
+    let builder = &mut app.recommendations_builder;
+    let is_tunable_selected = builder.selected_tunable().is_some();
+
+    match key {
+        k if common_key_events::left_event(k) => match builder.selected_bound.previous() {
+            Some(bound) if is_tunable_selected => builder.selected_bound = bound,
+            _ => common_key_events::handle_left_event(app),
+        },
+        k if common_key_events::right_event(k) && is_tunable_selected => {
+            if let Some(bound) = builder.selected_bound.next() {
+                builder.selected_bound = bound;
+            }
+        }
+        k if common_key_events::down_event(k) => {
+            builder.selected_index =
+                common_key_events::on_down_press_handler(&rows, Some(builder.selected_index));
+        }
+        k if common_key_events::up_event(k) => {
+            builder.selected_index =
+                common_key_events::on_up_press_handler(&rows, Some(builder.selected_index));
+        }
+        k if common_key_events::high_event(k) => {
+            builder.selected_index = common_key_events::on_high_press_handler();
+        }
+        k if common_key_events::middle_event(k) => {
+            builder.selected_index = common_key_events::on_middle_press_handler(&rows);
+        }
+        k if common_key_events::low_event(k) => {
+            builder.selected_index = common_key_events::on_low_press_handler(&rows);
+        }
+        Key::Enter => match builder.selected_row() {
+            Some(BuilderRow::AddSeed) => app.open_input_prompt(InputPrompt::RecommendationsSeed),
+            Some(BuilderRow::Tunable(_)) => {
+                app.open_input_prompt(InputPrompt::RecommendationsValue)
+            }
+            Some(BuilderRow::Seed(_)) | None => {}
+        },
+        Key::Char('x') => {
+            builder.clear_selected();
+            let last_index = builder.rows().len() - 1;
+            builder.selected_index = builder.selected_index.min(last_index);
+        }
+        Key::Char('r') => app.get_builder_recommendations(),
+        _ => {}
+    }
+}
+
+pub fn show(app: &mut App) {
+    if app.get_current_route().id != RouteId::RecommendationsBuilder {
+        app.push_navigation_stack(
+            RouteId::RecommendationsBuilder,
+            ActiveBlock::RecommendationsBuilder,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recommendations::{Bound, Seed, SeedKind, Tunable};
+
+    #[test]
+    fn test_select_and_clear_values() {
+        let mut app = App::new();
+        show(&mut app);
+        app.recommendations_builder
+            .add_seed(Seed {
+                kind: SeedKind::Genre,
+                id: "jazz".to_string(),
+                name: "jazz".to_string(),
+            })
+            .unwrap();
+
+        // The seed, then the row to add another, then energy
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Char('j'), &mut app);
+        handler(Key::Char('l'), &mut app);
+        assert_eq!(app.recommendations_builder.selected_bound, Bound::Max);
+        app.recommendations_builder
+            .set_selected_value("0.7")
+            .unwrap();
+        assert_eq!(
+            app.recommendations_builder.get_tuning(Tunable::Energy).max,
+            Some(0.7)
+        );
+
+        handler(Key::Char('x'), &mut app);
+        assert_eq!(
+            app.recommendations_builder.get_tuning(Tunable::Energy).max,
+            None
+        );
+
+        handler(Key::Enter, &mut app);
+        assert_eq!(app.input_prompt, InputPrompt::RecommendationsValue);
+    }
+
+    #[test]
+    fn test_remove_seed() {
+        let mut app = App::new();
+        show(&mut app);
+        app.recommendations_builder
+            .add_seed(Seed {
+                kind: SeedKind::Genre,
+                id: "jazz".to_string(),
+                name: "jazz".to_string(),
+            })
+            .unwrap();
+
+        handler(Key::Char('x'), &mut app);
+        assert!(app.recommendations_builder.seeds.is_empty());
+
+        handler(Key::Enter, &mut app);
+        assert_eq!(app.input_prompt, InputPrompt::RecommendationsSeed);
+    }
+}
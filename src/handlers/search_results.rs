@@ -236,7 +236,16 @@ fn handle_enter_event_on_selected_block(app: &mut App) {
             if let Some(index) = &app.search_results.selected_tracks_index {
                 if let Some(result) = app.search_results.tracks.clone() {
                     if let Some(track) = result.tracks.items.get(index.to_owned()) {
-                        app.start_playback(None, Some(vec![track.uri.to_owned()]), Some(0));
+                        // Carry on through the album the track is from
+                        match &track.album.uri {
+                            Some(album_uri) => app.start_context_playback_at_track(
+                                album_uri.to_owned(),
+                                track.uri.to_owned(),
+                            ),
+                            None => {
+                                app.start_playback(None, Some(vec![track.uri.to_owned()]), Some(0))
+                            }
+                        }
                     };
                 };
             };
@@ -430,14 +439,7 @@ pub fn handler(key: Key, app: &mut App) {
             SearchResultBlock::ArtistSearch => app.user_follow_artists(),
             SearchResultBlock::PlaylistSearch => {
                 app.user_follow_playlists();
-                if let Some(spotify) = &app.spotify {
-                    let playlists = spotify.current_user_playlists(app.large_search_limit, None);
-
-                    match playlists {
-                        Ok(p) => app.playlists = Some(p),
-                        Err(e) => app.handle_error(e),
-                    }
-                }
+                app.refresh_playlists();
             }
             SearchResultBlock::Empty => {}
         },
@@ -7,7 +7,11 @@ use crate::event::Key;
 pub fn handler(key: Key, app: &mut App) {
     match key {
         Key::Esc => {
-            app.set_current_route_state(Some(ActiveBlock::Library), None);
+            if app.is_block_visible(ActiveBlock::Library) {
+                app.set_current_route_state(Some(ActiveBlock::Library), None);
+            } else {
+                common_key_events::hover_sidebar(app, ActiveBlock::Library);
+            }
         }
         k if common_key_events::down_event(k) => {
             match &app.devices {
@@ -71,18 +75,20 @@ pub fn handler(key: Key, app: &mut App) {
             };
         }
         Key::Enter => {
-            if let (Some(devices), Some(index)) = (&app.devices, app.selected_device_index) {
-                if let Some(device) = &devices.devices.get(index) {
-                    match app.client_config.set_device_id(device.id.clone()) {
-                        Ok(()) => {
-                            app.pop_navigation_stack();
-                        }
-                        Err(e) => {
-                            app.handle_error(e);
-                        }
-                    };
-                }
-            };
+            let device_id = app
+                .devices
+                .as_ref()
+                .zip(app.selected_device_index)
+                .and_then(|(devices, index)| devices.devices.get(index))
+                .map(|device| device.id.to_owned());
+
+            if let Some(device_id) = device_id {
+                app.pop_navigation_stack();
+                app.transfer_playback(&device_id);
+            }
+        }
+        Key::Char('r') => {
+            app.refresh_devices();
         }
         _ => {}
     }
@@ -1,5 +1,5 @@
 use super::{
-    super::app::{App, RecommendationsContext, TrackTable, TrackTableContext},
+    super::app::{App, RecommendationsContext, TrackTableContext},
     common_key_events,
 };
 use crate::event::Key;
@@ -198,109 +198,12 @@ fn jump_to_end(app: &mut App) {
 }
 
 fn on_enter(app: &mut App) {
-    let TrackTable {
-        context,
-        selected_index,
-        tracks,
-    } = &app.track_table;
-    match &context {
-        Some(context) => match context {
-            TrackTableContext::MyPlaylists => {
-                if let Some(_track) = tracks.get(*selected_index) {
-                    let context_uri = match (&app.selected_playlist_index, &app.playlists) {
-                        (Some(selected_playlist_index), Some(playlists)) => {
-                            if let Some(selected_playlist) =
-                                playlists.items.get(selected_playlist_index.to_owned())
-                            {
-                                Some(selected_playlist.uri.to_owned())
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    };
-
-                    app.start_playback(
-                        context_uri,
-                        None,
-                        Some(app.track_table.selected_index + app.playlist_offset as usize),
-                    );
-                };
-            }
-            TrackTableContext::RecommendedTracks => {
-                if let Some(_track) = tracks.get(*selected_index) {
-                    app.start_recommendations_playback(Some(app.track_table.selected_index));
-                };
-            }
-            TrackTableContext::SavedTracks => {
-                if let Some(saved_tracks) = &app.library.saved_tracks.get_results(None) {
-                    let track_uris: Vec<String> = saved_tracks
-                        .items
-                        .iter()
-                        .map(|item| item.track.uri.to_owned())
-                        .collect();
-
-                    app.start_playback(
-                        None,
-                        Some(track_uris),
-                        Some(app.track_table.selected_index),
-                    );
-                };
-            }
-            TrackTableContext::AlbumSearch => {}
-            TrackTableContext::PlaylistSearch => {
-                let TrackTable {
-                    selected_index,
-                    tracks,
-                    ..
-                } = &app.track_table;
-                if let Some(_track) = tracks.get(*selected_index) {
-                    let context_uri = match (
-                        &app.search_results.selected_playlists_index,
-                        &app.search_results.playlists,
-                    ) {
-                        (Some(selected_playlist_index), Some(playlist_result)) => {
-                            if let Some(selected_playlist) = playlist_result
-                                .playlists
-                                .items
-                                .get(selected_playlist_index.to_owned())
-                            {
-                                Some(selected_playlist.uri.to_owned())
-                            } else {
-                                None
-                            }
-                        }
-                        _ => None,
-                    };
-
-                    app.start_playback(context_uri, None, Some(app.track_table.selected_index));
-                };
-            }
-            TrackTableContext::MadeForYou => {
-                if let Some(_track) = tracks.get(*selected_index) {
-                    let context_uri = Some(
-                        app.library
-                            .made_for_you_playlists
-                            .get_results(Some(0))
-                            .unwrap()
-                            .items
-                            .get(app.made_for_you_index)
-                            .unwrap()
-                            .uri
-                            .to_owned(),
-                    );
-
-                    app.start_playback(
-                        context_uri,
-                        None,
-                        Some(app.track_table.selected_index + app.made_for_you_offset as usize),
-                    );
-                }
-            }
-        },
-        None => {}
-    };
+    let selected_index = app.track_table.selected_index;
+    if app.track_table.tracks.get(selected_index).is_some() {
+        app.start_track_table_playback(selected_index);
+    }
 }
+
 fn jump_to_start(app: &mut App) {
     match &app.track_table.context {
         Some(context) => match context {
@@ -1,18 +1,35 @@
+mod ab_loop;
 mod app;
+mod auth;
+mod autoplay;
 mod banner;
 mod config;
 mod event;
+#[cfg(test)]
+mod fake_server;
 mod handlers;
+mod playlist_form;
+mod playlist_picker;
+mod podcasts;
+mod preferred_devices;
+mod qr_code;
+mod queue;
+mod recommendations;
 mod redirect_uri;
+mod seek;
+mod setup;
+mod sleep_timer;
 mod ui;
 mod user_config;
+mod web_api;
 
 use crate::app::RouteId;
 use crate::event::Key;
 use app::{ActiveBlock, App};
+use auth::{request_token, OAuth, SCOPES};
 use backtrace::Backtrace;
 use banner::BANNER;
-use clap::{App as ClapApp, Arg};
+use clap::{App as ClapApp, Arg, ArgMatches, SubCommand};
 use config::ClientConfig;
 use crossterm::{
     cursor::MoveTo,
@@ -22,12 +39,14 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use redirect_uri::redirect_uri_web_server;
+use failure::err_msg;
+use redirect_uri::{redirect_uri_web_server, CallbackError};
 use rspotify::spotify::{
     client::Spotify,
-    oauth2::{SpotifyClientCredentials, SpotifyOAuth, TokenInfo},
-    util::{get_token, process_token, request_token},
+    oauth2::{SpotifyClientCredentials, TokenInfo},
+    util::generate_random_string,
 };
+use sleep_timer::{SleepTimer, SleepTimerSetting};
 use std::{
     cmp::{max, min},
     io::{self, stdout, Write},
@@ -38,23 +57,10 @@ use tui::{
     backend::{Backend, CrosstermBackend},
     Terminal,
 };
-use user_config::UserConfig;
-
-const SCOPES: [&str; 13] = [
-    "playlist-read-collaborative",
-    "playlist-read-private",
-    "playlist-modify-private",
-    "playlist-modify-public",
-    "user-follow-read",
-    "user-follow-modify",
-    "user-library-modify",
-    "user-library-read",
-    "user-modify-playback-state",
-    "user-read-currently-playing",
-    "user-read-playback-state",
-    "user-read-private",
-    "user-read-recently-played",
-];
+use user_config::{PlaybarPosition, UserConfig};
+
+// How long to wait for the browser to redirect back after login
+const AUTH_TIMEOUT: Duration = Duration::from_secs(300);
 
 fn get_spotify(token_info: TokenInfo) -> (Spotify, Instant) {
     let token_expiry = Instant::now()
@@ -73,25 +79,89 @@ fn get_spotify(token_info: TokenInfo) -> (Spotify, Instant) {
     (spotify, token_expiry)
 }
 /// get token automatically with local webserver
-pub fn get_token_auto(spotify_oauth: &mut SpotifyOAuth, port: u16) -> Option<TokenInfo> {
-    match spotify_oauth.get_cached_token() {
+pub fn get_token_auto(oauth: &mut OAuth, port: u16) -> Option<TokenInfo> {
+    let missing_scopes = auth::get_missing_scopes(oauth.cache_path(), &SCOPES);
+    if !missing_scopes.is_empty() {
+        auth::print_missing_scopes(&missing_scopes);
+        return authorize(oauth, port);
+    }
+
+    match oauth.get_cached_token() {
         Some(token_info) => Some(token_info),
-        None => match redirect_uri_web_server(spotify_oauth, port) {
-            Ok(mut url) => process_token(spotify_oauth, &mut url),
-            Err(()) => {
-                println!("Starting webserver failed. Continuing with manual authentication");
-                request_token(spotify_oauth);
-                println!("Enter the URL you were redirected to: ");
-                let mut input = String::new();
-                match io::stdin().read_line(&mut input) {
-                    Ok(_) => process_token(spotify_oauth, &mut input),
-                    Err(_) => None,
+        None => authorize(oauth, port),
+    }
+}
+
+/// Log in through the browser, falling back to pasting the redirect URL
+fn authorize(oauth: &mut OAuth, port: u16) -> Option<TokenInfo> {
+    let state = generate_random_string(16);
+    let auth_url = oauth.get_authorize_url(&state);
+    match redirect_uri_web_server(&auth_url, &state, port, AUTH_TIMEOUT) {
+        Ok(url) => oauth.process_token(&url),
+        Err(CallbackError::Denied(error)) => {
+            println!("Authorization failed: {}", error);
+            None
+        }
+        Err(error) => {
+            if let CallbackError::Bind(e) = error {
+                println!(
+                    "Starting webserver failed ({}). Continuing with manual authentication",
+                    e
+                );
+                request_token(&auth_url);
+            }
+            println!("Enter the URL you were redirected to: ");
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).is_err() {
+                return None;
+            }
+            // A pasted URL needs the same checks as one the server received
+            match auth::parse_redirect_url(&input, &state) {
+                Ok(code) => oauth.get_access_token(&code),
+                Err(e) => {
+                    println!("Authorization failed: {}", e);
+                    None
                 }
             }
-        },
+        }
     }
 }
 
+/// `spt auth`: log in (again) without starting the app
+fn handle_auth_command(matches: &ArgMatches) -> Result<(), failure::Error> {
+    let mut client_config = ClientConfig::new();
+
+    if matches.subcommand_matches("status").is_some() {
+        let config_paths = client_config.get_or_build_paths()?;
+        if !auth::print_status(&config_paths.token_cache_path, &SCOPES) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut user_config = UserConfig::new();
+    user_config.load_config()?;
+    client_config.load_config(&user_config.theme)?;
+    let config_paths = client_config.get_or_build_paths()?;
+    let mut oauth = OAuth::new(
+        &client_config,
+        config_paths.token_cache_path.clone(),
+        &SCOPES.join(" "),
+    );
+
+    if matches.is_present("headless") {
+        auth::login_headless(&oauth, matches.is_present("qr"))?;
+    } else if authorize(&mut oauth, client_config.get_port()).is_none() {
+        return Err(err_msg("Spotify auth failed"));
+    }
+
+    println!(
+        "Logged in, the token is saved to {}",
+        config_paths.token_cache_path.display()
+    );
+    Ok(())
+}
+
 fn close_application() -> Result<(), failure::Error> {
     disable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -144,8 +214,35 @@ fn main() -> Result<(), failure::Error> {
                                .long("tick-rate")
                                .help("Set the tick rate (milliseconds): the lower the number the higher the FPS. It can be nicer to have a lower value when you want to use the audio analysis view of the app. Beware that this comes at a CPU cost!")
                                .takes_value(true))
+         .arg(Arg::with_name("sleep")
+                               .long("sleep")
+                               .value_name("WHEN")
+                               .help("Start a sleep timer that fades out and pauses playback: a number of minutes, `track` for the end of the current track or `context` for the end of the current album or playlist")
+                               .takes_value(true))
+        .subcommand(SubCommand::with_name("auth")
+                               .about("Log in to Spotify without starting the app")
+                               .arg(Arg::with_name("headless")
+                                    .long("headless")
+                                    .help("Don't start a local server or open a browser: print the authorization URL and paste the URL you are redirected to. For machines without a browser, e.g. over SSH"))
+                               .arg(Arg::with_name("qr")
+                                    .long("qr")
+                                    .requires("headless")
+                                    .help("Also show the authorization URL as a QR code"))
+                               .subcommand(SubCommand::with_name("status")
+                                    .about("Show whether you are logged in")))
+        .subcommand(SubCommand::with_name("logout")
+                               .about("Delete the cached Spotify token"))
         .get_matches();
 
+    match matches.subcommand() {
+        ("auth", Some(auth_matches)) => return handle_auth_command(auth_matches),
+        ("logout", Some(_)) => {
+            let config_paths = ClientConfig::new().get_or_build_paths()?;
+            return auth::logout(&config_paths.token_cache_path);
+        }
+        _ => {}
+    }
+
     let mut user_config = UserConfig::new();
     user_config.load_config()?;
 
@@ -160,22 +257,32 @@ fn main() -> Result<(), failure::Error> {
         }
     }
 
-    let mut client_config = ClientConfig::new();
-    client_config.load_config()?;
+    let sleep_timer = match matches.value_of("sleep") {
+        Some(sleep) => Some(SleepTimerSetting::parse(sleep)?),
+        None => None,
+    };
 
+    let mut client_config = ClientConfig::new();
     let config_paths = client_config.get_or_build_paths()?;
+    let is_first_run = !config_paths.config_file_path.exists();
+    client_config.load_config(&user_config.theme)?;
 
     // Start authorization with spotify
-    let mut oauth = SpotifyOAuth::default()
-        .client_id(&client_config.client_id)
-        .client_secret(&client_config.client_secret)
-        .redirect_uri(&client_config.get_redirect_uri())
-        .cache_path(config_paths.token_cache_path)
-        .scope(&SCOPES.join(" "))
-        .build();
+    let mut oauth = OAuth::new(
+        &client_config,
+        config_paths.token_cache_path,
+        &SCOPES.join(" "),
+    );
 
     match get_token_auto(&mut oauth, client_config.get_port()) {
         Some(token_info) => {
+            let (mut spotify, mut token_expiry) = get_spotify(token_info);
+
+            // Finish the setup now that the devices can be listed
+            if is_first_run {
+                setup::run_device_step(&user_config.theme, &spotify, &mut client_config)?;
+            }
+
             // Terminal initialization
             let mut stdout = stdout();
             execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -190,22 +297,19 @@ fn main() -> Result<(), failure::Error> {
             // Initialise app state
             let mut app = App::new();
 
-            let (mut spotify, mut token_expiry) = get_spotify(token_info);
-
             app.client_config = client_config;
             app.user_config = user_config;
 
             app.spotify = Some(spotify);
+            app.sleep_timer = sleep_timer.map(SleepTimer::new);
+            app.autoplay.is_enabled = app.user_config.behavior.autoplay;
 
             app.clipboard_context = clipboard::ClipboardProvider::new().ok();
 
-            app.help_docs_size = ui::help::get_help_docs().len() as u32;
-
-            // Now that spotify is ready, check if the user has already selected a device_id to
-            // play music on, if not send them to the device selection view
-            if app.client_config.device_id.is_none() {
-                app.handle_get_devices();
-            }
+            // Now that spotify is ready, check the device to play music on is still there. If
+            // not, and none of the preferred devices are available either, send them to the
+            // device selection view
+            app.select_startup_device();
 
             let mut is_first_render = true;
 
@@ -222,7 +326,12 @@ fn main() -> Result<(), failure::Error> {
                     app.size = size;
 
                     // Based on the size of the terminal, adjust the search limit.
-                    let potential_limit = max((app.size.height as i32) - 13, 0) as u32;
+                    let potential_limit = max(
+                        (app.size.height as i32)
+                            - 7
+                            - i32::from(app.user_config.layout.playbar_height),
+                        0,
+                    ) as u32;
                     let max_limit = min(potential_limit, 50);
                     app.large_search_limit = min((f32::from(size.height) / 1.4) as u32, max_limit);
                     app.small_search_limit =
@@ -230,30 +339,33 @@ fn main() -> Result<(), failure::Error> {
 
                     // Based on the size of the terminal, adjust how many lines are
                     // dislayed in the help menu
-                    if app.size.height > 8 {
-                        app.help_menu_max_lines = (app.size.height as u32) - 8;
+                    if app.size.height > 11 {
+                        app.help_menu_max_lines = (app.size.height as u32) - 11;
                     } else {
                         app.help_menu_max_lines = 0;
                     }
                 };
 
                 let current_route = app.get_current_route();
-                terminal.draw(|mut f| match current_route.active_block {
-                    ActiveBlock::HelpMenu => {
-                        ui::draw_help_menu(&mut f, &app);
-                    }
-                    ActiveBlock::Error => {
-                        ui::draw_error_screen(&mut f, &app);
-                    }
-                    ActiveBlock::SelectDevice => {
-                        ui::draw_device_list(&mut f, &app);
-                    }
-                    ActiveBlock::Analysis => {
-                        ui::audio_analysis::draw(&mut f, &app);
-                    }
-                    _ => {
-                        ui::draw_main_layout(&mut f, &app);
+                terminal.draw(|mut f| {
+                    match current_route.active_block {
+                        ActiveBlock::HelpMenu => {
+                            ui::draw_help_menu(&mut f, &app);
+                        }
+                        ActiveBlock::Error => {
+                            ui::draw_error_screen(&mut f, &app);
+                        }
+                        ActiveBlock::SelectDevice => {
+                            ui::draw_device_list(&mut f, &app);
+                        }
+                        ActiveBlock::Analysis => {
+                            ui::audio_analysis::draw(&mut f, &app);
+                        }
+                        _ => {
+                            ui::draw_main_layout(&mut f, &app);
+                        }
                     }
+                    ui::draw_notification(&mut f, &app);
                 })?;
 
                 if current_route.active_block == ActiveBlock::Input {
@@ -274,15 +386,21 @@ fn main() -> Result<(), failure::Error> {
                     1
                 };
 
+                // The input box is pushed down when the playbar is drawn above it
+                let input_offset = match app.user_config.layout.playbar_position {
+                    PlaybarPosition::Top => app.user_config.layout.playbar_height,
+                    PlaybarPosition::Bottom => 0,
+                };
+
                 // Put the cursor back inside the input box
                 terminal.backend_mut().execute(MoveTo(
                     cursor_offset + app.input_cursor_position,
-                    cursor_offset,
+                    cursor_offset + input_offset,
                 ))?;
 
                 if Instant::now() > token_expiry {
                     // refresh token
-                    if let Some(new_token_info) = get_token(&mut oauth) {
+                    if let Some(new_token_info) = oauth.get_token() {
                         let (new_spotify, new_token_expiry) = get_spotify(new_token_info);
                         spotify = new_spotify;
                         token_expiry = new_token_expiry;
@@ -307,6 +425,16 @@ fn main() -> Result<(), failure::Error> {
                         // case for the input handler
                         if current_active_block == ActiveBlock::Input {
                             handlers::input_handler(key, &mut app);
+                        } else if current_active_block == ActiveBlock::HelpMenu
+                            && app.help_menu_filter_active
+                        {
+                            handlers::help_menu_handler(key, &mut app);
+                        } else if current_active_block == ActiveBlock::PlaylistForm {
+                            // The form is modal, so it gets the global keys too
+                            handlers::playlist_form_handler(key, &mut app);
+                        } else if current_active_block == ActiveBlock::PlaylistPicker {
+                            // Like the form, and the filter is typed into
+                            handlers::playlist_picker_handler(key, &mut app);
                         } else if key == app.user_config.keys.back {
                             if app.get_current_route().active_block != ActiveBlock::Input {
                                 // Go back through navigation stack when not in search input mode and exit the app if there are no more places to back to
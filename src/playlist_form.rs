@@ -0,0 +1,183 @@
+use failure::format_err;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FormRow {
+    Name,
+    Description,
+    Public,
+    Collaborative,
+    Submit,
+}
+
+// The order here is the order the rows are shown in
+pub const FORM_ROWS: [FormRow; 5] = [
+    FormRow::Name,
+    FormRow::Description,
+    FormRow::Public,
+    FormRow::Collaborative,
+    FormRow::Submit,
+];
+
+impl FormRow {
+    pub fn title(self) -> &'static str {
+        match self {
+            FormRow::Name => "Name",
+            FormRow::Description => "Description",
+            FormRow::Public => "Public",
+            FormRow::Collaborative => "Collaborative",
+            FormRow::Submit => "",
+        }
+    }
+}
+
+/// The details of a playlist to create, or of one of the user's playlists to change
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaylistForm {
+    /// `None` for a new playlist
+    pub playlist_id: Option<String>,
+    pub name: String,
+    pub description: String,
+    pub public: bool,
+    pub collaborative: bool,
+    /// Tracks to add to a new playlist, like when saving a track list as one
+    pub track_uris: Vec<String>,
+    pub selected_index: usize,
+}
+
+impl PlaylistForm {
+    pub fn title(&self) -> String {
+        match self.playlist_id {
+            Some(_) => "Edit playlist".to_string(),
+            None if self.track_uris.is_empty() => "New playlist".to_string(),
+            None => format!("Save {} tracks as a new playlist", self.track_uris.len()),
+        }
+    }
+
+    pub fn submit_title(&self) -> &'static str {
+        match self.playlist_id {
+            Some(_) => "[ Save changes ]",
+            None => "[ Create playlist ]",
+        }
+    }
+
+    pub fn selected_row(&self) -> FormRow {
+        FORM_ROWS[self.selected_index.min(FORM_ROWS.len() - 1)]
+    }
+
+    /// The text of the selected row, when it is one that is typed in
+    pub fn get_selected_text(&self) -> Option<&str> {
+        match self.selected_row() {
+            FormRow::Name => Some(&self.name),
+            FormRow::Description => Some(&self.description),
+            _ => None,
+        }
+    }
+
+    pub fn set_selected_text(&mut self, text: &str) -> Result<(), failure::Error> {
+        match self.selected_row() {
+            FormRow::Name => {
+                validate_name(text)?;
+                self.name = text.trim().to_owned();
+                Ok(())
+            }
+            FormRow::Description => {
+                self.description = text.trim().to_owned();
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Turns public or collaborative on or off. Spotify only lets private playlists be
+    /// collaborative, so turning one on turns the other off.
+    pub fn toggle_selected(&mut self) {
+        match self.selected_row() {
+            FormRow::Public => {
+                self.public = !self.public;
+                if self.public {
+                    self.collaborative = false;
+                }
+            }
+            FormRow::Collaborative => {
+                self.collaborative = !self.collaborative;
+                if self.collaborative {
+                    self.public = false;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the form can be submitted, which a new form can't until it has a name
+    pub fn validate(&self) -> Result<(), failure::Error> {
+        validate_name(&self.name)
+    }
+
+    /// The description to send. An edited playlist always sends it, so it can be cleared.
+    pub fn get_description(&self) -> Option<String> {
+        if self.description.is_empty() && self.playlist_id.is_none() {
+            None
+        } else {
+            Some(self.description.to_owned())
+        }
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), failure::Error> {
+    if name.trim().is_empty() {
+        Err(format_err!("A playlist needs a name"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(form: &mut PlaylistForm, row: FormRow) {
+        form.selected_index = FORM_ROWS.iter().position(|r| *r == row).unwrap();
+    }
+
+    #[test]
+    fn test_public_and_collaborative_exclude_each_other() {
+        let mut form = PlaylistForm::default();
+        select(&mut form, FormRow::Public);
+        form.toggle_selected();
+        assert!(form.public);
+
+        select(&mut form, FormRow::Collaborative);
+        form.toggle_selected();
+        assert!(form.collaborative);
+        assert!(!form.public);
+
+        select(&mut form, FormRow::Public);
+        form.toggle_selected();
+        assert!(form.public);
+        assert!(!form.collaborative);
+    }
+
+    #[test]
+    fn test_set_selected_text() {
+        let mut form = PlaylistForm::default();
+        assert!(form.set_selected_text("  ").is_err());
+        form.set_selected_text(" Focus ").unwrap();
+        assert_eq!(form.name, "Focus");
+
+        select(&mut form, FormRow::Description);
+        assert_eq!(form.get_selected_text(), Some(""));
+        form.set_selected_text("For work").unwrap();
+        assert_eq!(form.description, "For work");
+
+        select(&mut form, FormRow::Public);
+        assert_eq!(form.get_selected_text(), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut form = PlaylistForm::default();
+        assert!(form.validate().is_err());
+        form.set_selected_text("Focus").unwrap();
+        assert!(form.validate().is_ok());
+    }
+}
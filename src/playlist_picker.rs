@@ -0,0 +1,133 @@
+use rspotify::spotify::model::playlist::SimplifiedPlaylist;
+
+/// Whether the user can add tracks to `playlist`: it is theirs, or they were let in on it
+pub fn is_editable(playlist: &SimplifiedPlaylist, user_id: &str) -> bool {
+    playlist.owner.id == user_id || playlist.collaborative
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PickerPlaylist {
+    pub id: String,
+    pub name: String,
+}
+
+/// The playlists to add a track to, chosen from the ones the user can edit
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaylistPicker {
+    pub track_uri: String,
+    pub track_name: String,
+    pub playlists: Vec<PickerPlaylist>,
+    /// Ids of the chosen playlists, in the order they were chosen
+    pub chosen_ids: Vec<String>,
+    pub filter: String,
+    pub is_filter_active: bool,
+    /// Index into the playlists the filter lets through
+    pub selected_index: usize,
+}
+
+impl PlaylistPicker {
+    pub fn new(track_uri: &str, track_name: &str, playlists: Vec<PickerPlaylist>) -> Self {
+        PlaylistPicker {
+            track_uri: track_uri.to_owned(),
+            track_name: track_name.to_owned(),
+            playlists,
+            ..Default::default()
+        }
+    }
+
+    /// The playlists whose name has the filter in it, ignoring case
+    pub fn get_filtered(&self) -> Vec<&PickerPlaylist> {
+        let filter = self.filter.to_lowercase();
+        self.playlists
+            .iter()
+            .filter(|playlist| playlist.name.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    pub fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.selected_index = 0;
+    }
+
+    pub fn is_chosen(&self, playlist_id: &str) -> bool {
+        self.chosen_ids.iter().any(|id| id == playlist_id)
+    }
+
+    /// Chooses the selected playlist, or unchooses it when it is chosen already
+    pub fn toggle_selected(&mut self) {
+        let id = match self.get_filtered().get(self.selected_index) {
+            Some(playlist) => playlist.id.to_owned(),
+            None => return,
+        };
+        if self.is_chosen(&id) {
+            self.chosen_ids.retain(|chosen_id| *chosen_id != id);
+        } else {
+            self.chosen_ids.push(id);
+        }
+    }
+
+    /// The chosen playlists, or the selected one when none are chosen
+    pub fn get_target_playlists(&self) -> Vec<&PickerPlaylist> {
+        if self.chosen_ids.is_empty() {
+            return self
+                .get_filtered()
+                .get(self.selected_index)
+                .map(|playlist| vec![*playlist])
+                .unwrap_or_default();
+        }
+        self.chosen_ids
+            .iter()
+            .filter_map(|id| self.playlists.iter().find(|playlist| playlist.id == *id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picker() -> PlaylistPicker {
+        let playlists = ["Road trip", "Focus", "Running"]
+            .iter()
+            .map(|name| PickerPlaylist {
+                id: name.to_lowercase(),
+                name: name.to_string(),
+            })
+            .collect();
+        PlaylistPicker::new("spotify:track:1", "Song", playlists)
+    }
+
+    fn names(playlists: Vec<&PickerPlaylist>) -> Vec<&str> {
+        playlists
+            .into_iter()
+            .map(|playlist| playlist.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut picker = picker();
+        picker.selected_index = 2;
+        picker.set_filter("R".to_string());
+        assert_eq!(names(picker.get_filtered()), vec!["Road trip", "Running"]);
+        assert_eq!(picker.selected_index, 0);
+    }
+
+    #[test]
+    fn test_get_target_playlists() {
+        let mut picker = picker();
+        picker.selected_index = 1;
+        assert_eq!(names(picker.get_target_playlists()), vec!["Focus"]);
+
+        picker.toggle_selected();
+        picker.set_filter("run".to_string());
+        picker.toggle_selected();
+        assert_eq!(
+            names(picker.get_target_playlists()),
+            vec!["Focus", "Running"]
+        );
+
+        picker.toggle_selected();
+        assert_eq!(names(picker.get_target_playlists()), vec!["Focus"]);
+    }
+}
@@ -0,0 +1,109 @@
+use serde::Deserialize;
+
+// NOTE: rspotify has no models for podcasts, so these only have the fields spotify-tui uses
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SimplifiedShow {
+    pub id: String,
+    pub name: String,
+    pub publisher: String,
+    #[serde(default)]
+    pub total_episodes: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SavedShow {
+    pub show: SimplifiedShow,
+}
+
+/// How far the user got in an episode
+#[derive(Clone, Debug, Deserialize)]
+pub struct ResumePoint {
+    pub fully_played: bool,
+    pub resume_position_ms: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SimplifiedEpisode {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub release_date: String,
+    pub duration_ms: u32,
+    pub uri: String,
+    /// Only there with the `user-read-playback-position` scope
+    pub resume_point: Option<ResumePoint>,
+}
+
+impl SimplifiedEpisode {
+    pub fn is_fully_played(&self) -> bool {
+        self.resume_point
+            .as_ref()
+            .map(|resume_point| resume_point.fully_played)
+            .unwrap_or(false)
+    }
+
+    /// Where the user left off, if they started the episode and didn't finish it
+    pub fn get_resume_position_ms(&self) -> Option<u32> {
+        match &self.resume_point {
+            Some(resume_point)
+                if !resume_point.fully_played
+                    && resume_point.resume_position_ms > 0
+                    && resume_point.resume_position_ms < self.duration_ms =>
+            {
+                Some(resume_point.resume_position_ms)
+            }
+            _ => None,
+        }
+    }
+
+    /// Where to start playing: where it was left off, or the beginning
+    pub fn get_start_position_ms(&self) -> u32 {
+        self.get_resume_position_ms().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn episode(id: &str, resume_point: Option<ResumePoint>) -> SimplifiedEpisode {
+        SimplifiedEpisode {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            release_date: "2020-05-01".to_string(),
+            duration_ms: 60_000,
+            uri: format!("spotify:episode:{}", id),
+            resume_point,
+        }
+    }
+
+    #[test]
+    fn test_get_start_position_ms() {
+        let started = episode(
+            "1",
+            Some(ResumePoint {
+                fully_played: false,
+                resume_position_ms: 30_000,
+            }),
+        );
+        assert_eq!(started.get_resume_position_ms(), Some(30_000));
+        assert_eq!(started.get_start_position_ms(), 30_000);
+
+        // Finished episodes play again from the start
+        let played = episode(
+            "1",
+            Some(ResumePoint {
+                fully_played: true,
+                resume_position_ms: 30_000,
+            }),
+        );
+        assert!(played.is_fully_played());
+        assert_eq!(played.get_start_position_ms(), 0);
+
+        let unknown = episode("1", None);
+        assert!(!unknown.is_fully_played());
+        assert_eq!(unknown.get_start_position_ms(), 0);
+    }
+}
@@ -0,0 +1,68 @@
+use rspotify::spotify::model::device::Device;
+
+/// Whether `device` is what an entry of `preferred_devices` asks for: either its name or its
+/// type, like `computer` or `speaker`. Case doesn't matter.
+pub fn matches_preference(device: &Device, preference: &str) -> bool {
+    let preference = preference.trim().to_lowercase();
+    device.name.to_lowercase() == preference
+        || format!("{:?}", device._type).to_lowercase() == preference
+}
+
+/// The device matching the earliest preference. Restricted devices can't be controlled, so
+/// they are never picked.
+pub fn find_preferred_device<'a>(
+    devices: &'a [Device],
+    preferences: &[String],
+) -> Option<&'a Device> {
+    preferences.iter().find_map(|preference| {
+        devices
+            .iter()
+            .find(|device| !device.is_restricted && matches_preference(device, preference))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspotify::spotify::senum::DeviceType;
+
+    fn device(name: &str, device_type: DeviceType) -> Device {
+        Device {
+            id: name.to_lowercase(),
+            is_active: false,
+            is_restricted: false,
+            name: name.to_string(),
+            _type: device_type,
+            volume_percent: 50,
+        }
+    }
+
+    #[test]
+    fn test_matches_preference() {
+        let kitchen = device("Kitchen", DeviceType::Speaker);
+        assert!(matches_preference(&kitchen, "kitchen"));
+        assert!(matches_preference(&kitchen, " Speaker "));
+        assert!(!matches_preference(&kitchen, "computer"));
+    }
+
+    #[test]
+    fn test_find_preferred_device() {
+        let mut phone = device("Phone", DeviceType::Smartphone);
+        let devices = vec![
+            device("Laptop", DeviceType::Computer),
+            device("Kitchen", DeviceType::Speaker),
+        ];
+        let preferences = vec![
+            "Living room".to_string(),
+            "speaker".to_string(),
+            "Laptop".to_string(),
+        ];
+
+        let preferred = find_preferred_device(&devices, &preferences).unwrap();
+        assert_eq!(preferred.name, "Kitchen");
+        assert!(find_preferred_device(&devices, &[]).is_none());
+
+        phone.is_restricted = true;
+        assert!(find_preferred_device(&[phone], &["Phone".to_string()]).is_none());
+    }
+}
@@ -0,0 +1,80 @@
+//! Shows the authorization URL as a QR code in the terminal when logging in on a machine
+//! without a browser.
+
+use qrcode::{Color, EcLevel};
+
+pub struct QrCode {
+    pub size: usize,
+    modules: Vec<Color>,
+}
+
+impl QrCode {
+    /// Encodes the data with the low error correction level, which keeps the code as small
+    /// as possible. `None` when the data is too long for any version.
+    pub fn encode(data: &[u8]) -> Option<QrCode> {
+        let code = qrcode::QrCode::with_error_correction_level(data, EcLevel::L).ok()?;
+        Some(QrCode {
+            size: code.width(),
+            modules: code.to_colors(),
+        })
+    }
+
+    /// Whether the module at column `x` and row `y` is dark
+    pub fn get_module(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x] == Color::Dark
+    }
+
+    /// Renders two rows of modules per line with half block characters, surrounded by a quiet
+    /// zone. Like `qrencode -t utf8`, light modules are drawn with the foreground colour so
+    /// the code scans on the usual light-on-dark terminal.
+    pub fn to_terminal_string(&self) -> String {
+        let quiet_zone = 2;
+        let size = self.size as isize;
+        let is_light = |x: isize, y: isize| {
+            x < 0 || y < 0 || x >= size || y >= size || !self.get_module(x as usize, y as usize)
+        };
+
+        let mut result = String::new();
+        let mut y = -quiet_zone;
+        while y < size + quiet_zone {
+            for x in -quiet_zone..size + quiet_zone {
+                result.push(match (is_light(x, y), is_light(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                });
+            }
+            result.push('\n');
+            y += 2;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_too_long() {
+        assert!(QrCode::encode(&[b'a'; 3000]).is_none());
+    }
+
+    #[test]
+    fn test_to_terminal_string() {
+        let qr_code = QrCode::encode(b"spotify-tui").unwrap();
+        let rendered = qr_code.to_terminal_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        // The size is always odd, so the last line has an empty bottom half
+        assert_eq!(lines.len() * 2, qr_code.size + 4 + 1);
+        assert!(lines
+            .iter()
+            .all(|line| line.chars().count() == qr_code.size + 4));
+        // The quiet zone is light, and the top left finder pattern is dark
+        assert!(lines[0].chars().all(|c| c == '█'));
+        assert!(qr_code.get_module(0, 0));
+        assert_eq!(lines[1].chars().nth(2), Some(' '));
+    }
+}
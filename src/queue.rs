@@ -0,0 +1,253 @@
+use rspotify::spotify::model::{
+    artist::SimplifiedArtist,
+    track::{FullTrack, SimplifiedTrack},
+};
+
+// How close to the end of a track the next queued track is started. Starting it a little
+// early stops Spotify from moving on to the next track of the context first.
+pub const ADVANCE_MARGIN_MS: u128 = 1_000;
+
+#[derive(Clone, Debug)]
+pub struct QueuedTrack {
+    pub id: Option<String>,
+    pub uri: String,
+    pub name: String,
+    pub artists: Vec<SimplifiedArtist>,
+    pub album: Option<String>,
+    pub duration_ms: u32,
+}
+
+impl QueuedTrack {
+    pub fn from_full_track(track: &FullTrack) -> QueuedTrack {
+        QueuedTrack {
+            id: track.id.clone(),
+            uri: track.uri.clone(),
+            name: track.name.clone(),
+            artists: track.artists.clone(),
+            album: Some(track.album.name.clone()),
+            duration_ms: track.duration_ms,
+        }
+    }
+
+    pub fn from_simplified_track(track: &SimplifiedTrack, album: Option<&str>) -> QueuedTrack {
+        QueuedTrack {
+            id: track.id.clone(),
+            uri: track.uri.clone(),
+            name: track.name.clone(),
+            artists: track.artists.clone(),
+            album: album.map(str::to_owned),
+            duration_ms: track.duration_ms,
+        }
+    }
+}
+
+/// Where playback continues once the queue has been played
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResumePoint {
+    pub context_uri: Option<String>,
+    pub uris: Option<Vec<String>>,
+    /// Position of the track in `uris`
+    pub position: Option<usize>,
+    /// The track that was playing when the queue took over, playback continues after it
+    pub track_uri: String,
+    pub track_duration_ms: u32,
+}
+
+/// Tracks to play before the rest of the current context, kept by the client because the
+/// Spotify API has no way to edit the player's own queue
+#[derive(Default)]
+pub struct Queue {
+    pub tracks: Vec<QueuedTrack>,
+    pub selected_index: usize,
+    pub resume: Option<ResumePoint>,
+    /// Whether the playing track was started from the queue
+    pub is_playing_queued: bool,
+    has_advanced: bool,
+}
+
+impl Queue {
+    pub fn enqueue(&mut self, track: QueuedTrack) {
+        self.tracks.push(track);
+    }
+
+    pub fn play_next(&mut self, track: QueuedTrack) {
+        self.tracks.insert(0, track);
+        // Keep the selection on the same track
+        if self.tracks.len() > 1 {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn pop_next(&mut self) -> Option<QueuedTrack> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        self.selected_index = self.selected_index.saturating_sub(1);
+        Some(self.tracks.remove(0))
+    }
+
+    pub fn remove_selected(&mut self) -> Option<QueuedTrack> {
+        if self.selected_index >= self.tracks.len() {
+            return None;
+        }
+        let track = self.tracks.remove(self.selected_index);
+        if self.selected_index >= self.tracks.len() {
+            self.selected_index = self.tracks.len().saturating_sub(1);
+        }
+        Some(track)
+    }
+
+    pub fn move_selected_up(&mut self) {
+        if self.selected_index > 0 && self.selected_index < self.tracks.len() {
+            self.tracks
+                .swap(self.selected_index, self.selected_index - 1);
+            self.selected_index -= 1;
+        }
+    }
+
+    pub fn move_selected_down(&mut self) {
+        if self.selected_index + 1 < self.tracks.len() {
+            self.tracks
+                .swap(self.selected_index, self.selected_index + 1);
+            self.selected_index += 1;
+        }
+    }
+
+    /// Whether there is a queued track, or a context to go back to after the queue
+    pub fn has_next(&self) -> bool {
+        !self.tracks.is_empty() || (self.is_playing_queued && self.resume.is_some())
+    }
+
+    /// Whether the playing track is about to end and the queue should take over. Only
+    /// returns `true` once per track.
+    pub fn should_advance(&mut self, is_playing: bool, remaining_ms: u128) -> bool {
+        if remaining_ms > ADVANCE_MARGIN_MS {
+            self.has_advanced = false;
+            return false;
+        }
+
+        if is_playing && self.has_next() && !self.has_advanced {
+            self.has_advanced = true;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(name: &str) -> QueuedTrack {
+        QueuedTrack {
+            id: Some(name.to_string()),
+            uri: format!("spotify:track:{}", name),
+            name: name.to_string(),
+            artists: vec![],
+            album: None,
+            duration_ms: 180_000,
+        }
+    }
+
+    fn names(queue: &Queue) -> Vec<&str> {
+        queue
+            .tracks
+            .iter()
+            .map(|track| track.name.as_str())
+            .collect()
+    }
+
+    fn pop_name(queue: &mut Queue) -> Option<String> {
+        queue.pop_next().map(|track| track.name)
+    }
+
+    #[test]
+    fn test_enqueue_and_play_next() {
+        let mut queue = Queue::default();
+        queue.enqueue(track("a"));
+        queue.enqueue(track("b"));
+        queue.play_next(track("c"));
+        assert_eq!(names(&queue), vec!["c", "a", "b"]);
+
+        assert_eq!(pop_name(&mut queue), Some("c".to_string()));
+        assert_eq!(pop_name(&mut queue), Some("a".to_string()));
+        assert_eq!(pop_name(&mut queue), Some("b".to_string()));
+        assert_eq!(pop_name(&mut queue), None);
+    }
+
+    #[test]
+    fn test_selection_follows_track() {
+        let mut queue = Queue::default();
+        queue.enqueue(track("a"));
+        queue.enqueue(track("b"));
+        queue.selected_index = 1;
+
+        queue.play_next(track("c"));
+        assert_eq!(queue.tracks[queue.selected_index].name, "b");
+
+        queue.pop_next();
+        assert_eq!(queue.tracks[queue.selected_index].name, "b");
+    }
+
+    #[test]
+    fn test_reorder_and_remove() {
+        let mut queue = Queue::default();
+        for name in &["a", "b", "c"] {
+            queue.enqueue(track(name));
+        }
+
+        queue.move_selected_up();
+        assert_eq!(names(&queue), vec!["a", "b", "c"]);
+
+        queue.move_selected_down();
+        assert_eq!(names(&queue), vec!["b", "a", "c"]);
+        assert_eq!(queue.selected_index, 1);
+
+        queue.selected_index = 2;
+        queue.move_selected_down();
+        assert_eq!(names(&queue), vec!["b", "a", "c"]);
+        queue.move_selected_up();
+        assert_eq!(names(&queue), vec!["b", "c", "a"]);
+
+        queue.selected_index = 2;
+        assert_eq!(queue.remove_selected().unwrap().name, "a");
+        assert_eq!(queue.selected_index, 1);
+        queue.remove_selected();
+        queue.remove_selected();
+        assert!(queue.remove_selected().is_none());
+    }
+
+    #[test]
+    fn test_should_advance_once_per_track() {
+        let mut queue = Queue::default();
+        assert!(!queue.should_advance(true, 0));
+
+        queue.enqueue(track("a"));
+        assert!(!queue.should_advance(true, 30_000));
+        assert!(!queue.should_advance(false, 500));
+        assert!(queue.should_advance(true, 500));
+        assert!(!queue.should_advance(true, 250));
+
+        // The next track started
+        assert!(!queue.should_advance(true, 180_000));
+        assert!(queue.should_advance(true, 0));
+    }
+
+    #[test]
+    fn test_should_advance_to_resume_point() {
+        let mut queue = Queue {
+            resume: Some(ResumePoint {
+                context_uri: Some("spotify:album:1".to_string()),
+                uris: None,
+                position: None,
+                track_uri: "spotify:track:1".to_string(),
+                track_duration_ms: 200_000,
+            }),
+            ..Default::default()
+        };
+        assert!(!queue.should_advance(true, 0));
+
+        queue.is_playing_queued = true;
+        assert!(queue.should_advance(true, 0));
+    }
+}
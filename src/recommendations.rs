@@ -0,0 +1,535 @@
+use failure::format_err;
+use serde_json::{map::Map, Number, Value};
+use std::fmt;
+
+/// Spotify takes at most this many artist, track and genre seeds together
+pub const MAX_SEEDS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeedKind {
+    Artist,
+    Track,
+    Genre,
+}
+
+impl fmt::Display for SeedKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeedKind::Artist => write!(f, "Artist"),
+            SeedKind::Track => write!(f, "Track"),
+            SeedKind::Genre => write!(f, "Genre"),
+        }
+    }
+}
+
+/// An artist, track or genre the recommendations are based on. For genres the id is the
+/// genre itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Seed {
+    pub kind: SeedKind,
+    pub id: String,
+    pub name: String,
+}
+
+/// Splits what was typed into the seed prompt into the kind of seed and what to look for.
+/// `track:` and `genre:` ask for those, anything else is an artist.
+pub fn parse_seed_query(input: &str) -> Result<(SeedKind, String), failure::Error> {
+    let input = input.trim();
+    let (kind, query) = match input.find(':') {
+        Some(index) => match input[..index].trim().to_lowercase().as_str() {
+            "artist" => (SeedKind::Artist, &input[index + 1..]),
+            "track" => (SeedKind::Track, &input[index + 1..]),
+            "genre" => (SeedKind::Genre, &input[index + 1..]),
+            _ => (SeedKind::Artist, input),
+        },
+        None => (SeedKind::Artist, input),
+    };
+
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(format_err!(
+            "Type an artist name, track:<name> or genre:<name> to add a seed"
+        ));
+    }
+    Ok((kind, query.to_owned()))
+}
+
+/// The audio features and popularity the recommendations can be tuned by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tunable {
+    Energy,
+    Danceability,
+    Valence,
+    Tempo,
+    Acousticness,
+    Instrumentalness,
+    Speechiness,
+    Liveness,
+    Loudness,
+    Popularity,
+}
+
+// The order the builder shows them in
+const TUNABLES: [Tunable; 10] = [
+    Tunable::Energy,
+    Tunable::Danceability,
+    Tunable::Valence,
+    Tunable::Tempo,
+    Tunable::Acousticness,
+    Tunable::Instrumentalness,
+    Tunable::Speechiness,
+    Tunable::Liveness,
+    Tunable::Loudness,
+    Tunable::Popularity,
+];
+
+impl Tunable {
+    /// The name of the attribute in the recommendations request, after `min_`, `max_` or
+    /// `target_`
+    pub fn attribute(self) -> &'static str {
+        match self {
+            Tunable::Energy => "energy",
+            Tunable::Danceability => "danceability",
+            Tunable::Valence => "valence",
+            Tunable::Tempo => "tempo",
+            Tunable::Acousticness => "acousticness",
+            Tunable::Instrumentalness => "instrumentalness",
+            Tunable::Speechiness => "speechiness",
+            Tunable::Liveness => "liveness",
+            Tunable::Loudness => "loudness",
+            Tunable::Popularity => "popularity",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tunable::Energy => "Energy",
+            Tunable::Danceability => "Danceability",
+            Tunable::Valence => "Valence (positivity)",
+            Tunable::Tempo => "Tempo (BPM)",
+            Tunable::Acousticness => "Acousticness",
+            Tunable::Instrumentalness => "Instrumentalness",
+            Tunable::Speechiness => "Speechiness",
+            Tunable::Liveness => "Liveness",
+            Tunable::Loudness => "Loudness (dB)",
+            Tunable::Popularity => "Popularity",
+        }
+    }
+
+    /// The values Spotify accepts, from lowest to highest
+    pub fn range(self) -> (f64, f64) {
+        match self {
+            Tunable::Tempo => (0.0, 250.0),
+            Tunable::Loudness => (-60.0, 0.0),
+            Tunable::Popularity => (0.0, 100.0),
+            _ => (0.0, 1.0),
+        }
+    }
+
+    pub fn format_value(self, value: f64) -> String {
+        match self {
+            Tunable::Tempo | Tunable::Loudness | Tunable::Popularity => format!("{:.0}", value),
+            _ => format!("{:.2}", value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    Min,
+    Target,
+    Max,
+}
+
+impl Bound {
+    fn prefix(self) -> &'static str {
+        match self {
+            Bound::Min => "min_",
+            Bound::Target => "target_",
+            Bound::Max => "max_",
+        }
+    }
+
+    pub fn previous(self) -> Option<Bound> {
+        match self {
+            Bound::Min => None,
+            Bound::Target => Some(Bound::Min),
+            Bound::Max => Some(Bound::Target),
+        }
+    }
+
+    pub fn next(self) -> Option<Bound> {
+        match self {
+            Bound::Min => Some(Bound::Target),
+            Bound::Target => Some(Bound::Max),
+            Bound::Max => None,
+        }
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bound::Min => write!(f, "minimum"),
+            Bound::Target => write!(f, "target"),
+            Bound::Max => write!(f, "maximum"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tuning {
+    pub min: Option<f64>,
+    pub target: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Tuning {
+    pub fn get(&self, bound: Bound) -> Option<f64> {
+        match bound {
+            Bound::Min => self.min,
+            Bound::Target => self.target,
+            Bound::Max => self.max,
+        }
+    }
+
+    fn set(&mut self, bound: Bound, value: Option<f64>) {
+        match bound {
+            Bound::Min => self.min = value,
+            Bound::Target => self.target = value,
+            Bound::Max => self.max = value,
+        }
+    }
+}
+
+/// A line of the builder
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BuilderRow {
+    Seed(usize),
+    /// Shown while there is room for another seed
+    AddSeed,
+    Tunable(Tunable),
+}
+
+/// The seeds and tuning of the recommendations asked for in the recommendations builder
+pub struct RecommendationsBuilder {
+    pub seeds: Vec<Seed>,
+    tunings: Vec<(Tunable, Tuning)>,
+    pub selected_index: usize,
+    /// The column selected in the rows of the tunables
+    pub selected_bound: Bound,
+}
+
+impl Default for RecommendationsBuilder {
+    fn default() -> Self {
+        RecommendationsBuilder {
+            seeds: vec![],
+            tunings: TUNABLES
+                .iter()
+                .map(|tunable| (*tunable, Tuning::default()))
+                .collect(),
+            selected_index: 0,
+            selected_bound: Bound::Target,
+        }
+    }
+}
+
+impl RecommendationsBuilder {
+    pub fn rows(&self) -> Vec<BuilderRow> {
+        let mut rows: Vec<BuilderRow> = (0..self.seeds.len()).map(BuilderRow::Seed).collect();
+        if self.seeds.len() < MAX_SEEDS {
+            rows.push(BuilderRow::AddSeed);
+        }
+        rows.extend(TUNABLES.iter().map(|tunable| BuilderRow::Tunable(*tunable)));
+        rows
+    }
+
+    pub fn selected_row(&self) -> Option<BuilderRow> {
+        self.rows().get(self.selected_index).copied()
+    }
+
+    pub fn selected_tunable(&self) -> Option<Tunable> {
+        match self.selected_row() {
+            Some(BuilderRow::Tunable(tunable)) => Some(tunable),
+            _ => None,
+        }
+    }
+
+    pub fn get_tuning(&self, tunable: Tunable) -> Tuning {
+        self.tunings
+            .iter()
+            .find(|(t, _)| *t == tunable)
+            .map_or_else(Tuning::default, |(_, tuning)| *tuning)
+    }
+
+    fn get_tuning_mut(&mut self, tunable: Tunable) -> &mut Tuning {
+        let index = TUNABLES
+            .iter()
+            .position(|t| *t == tunable)
+            .expect("every tunable has a tuning");
+        &mut self.tunings[index].1
+    }
+
+    pub fn add_seed(&mut self, seed: Seed) -> Result<(), failure::Error> {
+        if self.seeds.contains(&seed) {
+            return Ok(());
+        }
+        if self.seeds.len() >= MAX_SEEDS {
+            return Err(format_err!(
+                "Recommendations can have at most {} seeds, remove one first",
+                MAX_SEEDS
+            ));
+        }
+        self.seeds.push(seed);
+        Ok(())
+    }
+
+    /// Starts over from `seeds`, keeping the tuning
+    pub fn set_seeds(&mut self, seeds: Vec<Seed>) {
+        self.seeds = seeds;
+        self.seeds.truncate(MAX_SEEDS);
+        self.selected_index = 0;
+    }
+
+    /// Removes the selected seed, or clears the selected value of a tunable
+    pub fn clear_selected(&mut self) {
+        match self.selected_row() {
+            Some(BuilderRow::Seed(index)) => {
+                self.seeds.remove(index);
+            }
+            Some(BuilderRow::Tunable(tunable)) => {
+                let bound = self.selected_bound;
+                self.get_tuning_mut(tunable).set(bound, None);
+            }
+            Some(BuilderRow::AddSeed) | None => {}
+        }
+    }
+
+    /// Sets the selected value of the selected tunable to what was typed into the prompt. An
+    /// empty value clears it.
+    pub fn set_selected_value(&mut self, input: &str) -> Result<(), failure::Error> {
+        let tunable = match self.selected_tunable() {
+            Some(tunable) => tunable,
+            None => return Ok(()),
+        };
+        let bound = self.selected_bound;
+        let (low, high) = tunable.range();
+
+        let input = input.trim();
+        let value = if input.is_empty() {
+            None
+        } else {
+            match input.parse::<f64>() {
+                Ok(value) if value >= low && value <= high => Some(value),
+                _ => {
+                    return Err(format_err!(
+                        "Invalid {} {}: use a number from {} to {}",
+                        tunable.attribute(),
+                        bound,
+                        tunable.format_value(low),
+                        tunable.format_value(high)
+                    ))
+                }
+            }
+        };
+
+        let mut tuning = self.get_tuning(tunable);
+        tuning.set(bound, value);
+        let set_values: Vec<f64> = [tuning.min, tuning.target, tuning.max]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        if set_values.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(format_err!(
+                "The {} minimum, target and maximum have to go from low to high",
+                tunable.attribute()
+            ));
+        }
+
+        *self.get_tuning_mut(tunable) = tuning;
+        Ok(())
+    }
+
+    /// What the value prompt asks for
+    pub fn get_value_prompt(&self) -> String {
+        match self.selected_tunable() {
+            Some(tunable) => {
+                let (low, high) = tunable.range();
+                format!(
+                    "{} {} ({} to {}, empty to clear)",
+                    tunable.title(),
+                    self.selected_bound,
+                    tunable.format_value(low),
+                    tunable.format_value(high)
+                )
+            }
+            None => "Value".to_string(),
+        }
+    }
+
+    pub fn get_seed_ids(&self, kind: SeedKind) -> Option<Vec<String>> {
+        let ids: Vec<String> = self
+            .seeds
+            .iter()
+            .filter(|seed| seed.kind == kind)
+            .map(|seed| seed.id.to_owned())
+            .collect();
+        if ids.is_empty() {
+            None
+        } else {
+            Some(ids)
+        }
+    }
+
+    /// The `min_`, `max_` and `target_` attributes of the recommendations request
+    pub fn get_payload(&self) -> Map<String, Value> {
+        let mut payload = Map::new();
+        for (tunable, tuning) in &self.tunings {
+            for bound in &[Bound::Min, Bound::Target, Bound::Max] {
+                if let Some(value) = tuning.get(*bound) {
+                    let value = match tunable {
+                        // Spotify wants whole numbers for these
+                        Tunable::Popularity => Value::from(value.round() as u64),
+                        _ => Number::from_f64(value).map_or(Value::Null, Value::Number),
+                    };
+                    payload.insert(format!("{}{}", bound.prefix(), tunable.attribute()), value);
+                }
+            }
+        }
+        payload
+    }
+
+    pub fn get_seed_names(&self) -> String {
+        self.seeds
+            .iter()
+            .map(|seed| format!("'{}'", seed.name))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+/// Turns a genre as typed into the way Spotify writes it, like `hip-hop` for "Hip Hop"
+pub fn normalize_genre(genre: &str) -> String {
+    genre
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(kind: SeedKind, id: &str) -> Seed {
+        Seed {
+            kind,
+            id: id.to_string(),
+            name: id.to_string(),
+        }
+    }
+
+    fn select(builder: &mut RecommendationsBuilder, row: BuilderRow, bound: Bound) {
+        builder.selected_index = builder.rows().iter().position(|r| *r == row).unwrap();
+        builder.selected_bound = bound;
+    }
+
+    #[test]
+    fn test_parse_seed_query() {
+        assert_eq!(
+            parse_seed_query("Radiohead").unwrap(),
+            (SeedKind::Artist, "Radiohead".to_string())
+        );
+        assert_eq!(
+            parse_seed_query("track: Karma Police").unwrap(),
+            (SeedKind::Track, "Karma Police".to_string())
+        );
+        assert_eq!(
+            parse_seed_query("Genre:jazz").unwrap(),
+            (SeedKind::Genre, "jazz".to_string())
+        );
+        assert_eq!(
+            parse_seed_query("Sunn O))): live").unwrap(),
+            (SeedKind::Artist, "Sunn O))): live".to_string())
+        );
+        assert!(parse_seed_query("genre: ").is_err());
+    }
+
+    #[test]
+    fn test_normalize_genre() {
+        assert_eq!(normalize_genre(" Hip Hop "), "hip-hop");
+        assert_eq!(normalize_genre("jazz"), "jazz");
+    }
+
+    #[test]
+    fn test_seeds() {
+        let mut builder = RecommendationsBuilder::default();
+        for id in &["1", "2", "3", "4"] {
+            builder.add_seed(seed(SeedKind::Artist, id)).unwrap();
+        }
+        builder.add_seed(seed(SeedKind::Genre, "jazz")).unwrap();
+        // Adding one twice does nothing
+        builder.add_seed(seed(SeedKind::Genre, "jazz")).unwrap();
+        assert!(builder.add_seed(seed(SeedKind::Track, "5")).is_err());
+        assert!(!builder.rows().contains(&BuilderRow::AddSeed));
+
+        assert_eq!(
+            builder.get_seed_ids(SeedKind::Genre),
+            Some(vec!["jazz".to_string()])
+        );
+        assert_eq!(builder.get_seed_ids(SeedKind::Track), None);
+
+        select(&mut builder, BuilderRow::Seed(0), Bound::Target);
+        builder.clear_selected();
+        assert_eq!(builder.seeds.len(), 4);
+        assert!(builder.rows().contains(&BuilderRow::AddSeed));
+    }
+
+    #[test]
+    fn test_set_selected_value() {
+        let mut builder = RecommendationsBuilder::default();
+        select(
+            &mut builder,
+            BuilderRow::Tunable(Tunable::Energy),
+            Bound::Min,
+        );
+        builder.set_selected_value("0.6").unwrap();
+        assert!(builder.set_selected_value("1.5").is_err());
+        assert!(builder.set_selected_value("lots").is_err());
+
+        builder.selected_bound = Bound::Max;
+        // The maximum can't be below the minimum
+        assert!(builder.set_selected_value("0.4").is_err());
+        builder.set_selected_value("0.9").unwrap();
+
+        let tuning = builder.get_tuning(Tunable::Energy);
+        assert_eq!((tuning.min, tuning.max), (Some(0.6), Some(0.9)));
+
+        builder.set_selected_value("").unwrap();
+        assert_eq!(builder.get_tuning(Tunable::Energy).max, None);
+    }
+
+    #[test]
+    fn test_get_payload() {
+        let mut builder = RecommendationsBuilder::default();
+        select(
+            &mut builder,
+            BuilderRow::Tunable(Tunable::Energy),
+            Bound::Target,
+        );
+        builder.set_selected_value("0.8").unwrap();
+        select(
+            &mut builder,
+            BuilderRow::Tunable(Tunable::Popularity),
+            Bound::Min,
+        );
+        builder.set_selected_value("40").unwrap();
+
+        let payload = builder.get_payload();
+        assert_eq!(payload.len(), 2);
+        assert_eq!(payload["target_energy"].to_string(), "0.8");
+        assert_eq!(payload["min_popularity"].to_string(), "40");
+    }
+}
@@ -1,78 +1,327 @@
-use rspotify::spotify::{oauth2::SpotifyOAuth, util::request_token};
+use crate::auth::request_token;
+use reqwest::Url;
 use std::{
-    io::prelude::*,
+    collections::HashMap,
+    io::{self, prelude::*},
     net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
 };
 
-pub fn redirect_uri_web_server(spotify_oauth: &mut SpotifyOAuth, port: u16) -> Result<String, ()> {
-    let listener = TcpListener::bind(format!("127.0.0.1:{}", port));
-
-    match listener {
-        Ok(listener) => {
-            request_token(spotify_oauth);
-
-            for stream in listener.incoming() {
-                match stream {
-                    Ok(stream) => {
-                        if let Some(url) = handle_connection(stream) {
-                            return Ok(url);
-                        }
-                    }
-                    Err(e) => {
-                        println!("Error: {}", e);
-                    }
-                };
+const CALLBACK_PATH: &str = "/callback";
+// Requests from a browser are well under this, anything bigger is not our callback
+const MAX_REQUEST_SIZE: usize = 16 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub enum CallbackError {
+    /// The local server could not be started, e.g. because the port is in use
+    Bind(io::Error),
+    /// Nobody completed the login before the timeout
+    Timeout,
+    /// Spotify redirected back with an error, e.g. `access_denied`
+    Denied(String),
+}
+
+pub fn redirect_uri_web_server(
+    auth_url: &str,
+    state: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<String, CallbackError> {
+    let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).map_err(CallbackError::Bind)?;
+
+    request_token(auth_url);
+
+    let result = wait_for_callback(&listener, state, timeout);
+    if let Err(CallbackError::Timeout) = result {
+        println!(
+            "\nTimed out after {} seconds waiting for Spotify to redirect to http://localhost:{}{}.",
+            timeout.as_secs(),
+            port,
+            CALLBACK_PATH
+        );
+        println!("Make sure this redirect URI is added to your app in the Spotify dashboard.");
+    }
+    result
+}
+
+/// Serves requests until one of them is the OAuth callback for `state`, returning its
+/// path and query. Other paths (such as `/favicon.ico`) and callbacks with the wrong
+/// state are answered with an error and ignored.
+pub fn wait_for_callback(
+    listener: &TcpListener,
+    state: &str,
+    timeout: Duration,
+) -> Result<String, CallbackError> {
+    let deadline = Instant::now() + timeout;
+    listener
+        .set_nonblocking(true)
+        .map_err(CallbackError::Bind)?;
+
+    while Instant::now() < deadline {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Some(result) = handle_connection(stream, state) {
+                    return result;
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(e) => {
+                println!("Error: {}", e);
             }
         }
+    }
+
+    Err(CallbackError::Timeout)
+}
+
+fn handle_connection(mut stream: TcpStream, state: &str) -> Option<Result<String, CallbackError>> {
+    if stream.set_nonblocking(false).is_err()
+        || stream.set_read_timeout(Some(READ_TIMEOUT)).is_err()
+    {
+        return None;
+    }
+
+    let target = match read_request(&mut stream) {
+        Ok(target) => target,
         Err(e) => {
-            println!("Error: {}", e);
+            respond(&mut stream, "400 Bad Request", "Bad request", &e);
+            return None;
+        }
+    };
+
+    let (path, params) = match parse_target(&target) {
+        Some(parsed) => parsed,
+        None => {
+            respond(
+                &mut stream,
+                "400 Bad Request",
+                "Bad request",
+                "Malformed URL",
+            );
+            return None;
         }
+    };
+
+    if path != CALLBACK_PATH {
+        respond(&mut stream, "404 Not Found", "Not found", "");
+        return None;
     }
 
-    Err(())
-}
+    if params.get("state").map(String::as_str) != Some(state) {
+        respond(
+            &mut stream,
+            "400 Bad Request",
+            "Authorization failed",
+            "The state parameter does not match this login attempt. Please start the login again from your terminal.",
+        );
+        return None;
+    }
 
-fn handle_connection(mut stream: TcpStream) -> Option<String> {
-    // The request will be quite large (> 512) so just assign plenty just in case
-    let mut buffer = [0; 1000];
-    let _ = stream.read(&mut buffer).unwrap();
+    if let Some(error) = params.get("error") {
+        let message = if error == "access_denied" {
+            "You declined access to your Spotify account, so spotify-tui can't be used. Run spt again if you change your mind.".to_string()
+        } else {
+            format!("Spotify returned an error: {}", error)
+        };
+        respond(&mut stream, "200 OK", "Authorization failed", &message);
+        return Some(Err(CallbackError::Denied(error.to_string())));
+    }
 
-    // convert buffer into string and 'parse' the URL
-    match String::from_utf8(buffer.to_vec()) {
-        Ok(request) => {
-            let split: Vec<&str> = request.split_whitespace().collect();
+    if !params.contains_key("code") {
+        respond(
+            &mut stream,
+            "400 Bad Request",
+            "Authorization failed",
+            "The callback did not include an authorization code.",
+        );
+        return None;
+    }
 
-            if split.len() > 1 {
-                respond_with_success(stream);
-                return Some(split[1].to_string());
-            }
+    respond(
+        &mut stream,
+        "200 OK",
+        "spotify-tui",
+        "Client authorized. You can return to your terminal and close this window.",
+    );
+    Some(Ok(target))
+}
+
+/// Reads the request head and returns the request target of a `GET` request
+fn read_request(stream: &mut impl Read) -> Result<String, String> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
 
-            respond_with_error("Malformed request".to_string(), stream);
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > MAX_REQUEST_SIZE {
+            return Err("Request too large".to_string());
         }
-        Err(e) => {
-            respond_with_error(format!("Invalid UTF-8 sequence: {}", e), stream);
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => request.extend_from_slice(&buffer[..read]),
+            Err(e) => return Err(format!("Failed to read request: {}", e)),
         }
-    };
+    }
 
-    None
-}
+    let request =
+        String::from_utf8(request).map_err(|e| format!("Invalid UTF-8 sequence: {}", e))?;
+    let mut request_line = request.lines().next().unwrap_or("").split_whitespace();
 
-fn respond_with_success(mut stream: TcpStream) {
-    let contents = include_str!("redirect_uri.html");
+    match (
+        request_line.next(),
+        request_line.next(),
+        request_line.next(),
+    ) {
+        (Some("GET"), Some(target), Some(version)) if version.starts_with("HTTP/") => {
+            Ok(target.to_string())
+        }
+        _ => Err("Malformed request".to_string()),
+    }
+}
 
-    let response = format!("HTTP/1.1 200 OK\r\n\r\n{}", contents);
+/// Splits a request target or a full redirect url into its path and query parameters
+pub fn parse_target(target: &str) -> Option<(String, HashMap<String, String>)> {
+    let target = target.trim();
+    let url = Url::parse(target)
+        .or_else(|_| Url::parse("http://localhost").and_then(|base| base.join(target)))
+        .ok()?;
+    let params = url.query_pairs().into_owned().collect();
 
-    stream.write_all(response.as_bytes()).unwrap();
-    stream.flush().unwrap();
+    Some((url.path().to_string(), params))
 }
 
-fn respond_with_error(error_message: String, mut stream: TcpStream) {
-    println!("Error: {}", error_message);
+fn respond(stream: &mut TcpStream, status: &str, heading: &str, message: &str) {
+    let contents = include_str!("redirect_uri.html")
+        .replace("{{heading}}", &escape_html(heading))
+        .replace("{{message}}", &escape_html(message));
+
     let response = format!(
-        "HTTP/1.1 400 Bad Request\r\n\r\n400 - Bad Request - {}",
-        error_message
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        contents.len(),
+        contents
     );
 
-    stream.write_all(response.as_bytes()).unwrap();
-    stream.flush().unwrap();
+    if let Err(e) = stream
+        .write_all(response.as_bytes())
+        .and_then(|_| stream.flush())
+    {
+        println!("Error: {}", e);
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn send(port: u16, request: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn get(port: u16, target: &str) -> String {
+        send(
+            port,
+            &format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target),
+        )
+    }
+
+    fn start_server(
+        state: &'static str,
+        timeout: Duration,
+    ) -> (u16, thread::JoinHandle<Result<String, CallbackError>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || wait_for_callback(&listener, state, timeout));
+        (port, handle)
+    }
+
+    #[test]
+    fn test_parse_target() {
+        let (path, params) = parse_target("/callback?state=abc&code=123").unwrap();
+        assert_eq!(path, "/callback");
+        assert_eq!(params["code"], "123");
+        assert_eq!(params["state"], "abc");
+
+        let (path, params) =
+            parse_target("http://localhost:8888/callback?code=a%2Fb&state=abc\n").unwrap();
+        assert_eq!(path, "/callback");
+        assert_eq!(params["code"], "a/b");
+    }
+
+    #[test]
+    fn test_read_request() {
+        let mut request = "GET /callback?code=1 HTTP/1.1\r\nHost: localhost\r\n\r\n".as_bytes();
+        assert_eq!(read_request(&mut request).unwrap(), "/callback?code=1");
+
+        let mut request = "POST /callback HTTP/1.1\r\n\r\n".as_bytes();
+        assert!(read_request(&mut request).is_err());
+
+        let mut request = "garbage".as_bytes();
+        assert!(read_request(&mut request).is_err());
+    }
+
+    #[test]
+    fn test_ignores_other_paths_and_wrong_state() {
+        let (port, server) = start_server("the-state", Duration::from_secs(10));
+
+        assert!(get(port, "/favicon.ico").starts_with("HTTP/1.1 404"));
+        assert!(get(port, "/callback?code=123&state=other").starts_with("HTTP/1.1 400"));
+        assert!(get(port, "/callback?state=the-state").starts_with("HTTP/1.1 400"));
+        assert!(send(port, "not http\r\n\r\n").starts_with("HTTP/1.1 400"));
+
+        let response = get(port, "/callback?code=123&state=the-state");
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("Client authorized"));
+
+        assert_eq!(
+            server.join().unwrap().unwrap(),
+            "/callback?code=123&state=the-state"
+        );
+    }
+
+    #[test]
+    fn test_access_denied() {
+        let (port, server) = start_server("the-state", Duration::from_secs(10));
+
+        let response = get(port, "/callback?error=access_denied&state=the-state");
+        assert!(response.contains("You declined access"));
+
+        match server.join().unwrap() {
+            Err(CallbackError::Denied(error)) => assert_eq!(error, "access_denied"),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_timeout() {
+        let (_, server) = start_server("the-state", Duration::from_millis(200));
+
+        match server.join().unwrap() {
+            Err(CallbackError::Timeout) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<script>\"&\"</script>"),
+            "&lt;script&gt;&quot;&amp;&quot;&lt;/script&gt;"
+        );
+    }
 }
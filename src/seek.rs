@@ -0,0 +1,189 @@
+use failure::format_err;
+
+// Going to the previous section this far into a section goes to its start instead, like
+// previous track does
+const SECTION_RESTART_MS: u32 = 3_000;
+
+/// Parses what was typed into the seek prompt: a time like `1:23` or `1m30s`, a jump like
+/// `+30s` or `-2m`, or a percentage like `50%`. Returns the position to seek to.
+pub fn parse_seek_position(
+    input: &str,
+    progress_ms: u32,
+    duration_ms: u32,
+) -> Result<u32, failure::Error> {
+    let input = input.trim();
+    let invalid = || {
+        format_err!(
+            "Can't seek to \"{}\": use a time like 1:23, a jump like +30s or -2m, or a percentage like 50%",
+            input
+        )
+    };
+
+    let position_ms = if let Some(percent) = input.strip_suffix('%') {
+        let percent = percent.trim().parse::<u32>().map_err(|_| invalid())?;
+        if percent > 100 {
+            return Err(invalid());
+        }
+        percentage_position(duration_ms, percent)
+    } else if let Some(jump) = input.strip_prefix('+') {
+        progress_ms.saturating_add(parse_time(jump).ok_or_else(invalid)?)
+    } else if let Some(jump) = input.strip_prefix('-') {
+        progress_ms.saturating_sub(parse_time(jump).ok_or_else(invalid)?)
+    } else {
+        parse_time(input).ok_or_else(invalid)?
+    };
+
+    Ok(position_ms.min(duration_ms))
+}
+
+pub fn percentage_position(duration_ms: u32, percent: u32) -> u32 {
+    (u64::from(duration_ms) * u64::from(percent.min(100)) / 100) as u32
+}
+
+// `1:23` and `1:02:03`, or numbers with `h`, `m` or `s` after them like `1m30s`. A number on
+// its own is seconds.
+fn parse_time(time: &str) -> Option<u32> {
+    let time = time.trim();
+    if time.is_empty() {
+        return None;
+    }
+
+    let seconds = if time.contains(':') {
+        let parts = time
+            .split(':')
+            .map(|part| part.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        if parts.len() > 3 || parts.iter().skip(1).any(|part| *part >= 60) {
+            return None;
+        }
+        parts.iter().try_fold(0u32, |total, part| {
+            total.checked_mul(60)?.checked_add(*part)
+        })?
+    } else {
+        let mut total: u32 = 0;
+        let mut number = String::new();
+        for c in time.chars() {
+            let unit = match c {
+                '0'..='9' => {
+                    number.push(c);
+                    continue;
+                }
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            let seconds = number.parse::<u32>().ok()?.checked_mul(unit)?;
+            total = total.checked_add(seconds)?;
+            number.clear();
+        }
+        if !number.is_empty() {
+            total = total.checked_add(number.parse().ok()?)?;
+        }
+        total
+    };
+
+    seconds.checked_mul(1000)
+}
+
+/// The start of the first section after `progress_ms`. Starts are in milliseconds, in order.
+pub fn next_section_start(section_starts: &[u32], progress_ms: u32) -> Option<u32> {
+    section_starts
+        .iter()
+        .find(|start| **start > progress_ms)
+        .copied()
+}
+
+/// The start of the section before the one playing, or of the one playing when it has been
+/// playing for a while
+pub fn previous_section_start(section_starts: &[u32], progress_ms: u32) -> u32 {
+    let current = section_starts
+        .iter()
+        .rposition(|start| *start <= progress_ms);
+
+    match current {
+        Some(index) if progress_ms - section_starts[index] > SECTION_RESTART_MS => {
+            section_starts[index]
+        }
+        Some(index) if index > 0 => section_starts[index - 1],
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DURATION_MS: u32 = 200_000;
+
+    #[test]
+    fn test_parse_absolute() {
+        assert_eq!(parse_seek_position("1:23", 0, DURATION_MS).unwrap(), 83_000);
+        assert_eq!(parse_seek_position("90", 0, DURATION_MS).unwrap(), 90_000);
+        assert_eq!(
+            parse_seek_position("1m30s", 0, DURATION_MS).unwrap(),
+            90_000
+        );
+        assert_eq!(
+            parse_seek_position("1:00:00", 0, DURATION_MS).unwrap(),
+            DURATION_MS
+        );
+    }
+
+    #[test]
+    fn test_parse_relative() {
+        assert_eq!(
+            parse_seek_position("+30s", 60_000, DURATION_MS).unwrap(),
+            90_000
+        );
+        assert_eq!(
+            parse_seek_position("-2m", 150_000, DURATION_MS).unwrap(),
+            30_000
+        );
+        assert_eq!(parse_seek_position("-2m", 60_000, DURATION_MS).unwrap(), 0);
+        assert_eq!(
+            parse_seek_position("+0:10", 60_000, DURATION_MS).unwrap(),
+            70_000
+        );
+    }
+
+    #[test]
+    fn test_parse_percentage() {
+        assert_eq!(parse_seek_position("50%", 0, DURATION_MS).unwrap(), 100_000);
+        assert_eq!(parse_seek_position(" 0 % ", 0, DURATION_MS).unwrap(), 0);
+        assert!(parse_seek_position("150%", 0, DURATION_MS).is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for input in &[
+            "",
+            "+",
+            "soon",
+            "1:75",
+            "1:2:3:4",
+            "5x",
+            "--1",
+            "+2000000h",
+            "99999999:00",
+        ] {
+            assert!(
+                parse_seek_position(input, 0, DURATION_MS).is_err(),
+                "{} should be invalid",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_section_starts() {
+        let starts = [0, 20_000, 45_000];
+        assert_eq!(next_section_start(&starts, 10_000), Some(20_000));
+        assert_eq!(next_section_start(&starts, 20_000), Some(45_000));
+        assert_eq!(next_section_start(&starts, 50_000), None);
+
+        assert_eq!(previous_section_start(&starts, 30_000), 20_000);
+        assert_eq!(previous_section_start(&starts, 21_000), 0);
+        assert_eq!(previous_section_start(&starts, 1_000), 0);
+    }
+}
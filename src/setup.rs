@@ -0,0 +1,569 @@
+use crate::{
+    auth::{self, CredentialsError, ACCOUNTS_URL},
+    config::{AuthFlow, ClientConfig, DEFAULT_PORT},
+    event::Key,
+    ui,
+    user_config::Theme,
+};
+use crossterm::{
+    event::{self, Event},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use failure::err_msg;
+use rspotify::spotify::{client::Spotify, model::device::Device};
+use std::{
+    io::{self, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    Terminal,
+};
+
+// Client ids and secrets from the Spotify dashboard are 32 hex characters
+const CREDENTIAL_LENGTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum SetupStep {
+    ClientId,
+    ClientSecret,
+    Port,
+    Verify,
+    /// Picks the default device. Runs after logging in, as listing devices needs a token.
+    Device,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verification {
+    Pending,
+    Passed(String),
+    /// The credentials could not be checked, but may well be fine
+    Warning(String),
+    Failed(String),
+}
+
+/// State of the first run setup, which asks for the `client.yml` values
+pub struct SetupWizard {
+    pub step: SetupStep,
+    pub client_id: String,
+    pub client_secret: String,
+    pub port: String,
+    pub error: Option<String>,
+    pub verification: Verification,
+    pub config_file_path: PathBuf,
+    /// `None` until they have been fetched
+    pub devices: Option<Vec<Device>>,
+    pub selected_device_index: usize,
+    pub device_id: Option<String>,
+    is_finished: bool,
+    is_cancelled: bool,
+}
+
+impl SetupWizard {
+    pub fn new(config_file_path: &Path) -> SetupWizard {
+        SetupWizard {
+            step: SetupStep::ClientId,
+            client_id: String::new(),
+            client_secret: String::new(),
+            port: String::new(),
+            error: None,
+            verification: Verification::Pending,
+            config_file_path: config_file_path.to_path_buf(),
+            devices: None,
+            selected_device_index: 0,
+            device_id: None,
+            is_finished: false,
+            is_cancelled: false,
+        }
+    }
+
+    /// The port the redirect URI will use, falling back to the default while it's invalid
+    pub fn get_port(&self) -> u16 {
+        parse_port(&self.port).unwrap_or(DEFAULT_PORT)
+    }
+
+    pub fn uses_pkce(&self) -> bool {
+        self.client_secret.trim().is_empty()
+    }
+
+    pub fn handle_key(&mut self, key: Key) {
+        match key {
+            // Like Esc, the config has been saved before the device step, so it is skipped
+            Key::Ctrl('c') if self.step == SetupStep::Device => self.is_finished = true,
+            Key::Ctrl('c') => self.is_cancelled = true,
+            Key::Esc => {
+                self.error = None;
+                self.step = match self.step {
+                    SetupStep::ClientId => {
+                        self.is_cancelled = true;
+                        SetupStep::ClientId
+                    }
+                    SetupStep::ClientSecret => SetupStep::ClientId,
+                    SetupStep::Port => SetupStep::ClientSecret,
+                    SetupStep::Verify => SetupStep::Port,
+                    // The config has been saved by now, so Esc skips picking a device
+                    SetupStep::Device => {
+                        self.is_finished = true;
+                        SetupStep::Device
+                    }
+                };
+            }
+            Key::Enter => self.submit(),
+            Key::Down | Key::Char('j') if self.step == SetupStep::Device => {
+                let device_count = self.devices.as_ref().map(Vec::len).unwrap_or(0);
+                if self.selected_device_index + 1 < device_count {
+                    self.selected_device_index += 1;
+                }
+            }
+            Key::Up | Key::Char('k') if self.step == SetupStep::Device => {
+                self.selected_device_index = self.selected_device_index.saturating_sub(1);
+            }
+            Key::Char('r') if self.step == SetupStep::Device => self.devices = None,
+            Key::Ctrl('u') => {
+                if let Some(input) = self.current_input() {
+                    input.clear();
+                }
+            }
+            Key::Backspace => {
+                if let Some(input) = self.current_input() {
+                    input.pop();
+                }
+            }
+            Key::Char(c) => {
+                if let Some(input) = self.current_input() {
+                    input.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn current_input(&mut self) -> Option<&mut String> {
+        match self.step {
+            SetupStep::ClientId => Some(&mut self.client_id),
+            SetupStep::ClientSecret => Some(&mut self.client_secret),
+            SetupStep::Port => Some(&mut self.port),
+            SetupStep::Verify | SetupStep::Device => None,
+        }
+    }
+
+    fn submit(&mut self) {
+        let result = match self.step {
+            SetupStep::ClientId => validate_credential(self.client_id.trim(), "Client ID"),
+            SetupStep::ClientSecret if self.uses_pkce() => Ok(()),
+            SetupStep::ClientSecret => {
+                validate_credential(self.client_secret.trim(), "Client Secret")
+            }
+            SetupStep::Port => parse_port(&self.port).and_then(check_port_free),
+            SetupStep::Verify => {
+                match self.verification {
+                    Verification::Passed(_) | Verification::Warning(_) => self.is_finished = true,
+                    // Start over so the credentials can be corrected
+                    Verification::Failed(_) => self.step = SetupStep::ClientId,
+                    Verification::Pending => {}
+                }
+                return;
+            }
+            SetupStep::Device => {
+                if let Some(device) = self.get_selected_device() {
+                    self.device_id = Some(device.id.to_owned());
+                    self.is_finished = true;
+                }
+                return;
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                self.error = None;
+                self.step = match self.step {
+                    SetupStep::ClientId => SetupStep::ClientSecret,
+                    SetupStep::ClientSecret => SetupStep::Port,
+                    _ => {
+                        self.verification = Verification::Pending;
+                        SetupStep::Verify
+                    }
+                };
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub fn needs_verification(&self) -> bool {
+        self.step == SetupStep::Verify && self.verification == Verification::Pending
+    }
+
+    /// Checks the credentials with Spotify. With PKCE there is no secret to check yet.
+    pub fn verify(&mut self, accounts_url: &str) {
+        self.verification = if self.uses_pkce() {
+            Verification::Passed(
+                "No Client Secret was entered, so spotify-tui will log in with PKCE. Your Client ID is checked when you log in."
+                    .to_string(),
+            )
+        } else {
+            match auth::check_client_credentials(
+                accounts_url,
+                self.client_id.trim(),
+                self.client_secret.trim(),
+            ) {
+                Ok(()) => {
+                    Verification::Passed("Spotify accepted your Client ID and Client Secret.".to_string())
+                }
+                Err(CredentialsError::Rejected(e)) => Verification::Failed(format!(
+                    "Spotify rejected your Client ID or Client Secret: {}",
+                    e
+                )),
+                Err(CredentialsError::Unreachable(e)) => Verification::Warning(format!(
+                    "Your credentials could not be checked ({}). They will be checked when you log in.",
+                    e
+                )),
+            }
+        };
+    }
+
+    pub fn needs_devices(&self) -> bool {
+        self.step == SetupStep::Device && self.devices.is_none()
+    }
+
+    /// Fetches the devices, selecting the active one
+    pub fn load_devices(&mut self, spotify: &Spotify) {
+        let devices = match spotify.device() {
+            Ok(payload) => {
+                self.error = None;
+                payload.devices
+            }
+            Err(e) => {
+                self.error = Some(format!("Your devices could not be listed: {}", e));
+                Vec::new()
+            }
+        };
+        self.selected_device_index = devices
+            .iter()
+            .position(|device| device.is_active)
+            .unwrap_or(0);
+        self.devices = Some(devices);
+    }
+
+    pub fn get_selected_device(&self) -> Option<&Device> {
+        self.devices
+            .as_ref()
+            .and_then(|devices| devices.get(self.selected_device_index))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.is_cancelled
+    }
+
+    pub fn get_config(&self) -> ClientConfig {
+        ClientConfig {
+            client_id: self.client_id.trim().to_string(),
+            client_secret: self.client_secret.trim().to_string(),
+            secret_command: None,
+            device_id: self.device_id.clone(),
+            port: Some(self.get_port()),
+            auth_flow: if self.uses_pkce() {
+                Some(AuthFlow::Pkce)
+            } else {
+                None
+            },
+            device_volumes: Default::default(),
+        }
+    }
+}
+
+fn validate_credential(value: &str, name: &str) -> Result<(), String> {
+    if value.len() == CREDENTIAL_LENGTH && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "A {} is {} characters long and only contains 0-9 and a-f",
+            name, CREDENTIAL_LENGTH
+        ))
+    }
+}
+
+fn parse_port(port: &str) -> Result<u16, String> {
+    let port = port.trim();
+    if port.is_empty() {
+        return Ok(DEFAULT_PORT);
+    }
+    match port.parse::<u16>() {
+        Ok(port) if port > 0 => Ok(port),
+        _ => Err(format!("`{}` is not a port between 1 and 65535", port)),
+    }
+}
+
+fn check_port_free(port: u16) -> Result<(), String> {
+    TcpListener::bind(("127.0.0.1", port))
+        .map(|_| ())
+        .map_err(|e| {
+            format!(
+                "Port {} can't be used ({}), please pick another one",
+                port, e
+            )
+        })
+}
+
+/// Runs the setup full screen and returns the config to save
+pub fn run(theme: &Theme, config_file_path: &Path) -> Result<ClientConfig, failure::Error> {
+    let mut wizard = SetupWizard::new(config_file_path);
+    run_full_screen(&mut wizard, theme, None)?;
+    Ok(wizard.get_config())
+}
+
+/// The last step of the setup, once the user has logged in: saves the device they pick as
+/// the one to play on
+pub fn run_device_step(
+    theme: &Theme,
+    spotify: &Spotify,
+    client_config: &mut ClientConfig,
+) -> Result<(), failure::Error> {
+    let paths = client_config.get_or_build_paths()?;
+    let mut wizard = SetupWizard::new(&paths.config_file_path);
+    wizard.client_id = client_config.client_id.to_owned();
+    wizard.client_secret = client_config.client_secret.to_owned();
+    wizard.port = client_config.get_port().to_string();
+    wizard.step = SetupStep::Device;
+
+    run_full_screen(&mut wizard, theme, Some(spotify))?;
+    match wizard.device_id {
+        Some(device_id) => client_config.set_device_id(device_id),
+        None => Ok(()),
+    }
+}
+
+fn run_full_screen(
+    wizard: &mut SetupWizard,
+    theme: &Theme,
+    spotify: Option<&Spotify>,
+) -> Result<(), failure::Error> {
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+    terminal.hide_cursor()?;
+
+    let result = run_wizard(&mut terminal, wizard, theme, spotify);
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_wizard<B>(
+    terminal: &mut Terminal<B>,
+    wizard: &mut SetupWizard,
+    theme: &Theme,
+    spotify: Option<&Spotify>,
+) -> Result<(), failure::Error>
+where
+    B: Backend,
+{
+    loop {
+        terminal.draw(|mut f| ui::setup::draw(&mut f, wizard, theme))?;
+
+        if wizard.is_cancelled() {
+            return Err(err_msg("Setup cancelled, run spt again when you are ready"));
+        }
+        if wizard.is_finished() {
+            return Ok(());
+        }
+
+        // The "checking" screen has been drawn, now do the slow part
+        if wizard.needs_verification() {
+            wizard.verify(ACCOUNTS_URL);
+            continue;
+        }
+        if let Some(spotify) = spotify.filter(|_| wizard.needs_devices()) {
+            wizard.load_devices(spotify);
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            wizard.handle_key(Key::from(key));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspotify::spotify::senum::DeviceType;
+
+    const CLIENT_ID: &str = "0123456789abcdef0123456789abcdef";
+
+    fn type_text(wizard: &mut SetupWizard, text: &str) {
+        for c in text.chars() {
+            wizard.handle_key(Key::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_validate_credential() {
+        assert!(validate_credential(CLIENT_ID, "Client ID").is_ok());
+        assert!(validate_credential(&CLIENT_ID.to_uppercase(), "Client ID").is_ok());
+        assert!(validate_credential(&CLIENT_ID[1..], "Client ID").is_err());
+        assert!(validate_credential("0123456789abcdef0123456789abcdeg", "Client ID").is_err());
+        assert!(validate_credential("", "Client ID").is_err());
+    }
+
+    #[test]
+    fn test_parse_port() {
+        assert_eq!(parse_port(""), Ok(DEFAULT_PORT));
+        assert_eq!(parse_port(" 8080 "), Ok(8080));
+        assert!(parse_port("0").is_err());
+        assert!(parse_port("65536").is_err());
+        assert!(parse_port("port").is_err());
+    }
+
+    #[test]
+    fn test_check_port_free() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(check_port_free(port).is_err());
+
+        drop(listener);
+        assert!(check_port_free(port).is_ok());
+    }
+
+    #[test]
+    fn test_wizard_steps() {
+        let mut wizard = SetupWizard::new(Path::new("client.yml"));
+
+        // An invalid id is rejected with an explanation
+        type_text(&mut wizard, "not-an-id");
+        wizard.handle_key(Key::Enter);
+        assert_eq!(wizard.step, SetupStep::ClientId);
+        assert!(wizard.error.is_some());
+
+        wizard.handle_key(Key::Ctrl('u'));
+        type_text(&mut wizard, CLIENT_ID);
+        wizard.handle_key(Key::Enter);
+        assert_eq!(wizard.step, SetupStep::ClientSecret);
+        assert_eq!(wizard.error, None);
+
+        // Esc goes back a step, keeping what was typed
+        wizard.handle_key(Key::Esc);
+        assert_eq!(wizard.step, SetupStep::ClientId);
+        assert_eq!(wizard.client_id, CLIENT_ID);
+        wizard.handle_key(Key::Enter);
+
+        // Leaving the secret empty selects PKCE
+        wizard.handle_key(Key::Enter);
+        assert_eq!(wizard.step, SetupStep::Port);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        type_text(&mut wizard, &port.to_string());
+        wizard.handle_key(Key::Enter);
+        assert_eq!(wizard.step, SetupStep::Port);
+        assert!(wizard.error.is_some());
+
+        drop(listener);
+        wizard.handle_key(Key::Enter);
+        assert_eq!(wizard.step, SetupStep::Verify);
+        assert!(wizard.needs_verification());
+
+        // Nothing to check with PKCE, so no request is made
+        wizard.verify("http://127.0.0.1:1");
+        assert!(!wizard.needs_verification());
+        wizard.handle_key(Key::Enter);
+        assert!(wizard.is_finished());
+
+        let config = wizard.get_config();
+        assert_eq!(config.client_id, CLIENT_ID);
+        assert_eq!(config.client_secret, "");
+        assert_eq!(config.port, Some(port));
+        assert_eq!(config.auth_flow, Some(AuthFlow::Pkce));
+    }
+
+    #[test]
+    fn test_wizard_failed_verification_starts_over() {
+        let mut wizard = SetupWizard::new(Path::new("client.yml"));
+        wizard.client_id = CLIENT_ID.to_string();
+        wizard.client_secret = CLIENT_ID.to_string();
+        wizard.step = SetupStep::Verify;
+        wizard.verification = Verification::Failed("invalid_client".to_string());
+
+        wizard.handle_key(Key::Enter);
+        assert_eq!(wizard.step, SetupStep::ClientId);
+        assert!(!wizard.is_finished());
+        assert_eq!(wizard.get_config().auth_flow, None);
+    }
+
+    #[test]
+    fn test_wizard_device_step() {
+        let mut wizard = SetupWizard::new(Path::new("client.yml"));
+        wizard.step = SetupStep::Device;
+        assert!(wizard.needs_devices());
+
+        // Nothing to pick until there are devices
+        wizard.devices = Some(Vec::new());
+        wizard.handle_key(Key::Enter);
+        assert!(!wizard.is_finished());
+
+        wizard.devices = Some(
+            ["Kitchen", "Laptop"]
+                .iter()
+                .map(|name| Device {
+                    id: name.to_lowercase(),
+                    is_active: false,
+                    is_restricted: false,
+                    name: name.to_string(),
+                    _type: DeviceType::Speaker,
+                    volume_percent: 50,
+                })
+                .collect(),
+        );
+        wizard.handle_key(Key::Char('j'));
+        wizard.handle_key(Key::Char('j'));
+        assert_eq!(wizard.selected_device_index, 1);
+
+        wizard.handle_key(Key::Enter);
+        assert!(wizard.is_finished());
+        assert_eq!(wizard.get_config().device_id, Some("laptop".to_string()));
+
+        // Refreshing fetches the devices again
+        wizard.handle_key(Key::Char('r'));
+        assert!(wizard.needs_devices());
+    }
+
+    #[test]
+    fn test_wizard_skip_device_step() {
+        let mut wizard = SetupWizard::new(Path::new("client.yml"));
+        wizard.step = SetupStep::Device;
+        wizard.handle_key(Key::Esc);
+        assert!(wizard.is_finished());
+        assert!(!wizard.is_cancelled());
+        assert_eq!(wizard.device_id, None);
+    }
+
+    #[test]
+    fn test_wizard_cancel() {
+        let mut wizard = SetupWizard::new(Path::new("client.yml"));
+        wizard.handle_key(Key::Esc);
+        assert!(wizard.is_cancelled());
+
+        let mut wizard = SetupWizard::new(Path::new("client.yml"));
+        wizard.step = SetupStep::Port;
+        wizard.handle_key(Key::Ctrl('c'));
+        assert!(wizard.is_cancelled());
+
+        // Only skips picking a device, the config has been saved
+        let mut wizard = SetupWizard::new(Path::new("client.yml"));
+        wizard.step = SetupStep::Device;
+        wizard.handle_key(Key::Ctrl('c'));
+        assert!(wizard.is_finished());
+        assert!(!wizard.is_cancelled());
+        assert_eq!(wizard.device_id, None);
+    }
+}
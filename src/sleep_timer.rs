@@ -0,0 +1,243 @@
+use failure::format_err;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Over how long the volume is lowered before playback is paused
+pub const FADE_DURATION: Duration = Duration::from_secs(60);
+// Lowering the volume takes a request, so don't do it on every tick
+pub const FADE_STEP_INTERVAL: Duration = Duration::from_secs(2);
+// The longest timer in minutes, a day
+const MAX_MINUTES: u64 = 24 * 60;
+
+// What the sleep timer key cycles through
+const PRESETS: [SleepTimerSetting; 7] = [
+    SleepTimerSetting::Minutes(15),
+    SleepTimerSetting::Minutes(30),
+    SleepTimerSetting::Minutes(45),
+    SleepTimerSetting::Minutes(60),
+    SleepTimerSetting::Minutes(90),
+    SleepTimerSetting::EndOfTrack,
+    SleepTimerSetting::EndOfContext,
+];
+
+/// When to stop playing, as asked for with the sleep timer key or `--sleep`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SleepTimerSetting {
+    Minutes(u64),
+    EndOfTrack,
+    /// The end of the album or playlist that is playing
+    EndOfContext,
+}
+
+impl SleepTimerSetting {
+    pub fn parse(value: &str) -> Result<SleepTimerSetting, failure::Error> {
+        match value.trim() {
+            "track" => Ok(SleepTimerSetting::EndOfTrack),
+            "context" | "album" | "playlist" => Ok(SleepTimerSetting::EndOfContext),
+            minutes => match minutes.parse::<u64>() {
+                Ok(minutes) if minutes > 0 && minutes <= MAX_MINUTES => Ok(SleepTimerSetting::Minutes(minutes)),
+                _ => Err(format_err!(
+                    "Invalid sleep timer \"{}\": use a number of minutes up to {}, \"track\" or \"context\"",
+                    value,
+                    MAX_MINUTES
+                )),
+            },
+        }
+    }
+
+    /// The preset after `current`, or `None` to turn the timer off after the last one
+    pub fn next_preset(current: Option<SleepTimerSetting>) -> Option<SleepTimerSetting> {
+        match current.and_then(|current| PRESETS.iter().position(|preset| *preset == current)) {
+            Some(index) => PRESETS.get(index + 1).copied(),
+            None if current.is_some() => None,
+            None => Some(PRESETS[0]),
+        }
+    }
+}
+
+impl fmt::Display for SleepTimerSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SleepTimerSetting::Minutes(minutes) => write!(f, "{} minutes", minutes),
+            SleepTimerSetting::EndOfTrack => write!(f, "end of track"),
+            SleepTimerSetting::EndOfContext => write!(f, "end of album/playlist"),
+        }
+    }
+}
+
+/// The point the timer runs to, some of which are only known once something is playing
+#[derive(Clone, Debug, PartialEq)]
+pub enum SleepTimerEnd {
+    At(Instant),
+    /// After the track with this uri
+    Track(Option<String>),
+    Context(Option<ContextEnd>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContextEnd {
+    pub context_uri: Option<String>,
+    /// `None` when the last track could not be found, then the timer only ends when
+    /// something other than the context plays
+    pub last_track_uri: Option<String>,
+}
+
+pub struct SleepTimer {
+    pub setting: SleepTimerSetting,
+    pub end: SleepTimerEnd,
+    /// The volume before fading started, restored after pausing
+    pub fade_from: Option<Fade>,
+    pub last_fade_step: Option<Instant>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fade {
+    pub volume: u8,
+    pub duration: Duration,
+}
+
+impl SleepTimer {
+    pub fn new(setting: SleepTimerSetting) -> SleepTimer {
+        let end = match setting {
+            SleepTimerSetting::Minutes(minutes) => {
+                SleepTimerEnd::At(Instant::now() + Duration::from_secs(minutes * 60))
+            }
+            SleepTimerSetting::EndOfTrack => SleepTimerEnd::Track(None),
+            SleepTimerSetting::EndOfContext => SleepTimerEnd::Context(None),
+        };
+
+        SleepTimer {
+            setting,
+            end,
+            fade_from: None,
+            last_fade_step: None,
+        }
+    }
+
+    /// The volume to fade to, if it's time for the next step. Fading starts at `volume`
+    /// once less than `FADE_DURATION` remains.
+    pub fn next_fade_volume(&mut self, volume: u8, remaining: Duration) -> Option<u8> {
+        if remaining > FADE_DURATION {
+            return None;
+        }
+
+        let fade = *self.fade_from.get_or_insert(Fade {
+            volume,
+            duration: remaining,
+        });
+
+        let is_step_due = match self.last_fade_step {
+            Some(last_step) => last_step.elapsed() >= FADE_STEP_INTERVAL,
+            None => true,
+        };
+        if !is_step_due {
+            return None;
+        }
+        self.last_fade_step = Some(Instant::now());
+
+        let target = fade_volume(fade, remaining);
+        if target < volume {
+            Some(target)
+        } else {
+            None
+        }
+    }
+}
+
+/// Lowers the volume linearly so it reaches 0 when nothing remains
+pub fn fade_volume(fade: Fade, remaining: Duration) -> u8 {
+    if fade.duration.as_millis() == 0 {
+        return 0;
+    }
+    let remaining = remaining.min(fade.duration);
+    (u128::from(fade.volume) * remaining.as_millis() / fade.duration.as_millis()) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            SleepTimerSetting::parse("30").unwrap(),
+            SleepTimerSetting::Minutes(30)
+        );
+        assert_eq!(
+            SleepTimerSetting::parse("track").unwrap(),
+            SleepTimerSetting::EndOfTrack
+        );
+        assert_eq!(
+            SleepTimerSetting::parse("playlist").unwrap(),
+            SleepTimerSetting::EndOfContext
+        );
+        assert_eq!(
+            SleepTimerSetting::parse("1440").unwrap(),
+            SleepTimerSetting::Minutes(1440)
+        );
+        assert!(SleepTimerSetting::parse("0").is_err());
+        assert!(SleepTimerSetting::parse("1441").is_err());
+        assert!(SleepTimerSetting::parse("18446744073709551615").is_err());
+        assert!(SleepTimerSetting::parse("soon").is_err());
+    }
+
+    #[test]
+    fn test_next_preset() {
+        assert_eq!(
+            SleepTimerSetting::next_preset(None),
+            Some(SleepTimerSetting::Minutes(15))
+        );
+        assert_eq!(
+            SleepTimerSetting::next_preset(Some(SleepTimerSetting::Minutes(90))),
+            Some(SleepTimerSetting::EndOfTrack)
+        );
+        assert_eq!(
+            SleepTimerSetting::next_preset(Some(SleepTimerSetting::EndOfContext)),
+            None
+        );
+        // A custom time from the command line turns off like the last preset
+        assert_eq!(
+            SleepTimerSetting::next_preset(Some(SleepTimerSetting::Minutes(20))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_fade_volume() {
+        let fade = Fade {
+            volume: 80,
+            duration: FADE_DURATION,
+        };
+        assert_eq!(fade_volume(fade, Duration::from_secs(60)), 80);
+        assert_eq!(fade_volume(fade, Duration::from_secs(30)), 40);
+        assert_eq!(fade_volume(fade, Duration::from_secs(0)), 0);
+        assert_eq!(fade_volume(fade, Duration::from_secs(90)), 80);
+    }
+
+    #[test]
+    fn test_next_fade_volume() {
+        let mut timer = SleepTimer::new(SleepTimerSetting::EndOfTrack);
+        assert_eq!(timer.next_fade_volume(50, Duration::from_secs(120)), None);
+        assert_eq!(timer.fade_from, None);
+
+        // Less than the fade duration was left when fading started
+        assert_eq!(timer.next_fade_volume(50, Duration::from_secs(20)), None);
+        assert_eq!(
+            timer.fade_from,
+            Some(Fade {
+                volume: 50,
+                duration: Duration::from_secs(20)
+            })
+        );
+        // Not yet time for the next step
+        assert_eq!(timer.next_fade_volume(50, Duration::from_secs(10)), None);
+
+        timer.last_fade_step = Some(Instant::now() - FADE_STEP_INTERVAL);
+        assert_eq!(
+            timer.next_fade_volume(50, Duration::from_secs(10)),
+            Some(25)
+        );
+    }
+}
@@ -3,13 +3,9 @@ use crate::app::App;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout},
-    style::Style,
     widgets::{BarChart, Block, Borders, Paragraph, Text, Widget},
     Frame,
 };
-const PITCHES: [&str; 12] = [
-    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
-];
 
 pub fn draw<B>(f: &mut Frame<B>, app: &App)
 where
@@ -26,12 +22,12 @@ where
     let analysis_block = Block::default()
         .title("Analysis")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(app.user_config.theme.inactive))
-        .title_style(Style::default().fg(app.user_config.theme.inactive));
+        .border_style(app.user_config.theme.inactive)
+        .title_style(app.user_config.theme.inactive);
 
-    let white = Style::default().fg(app.user_config.theme.text);
-    let gray = Style::default().fg(app.user_config.theme.inactive);
-    let width = (chunks[1].width) as f32 / (1 + PITCHES.len()) as f32;
+    let white = app.user_config.theme.text;
+    let gray = app.user_config.theme.inactive;
+    let width = (chunks[1].width) as f32 / (1 + util::PITCHES.len()) as f32;
     let tick_rate = app.user_config.behavior.tick_rate_milliseconds;
     let bar_chart_title = &format!("Pitches | Tick Rate {} {}FPS", tick_rate, 1000 / tick_rate);
 
@@ -46,13 +42,13 @@ where
     let empty_analysis_block = || {
         Paragraph::new(analysis_text.iter())
             .block(analysis_block)
-            .style(Style::default().fg(app.user_config.theme.text))
+            .style(app.user_config.theme.text)
     };
     let pitch_text = [Text::raw("No pitch information available")];
     let empty_pitches_block = || {
         Paragraph::new(pitch_text.iter())
             .block(bar_chart_block)
-            .style(Style::default().fg(app.user_config.theme.text))
+            .style(app.user_config.theme.text)
     };
 
     if let Some(analysis) = &app.audio_analysis {
@@ -85,7 +81,9 @@ where
                     )),
                     Text::raw(format!(
                         "Key: {} (confidence {:.0}%)\n",
-                        PITCHES.get(section.key as usize).unwrap_or(&PITCHES[0]),
+                        util::PITCHES
+                            .get(section.key as usize)
+                            .unwrap_or(&util::PITCHES[0]),
                         section.key_confidence * 100.0
                     )),
                     Text::raw(format!(
@@ -97,7 +95,7 @@ where
                 .iter(),
             )
             .block(analysis_block)
-            .style(Style::default().fg(app.user_config.theme.text))
+            .style(app.user_config.theme.text)
             .render(f, chunks[0]);
 
             let data: Vec<(&str, u64)> = segment
@@ -105,7 +103,7 @@ where
                 .iter()
                 .enumerate()
                 .map(|(index, pitch)| {
-                    let display_pitch = *PITCHES.get(index).unwrap_or(&PITCHES[0]);
+                    let display_pitch = *util::PITCHES.get(index).unwrap_or(&util::PITCHES[0]);
                     let bar_value = ((pitch * 1000.0) as u64)
                         // Add a beat offset to make the bar animate between beats
                         .checked_add((beat_offset * 3000.0) as u64)
@@ -119,11 +117,13 @@ where
                 .block(bar_chart_block)
                 .data(&data)
                 .bar_width(width as u16)
-                .style(Style::default().fg(app.user_config.theme.analysis_bar))
+                .style(app.user_config.theme.analysis_bar)
                 .value_style(
-                    Style::default()
-                        .fg(app.user_config.theme.analysis_bar_text)
-                        .bg(app.user_config.theme.analysis_bar),
+                    app.user_config.theme.analysis_bar_text.bg(app
+                        .user_config
+                        .theme
+                        .analysis_bar
+                        .fg),
                 )
                 .render(f, chunks[1]);
         } else {
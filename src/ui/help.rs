@@ -1,96 +1,664 @@
-pub fn get_help_docs() -> Vec<Vec<&'static str>> {
+use crate::{
+    app::{ActiveBlock, App},
+    event::Key,
+    user_config::UserConfig,
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HelpContext {
+    General,
+    HelpMenu,
+    SelectedBlock,
+    SearchInput,
+    Pagination,
+    LibraryAlbums,
+    LibraryArtists,
+    LibraryPodcasts,
+    Playlist,
+    SearchResult,
+    Queue,
+    RecommendationsBuilder,
+    PlayBar,
+    Devices,
+}
+
+// The order here is the order the groups are shown in
+const HELP_CONTEXTS: [HelpContext; 14] = [
+    HelpContext::General,
+    HelpContext::SelectedBlock,
+    HelpContext::SearchInput,
+    HelpContext::Pagination,
+    HelpContext::LibraryAlbums,
+    HelpContext::LibraryArtists,
+    HelpContext::LibraryPodcasts,
+    HelpContext::Playlist,
+    HelpContext::SearchResult,
+    HelpContext::Queue,
+    HelpContext::RecommendationsBuilder,
+    HelpContext::PlayBar,
+    HelpContext::Devices,
+    HelpContext::HelpMenu,
+];
+
+impl HelpContext {
+    pub fn title(self) -> &'static str {
+        match self {
+            HelpContext::General => "General",
+            HelpContext::HelpMenu => "Help menu",
+            HelpContext::SelectedBlock => "Selected block",
+            HelpContext::SearchInput => "Search input",
+            HelpContext::Pagination => "Pagination",
+            HelpContext::LibraryAlbums => "Library -> Albums",
+            HelpContext::LibraryArtists => "Library -> Artists",
+            HelpContext::LibraryPodcasts => "Library -> Podcasts",
+            HelpContext::Playlist => "Playlist",
+            HelpContext::SearchResult => "Search result",
+            HelpContext::Queue => "Queue",
+            HelpContext::RecommendationsBuilder => "Recommendations builder",
+            HelpContext::PlayBar => "Playbar",
+            HelpContext::Devices => "Devices",
+        }
+    }
+
+    // Whether the keys of this context do anything while `block` is active
+    fn is_valid_in(self, block: ActiveBlock) -> bool {
+        match self {
+            HelpContext::General | HelpContext::HelpMenu => true,
+            HelpContext::SelectedBlock => block != ActiveBlock::Input,
+            HelpContext::SearchInput => block == ActiveBlock::Input,
+            HelpContext::Pagination => block == ActiveBlock::TrackTable,
+            HelpContext::LibraryAlbums => block == ActiveBlock::AlbumList,
+            HelpContext::LibraryArtists => block == ActiveBlock::Artists,
+            HelpContext::LibraryPodcasts => {
+                block == ActiveBlock::Podcasts || block == ActiveBlock::EpisodeTable
+            }
+            HelpContext::Playlist => block == ActiveBlock::MyPlaylists,
+            HelpContext::SearchResult => block == ActiveBlock::SearchResultBlock,
+            HelpContext::Queue => block == ActiveBlock::Queue,
+            HelpContext::RecommendationsBuilder => block == ActiveBlock::RecommendationsBuilder,
+            HelpContext::PlayBar => block == ActiveBlock::PlayBar,
+            HelpContext::Devices => block == ActiveBlock::SelectDevice,
+        }
+    }
+}
+
+pub struct HelpItem {
+    pub description: String,
+    pub event: String,
+    pub context: HelpContext,
+}
+
+impl HelpItem {
+    fn new(description: &str, event: &str, context: HelpContext) -> HelpItem {
+        HelpItem {
+            description: description.to_string(),
+            event: event.to_string(),
+            context,
+        }
+    }
+
+    fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.description.to_lowercase().contains(&filter)
+            || self.event.to_lowercase().contains(&filter)
+            || self.context.title().to_lowercase().contains(&filter)
+    }
+}
+
+/// A line of the help table: either the heading of a context group or a key in that group
+pub enum HelpRow {
+    Heading(&'static str),
+    Item(HelpItem),
+}
+
+fn format_seconds(milliseconds: u32) -> String {
+    match milliseconds {
+        1000 => "1 second".to_string(),
+        ms if ms % 1000 == 0 => format!("{} seconds", ms / 1000),
+        ms => format!("{:.1} seconds", f64::from(ms) / 1000.0),
+    }
+}
+
+fn key_list(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+pub fn get_help_docs(user_config: &UserConfig) -> Vec<HelpItem> {
+    let key_bindings = &user_config.keys;
+    let behavior = &user_config.behavior;
+
     vec![
-        vec!["Jump to currently playing album", "a", "General"],
-        vec![
+        HelpItem::new(
+            "Jump to currently playing album",
+            &key_bindings.jump_to_album.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
             "Jump to currently playing artist's album list",
-            "A",
-            "General",
-        ],
-        vec!["Increase volume by 10%", "+", "General"],
-        vec!["Decrease volume by 10%", "-", "General"],
-        vec!["Skip to next track", "n", "General"],
-        vec!["Skip to previous track", "p", "General"],
-        vec!["Seek backwards 5 seconds", "<", "General"],
-        vec!["Seek forwards 5 seconds", ">", "General"],
-        vec!["Toggle shuffle", "<Ctrl+s>", "General"],
-        vec!["Copy url to currently playing song", "c", "General"],
-        vec!["Copy url to currently playing album", "C", "General"],
-        vec!["Cycle repeat mode", "<Ctrl+r>", "General"],
-        vec![
+            &key_bindings.jump_to_artist_album.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            &format!("Increase volume by {}%", behavior.volume_increment),
+            &key_bindings.increase_volume.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            &format!("Decrease volume by {}%", behavior.volume_increment),
+            &key_bindings.decrease_volume.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Mute/unmute",
+            &key_bindings.toggle_mute.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Set the volume",
+            &key_bindings.volume_prompt.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Skip to next track",
+            &key_bindings.next_track.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Skip to previous track",
+            &key_bindings.previous_track.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            &format!(
+                "Seek backwards {}",
+                format_seconds(behavior.seek_milliseconds)
+            ),
+            &key_bindings.seek_backwards.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            &format!(
+                "Seek forwards {}",
+                format_seconds(behavior.seek_milliseconds)
+            ),
+            &key_bindings.seek_forwards.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Toggle shuffle",
+            &key_bindings.shuffle.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Copy url to currently playing song",
+            &key_bindings.copy_song_url.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Copy url to currently playing album",
+            &key_bindings.copy_album_url.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Cycle repeat mode",
+            &key_bindings.repeat.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
             "Move selection left",
-            "h | <Left Arrow Key> | <Ctrl+b>",
-            "General",
-        ],
-        vec![
+            &key_list(&[Key::Char('h'), Key::Left, Key::Ctrl('b')]),
+            HelpContext::General,
+        ),
+        HelpItem::new(
             "Move selection down",
-            "j | <Down Arrow Key> | <Ctrl+n>",
-            "General",
-        ],
-        vec![
+            &key_list(&[Key::Char('j'), Key::Down, Key::Ctrl('n')]),
+            HelpContext::General,
+        ),
+        HelpItem::new(
             "Move selection up",
-            "k | <Up Arrow Key> | <Ctrl+p>",
-            "General",
-        ],
-        vec![
+            &key_list(&[Key::Char('k'), Key::Up, Key::Ctrl('p')]),
+            HelpContext::General,
+        ),
+        HelpItem::new(
             "Move selection right",
-            "l | <Right Arrow Key> | <Ctrl+f>",
-            "General",
-        ],
-        vec!["Move selection to top of list", "H", "General"],
-        vec!["Move selection to middle of list", "M", "General"],
-        vec!["Move selection to bottom of list", "L", "General"],
-        vec!["Enter input for search", "/", "General"],
-        vec!["Pause/Resume playback", "<Space>", "General"],
-        vec!["Enter active mode", "<Enter>", "General"],
-        vec!["Go to audio analysis screen", "v", "General"],
-        vec![
+            &key_list(&[Key::Char('l'), Key::Right, Key::Ctrl('f')]),
+            HelpContext::General,
+        ),
+        HelpItem::new("Move selection to top of list", "H", HelpContext::General),
+        HelpItem::new(
+            "Move selection to middle of list",
+            "M",
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Move selection to bottom of list",
+            "L",
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Enter input for search",
+            &key_bindings.search.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Pause/Resume playback",
+            &key_bindings.toggle_playback.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Enter active mode",
+            &Key::Enter.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Go to audio analysis screen",
+            &key_bindings.audio_analysis.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
             "Go back or exit when nowhere left to back to",
-            "q",
-            "General",
-        ],
-        vec!["Select device to play music on", "d", "General"],
-        vec!["Enter hover mode", "<Esc>", "Selected block"],
-        vec!["Save track in list or table", "s", "Selected block"],
-        vec![
+            &key_bindings.back.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Select device to play music on",
+            &key_bindings.manage_devices.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Move playback to the next device",
+            &key_bindings.next_device.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Show/hide the sidebar",
+            &key_bindings.toggle_sidebar.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Show the play queue",
+            &key_bindings.show_queue.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Add the selected or playing track to playlists",
+            &key_bindings.add_to_playlist.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Save the tracks of the list as a new playlist",
+            &key_bindings.save_as_playlist.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Keep playing recommendations when an album or playlist ends",
+            &key_bindings.toggle_autoplay.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Build recommendations from seeds and audio features",
+            &key_bindings.recommendations_builder.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Cycle the sleep timer (minutes, end of track, end of album/playlist, off)",
+            &key_bindings.sleep_timer.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new("Seek to 0-90% of the track", "0-9", HelpContext::General),
+        HelpItem::new(
+            "Seek to a time, a jump or a percentage",
+            &key_bindings.seek_prompt.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Seek to the next section of the track",
+            &key_bindings.next_section.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Seek to the previous section of the track",
+            &key_bindings.previous_section.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Mark loop point A, then B, then clear the loop",
+            &key_bindings.ab_loop.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Show this help menu",
+            &key_bindings.help.to_string(),
+            HelpContext::General,
+        ),
+        HelpItem::new(
+            "Enter hover mode",
+            &Key::Esc.to_string(),
+            HelpContext::SelectedBlock,
+        ),
+        HelpItem::new(
+            "Save track in list or table",
+            "s",
+            HelpContext::SelectedBlock,
+        ),
+        HelpItem::new(
             "Start playback or enter album/artist/playlist",
-            "<Enter>",
-            "Selected block",
-        ],
-        vec![
+            &key_bindings.submit.to_string(),
+            HelpContext::SelectedBlock,
+        ),
+        HelpItem::new(
+            "Add track to the end of the queue",
+            &key_bindings.add_to_queue.to_string(),
+            HelpContext::SelectedBlock,
+        ),
+        HelpItem::new(
+            "Play track after the current one",
+            &key_bindings.play_next.to_string(),
+            HelpContext::SelectedBlock,
+        ),
+        HelpItem::new(
             "Play recommendations for song/artist",
             "r",
-            "Selected block",
-        ],
-        vec!["Play all tracks for artist", "e", "Library -> Artists"],
-        vec!["Delete entire input", "<Ctrl+u>", "Search input"],
-        vec!["Search with input text", "<Enter>", "Search input"],
-        vec![
+            HelpContext::SelectedBlock,
+        ),
+        HelpItem::new(
+            "Play all tracks for artist",
+            "e",
+            HelpContext::LibraryArtists,
+        ),
+        HelpItem::new(
+            "Delete entire input",
+            &Key::Ctrl('u').to_string(),
+            HelpContext::SearchInput,
+        ),
+        HelpItem::new(
+            "Search with input text",
+            &Key::Enter.to_string(),
+            HelpContext::SearchInput,
+        ),
+        HelpItem::new(
             "Move cursor one space left",
-            "<Left Arrow Key>",
-            "Search input",
-        ],
-        vec![
+            &Key::Left.to_string(),
+            HelpContext::SearchInput,
+        ),
+        HelpItem::new(
             "Move cursor one space right",
-            "<Right Arrow Key>",
-            "Search input",
-        ],
-        vec!["Jump to start of input", "<Ctrl+a>", "Search input"],
-        vec!["Jump to end of input", "<Ctrl+e>", "Search input"],
-        vec![
+            &Key::Right.to_string(),
+            HelpContext::SearchInput,
+        ),
+        HelpItem::new(
+            "Jump to start of input",
+            &Key::Ctrl('a').to_string(),
+            HelpContext::SearchInput,
+        ),
+        HelpItem::new(
+            "Jump to end of input",
+            &Key::Ctrl('e').to_string(),
+            HelpContext::SearchInput,
+        ),
+        HelpItem::new(
             "Escape from the input back to hovered block",
-            "<Esc>",
-            "Search input",
-        ],
-        vec!["Scroll down to next result page", "<Ctrl+d>", "Pagination"],
-        vec![
+            &Key::Esc.to_string(),
+            HelpContext::SearchInput,
+        ),
+        HelpItem::new(
+            "Scroll down to next result page",
+            &Key::Ctrl('d').to_string(),
+            HelpContext::Pagination,
+        ),
+        HelpItem::new(
             "Scroll up to previous result page",
-            "<Ctrl+u>",
-            "Pagination",
-        ],
-        vec!["Jump to start of playlist", "<Ctrl+a>", "Pagination"],
-        vec!["Jump to end of playlist", "<Ctrl+e>", "Pagination"],
-        vec!["Delete saved album", "D", "Library -> Albums"],
-        vec!["Delete saved playist", "D", "Playlist"],
-        vec!["Follow an artists/playlist", "w", "Search result"],
+            &Key::Ctrl('u').to_string(),
+            HelpContext::Pagination,
+        ),
+        HelpItem::new(
+            "Jump to start of playlist",
+            &Key::Ctrl('a').to_string(),
+            HelpContext::Pagination,
+        ),
+        HelpItem::new(
+            "Jump to end of playlist",
+            &Key::Ctrl('e').to_string(),
+            HelpContext::Pagination,
+        ),
+        HelpItem::new("Delete saved album", "D", HelpContext::LibraryAlbums),
+        HelpItem::new(
+            "Open a show, or play an episode from where you left off",
+            &Key::Enter.to_string(),
+            HelpContext::LibraryPodcasts,
+        ),
+        HelpItem::new(
+            "Next/previous page of shows or episodes",
+            &key_list(&[Key::Ctrl('d'), Key::Ctrl('u')]),
+            HelpContext::LibraryPodcasts,
+        ),
+        HelpItem::new("Save the show", "w", HelpContext::LibraryPodcasts),
+        HelpItem::new("Remove the show", "D", HelpContext::LibraryPodcasts),
+        HelpItem::new("Delete saved playist", "D", HelpContext::Playlist),
+        HelpItem::new("Create a playlist", "N", HelpContext::Playlist),
+        HelpItem::new(
+            "Rename, describe or share the selected playlist",
+            "e",
+            HelpContext::Playlist,
+        ),
+        HelpItem::new("Follow an artists/playlist", "w", HelpContext::SearchResult),
+        HelpItem::new(
+            "Play track now",
+            &Key::Enter.to_string(),
+            HelpContext::Queue,
+        ),
+        HelpItem::new("Remove track from queue", "D", HelpContext::Queue),
+        HelpItem::new("Move track up", "K", HelpContext::Queue),
+        HelpItem::new("Move track down", "J", HelpContext::Queue),
+        HelpItem::new(
+            "Add a seed or set the selected value",
+            &Key::Enter.to_string(),
+            HelpContext::RecommendationsBuilder,
+        ),
+        HelpItem::new(
+            "Select the minimum, target or maximum",
+            &key_list(&[Key::Char('h'), Key::Char('l')]),
+            HelpContext::RecommendationsBuilder,
+        ),
+        HelpItem::new(
+            "Remove the seed or clear the value",
+            "x",
+            HelpContext::RecommendationsBuilder,
+        ),
+        HelpItem::new(
+            "Show the recommendations",
+            "r",
+            HelpContext::RecommendationsBuilder,
+        ),
+        HelpItem::new(
+            "Move playback to the selected device",
+            &Key::Enter.to_string(),
+            HelpContext::Devices,
+        ),
+        HelpItem::new("Refresh the devices", "r", HelpContext::Devices),
+        HelpItem::new(
+            "Snap loop points to bars, sections or nothing",
+            "g",
+            HelpContext::PlayBar,
+        ),
+        HelpItem::new(
+            "Number of times to loop (1, 2, 3, 5, 10 or no limit)",
+            "x",
+            HelpContext::PlayBar,
+        ),
+        HelpItem::new(
+            "Filter the help menu",
+            &key_bindings.search.to_string(),
+            HelpContext::HelpMenu,
+        ),
+        HelpItem::new(
+            "Stop editing the filter",
+            &key_list(&[Key::Enter, Key::Esc]),
+            HelpContext::HelpMenu,
+        ),
+        HelpItem::new(
+            "Toggle showing only keys for the current block",
+            &Key::Tab.to_string(),
+            HelpContext::HelpMenu,
+        ),
+        HelpItem::new(
+            "Scroll down a page",
+            &key_list(&[Key::Char('j'), Key::Down, Key::Ctrl('d')]),
+            HelpContext::HelpMenu,
+        ),
+        HelpItem::new(
+            "Scroll up a page",
+            &key_list(&[Key::Char('k'), Key::Up, Key::Ctrl('u')]),
+            HelpContext::HelpMenu,
+        ),
     ]
 }
+
+/// Groups the help docs by context, keeping only the items that match `filter` and, when
+/// `block` is given, only the contexts whose keys work in that block.
+pub fn group_help_docs(
+    help_docs: Vec<HelpItem>,
+    filter: &str,
+    block: Option<ActiveBlock>,
+) -> Vec<HelpRow> {
+    let mut help_docs = help_docs
+        .into_iter()
+        .filter(|item| item.matches(filter))
+        .filter(|item| match block {
+            Some(block) => item.context.is_valid_in(block),
+            None => true,
+        })
+        .collect::<Vec<HelpItem>>();
+
+    let mut rows = vec![];
+    for context in HELP_CONTEXTS.iter() {
+        let (group, rest): (Vec<HelpItem>, Vec<HelpItem>) = help_docs
+            .into_iter()
+            .partition(|item| item.context == *context);
+        help_docs = rest;
+
+        if !group.is_empty() {
+            rows.push(HelpRow::Heading(context.title()));
+            rows.extend(group.into_iter().map(HelpRow::Item));
+        }
+    }
+    rows
+}
+
+pub fn get_help_rows(app: &App) -> Vec<HelpRow> {
+    let block = if app.help_menu_context_only {
+        Some(app.help_menu_block)
+    } else {
+        None
+    };
+
+    group_help_docs(
+        get_help_docs(&app.user_config),
+        &app.help_menu_filter,
+        block,
+    )
+}
+
+// Name of the block that the context-sensitive help menu is showing keys for
+pub fn get_block_title(block: ActiveBlock) -> &'static str {
+    match block {
+        ActiveBlock::Analysis => "Audio analysis",
+        ActiveBlock::PlayBar => "Playbar",
+        ActiveBlock::AlbumTracks => "Album tracks",
+        ActiveBlock::AlbumList => "Albums",
+        ActiveBlock::ArtistBlock => "Artist",
+        ActiveBlock::Empty => "Hovered block",
+        ActiveBlock::Error => "Error",
+        ActiveBlock::HelpMenu => "Help",
+        ActiveBlock::Home => "Home",
+        ActiveBlock::Input => "Search input",
+        ActiveBlock::Library => "Library",
+        ActiveBlock::MyPlaylists => "Playlists",
+        ActiveBlock::PlaylistForm => "Playlist form",
+        ActiveBlock::PlaylistPicker => "Add to playlists",
+        ActiveBlock::Podcasts => "Podcasts",
+        ActiveBlock::EpisodeTable => "Episodes",
+        ActiveBlock::RecentlyPlayed => "Recently played",
+        ActiveBlock::SearchResultBlock => "Search results",
+        ActiveBlock::SelectDevice => "Devices",
+        ActiveBlock::TrackTable => "Tracks",
+        ActiveBlock::MadeForYou => "Made For You",
+        ActiveBlock::Artists => "Artists",
+        ActiveBlock::Queue => "Queue",
+        ActiveBlock::RecommendationsBuilder => "Recommendations builder",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item_descriptions(rows: &[HelpRow]) -> Vec<&str> {
+        rows.iter()
+            .filter_map(|row| match row {
+                HelpRow::Item(item) => Some(item.description.as_str()),
+                HelpRow::Heading(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_help_docs_use_user_config() {
+        let mut user_config = UserConfig::new();
+        user_config.keys.increase_volume = Key::Ctrl('k');
+        user_config.behavior.volume_increment = 5;
+        user_config.behavior.seek_milliseconds = 2500;
+
+        let help_docs = get_help_docs(&user_config);
+
+        let volume = help_docs
+            .iter()
+            .find(|item| item.description == "Increase volume by 5%")
+            .unwrap();
+        assert_eq!(volume.event, "ctrl-k");
+        assert!(help_docs
+            .iter()
+            .any(|item| item.description == "Seek forwards 2.5 seconds"));
+    }
+
+    #[test]
+    fn test_group_help_docs_filter() {
+        let user_config = UserConfig::new();
+
+        let rows = group_help_docs(get_help_docs(&user_config), "VOLUME", None);
+        assert_eq!(
+            item_descriptions(&rows),
+            vec![
+                "Increase volume by 10%",
+                "Decrease volume by 10%",
+                "Set the volume"
+            ]
+        );
+        match rows.first() {
+            Some(HelpRow::Heading(title)) => assert_eq!(*title, "General"),
+            _ => panic!("the first row should be a heading"),
+        }
+
+        let rows = group_help_docs(get_help_docs(&user_config), "no such key", None);
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_group_help_docs_context() {
+        let user_config = UserConfig::new();
+
+        let rows = group_help_docs(
+            get_help_docs(&user_config),
+            "",
+            Some(ActiveBlock::AlbumList),
+        );
+        let descriptions = item_descriptions(&rows);
+        assert!(descriptions.contains(&"Delete saved album"));
+        assert!(descriptions.contains(&"Skip to next track"));
+        assert!(!descriptions.contains(&"Delete saved playist"));
+        assert!(!descriptions.contains(&"Search with input text"));
+    }
+}
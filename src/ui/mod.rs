@@ -1,26 +1,40 @@
 pub mod audio_analysis;
 pub mod help;
+pub mod setup;
 pub mod util;
 use super::{
+    ab_loop::{LoopRegion, Snap},
     app::{
-        ActiveBlock, AlbumTableContext, App, ArtistBlock, RecommendationsContext, RouteId,
-        SearchResultBlock, LIBRARY_OPTIONS,
+        ActiveBlock, AlbumTableContext, App, ArtistBlock, InputPrompt, RecommendationsContext,
+        RouteId, SearchResultBlock, TrackAdded, LIBRARY_OPTIONS,
     },
     banner::BANNER,
+    playlist_form::{FormRow, FORM_ROWS},
+    recommendations::{Bound, BuilderRow, MAX_SEEDS},
+    user_config::{ColumnWidth, PlaybarPosition, TrackColumn, TrackColumnConfig},
 };
-use help::get_help_docs;
-use rspotify::spotify::senum::RepeatState;
+use help::{get_block_title, get_help_rows, HelpRow};
+use rspotify::spotify::{
+    model::{
+        artist::SimplifiedArtist,
+        track::{FullTrack, SimplifiedTrack},
+    },
+    senum::RepeatState,
+};
+use std::cmp::{max, min};
 use tui::{
     backend::Backend,
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::Modifier,
     widgets::{Block, Borders, Gauge, Paragraph, Row, SelectableList, Table, Text, Widget},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 use util::{
-    create_artist_string, display_track_progress, get_artist_highlight_state, get_color,
-    get_percentage_width, get_search_results_highlight_state, get_track_progress_percentage,
-    millis_to_minutes,
+    create_artist_string, display_device, display_track_progress, get_artist_highlight_state,
+    get_color, get_key_name, get_percentage_width, get_search_results_highlight_state,
+    get_track_progress_column, get_track_progress_percentage, millis_to_minutes, with_modifier,
 };
 
 pub enum TableId {
@@ -30,6 +44,12 @@ pub enum TableId {
     Song,
     RecentlyPlayed,
     MadeForYou,
+    Queue,
+    RecommendationsBuilder,
+    Podcasts,
+    Episodes,
+    PlaylistForm,
+    PlaylistPicker,
 }
 
 #[derive(PartialEq)]
@@ -74,37 +94,65 @@ where
 {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(100)].as_ref())
+        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
         .margin(2)
         .split(f.size());
 
-    let white = Style::default().fg(app.user_config.theme.text);
-    let gray = Style::default().fg(app.user_config.theme.text);
-    let header = ["Description", "Event", "Context"];
+    let white = app.user_config.theme.text;
+    let gray = app.user_config.theme.text;
+    let heading = with_modifier(app.user_config.theme.active, Modifier::BOLD);
+    let header = ["Description", "Event"];
 
-    let help_docs = get_help_docs();
-    let help_docs = &help_docs[app.help_menu_offset as usize..];
+    let filter_title = if app.help_menu_filter_active {
+        "Filter (press <Enter> to stop editing)".to_string()
+    } else {
+        format!("Filter (press {} to edit)", app.user_config.keys.search)
+    };
+    let filter_style = get_color((app.help_menu_filter_active, false), app.user_config.theme);
+    Paragraph::new([Text::raw(&app.help_menu_filter)].iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(&filter_title)
+                .title_style(filter_style)
+                .border_style(filter_style),
+        )
+        .style(white)
+        .render(f, chunks[0]);
 
-    let rows = help_docs
-        .iter()
-        .map(|item| Row::StyledData(item.iter(), gray));
+    let rows = get_help_rows(app)
+        .into_iter()
+        .skip(app.help_menu_offset as usize)
+        .map(|row| match row {
+            HelpRow::Heading(title) => {
+                Row::StyledData(vec![title.to_string(), String::new()].into_iter(), heading)
+            }
+            HelpRow::Item(item) => {
+                Row::StyledData(vec![item.description, item.event].into_iter(), gray)
+            }
+        });
+
+    let title = if app.help_menu_context_only {
+        format!(
+            "Help: {} (press <Tab> to show all, <Esc> to go back)",
+            get_block_title(app.help_menu_block)
+        )
+    } else {
+        "Help (press <Tab> to show current block only, <Esc> to go back)".to_string()
+    };
 
     Table::new(header.iter(), rows)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .style(white)
-                .title("Help (press <Esc> to go back)")
+                .title(&title)
                 .title_style(gray)
                 .border_style(gray),
         )
-        .style(Style::default().fg(app.user_config.theme.text))
-        .widths(&[
-            Constraint::Length(50),
-            Constraint::Length(40),
-            Constraint::Length(20),
-        ])
-        .render(f, chunks[0]);
+        .style(app.user_config.theme.text)
+        .widths(&[Constraint::Length(50), Constraint::Length(40)])
+        .render(f, chunks[1]);
 }
 
 pub fn draw_input_and_help_box<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
@@ -123,12 +171,26 @@ where
         current_route.hovered_block == ActiveBlock::Input,
     );
 
+    let title = match app.input_prompt {
+        InputPrompt::Search => "Search".to_string(),
+        InputPrompt::Seek => "Seek to (1:23, +30s, -2m or 50%)".to_string(),
+        InputPrompt::Volume => "Volume (0-100%)".to_string(),
+        InputPrompt::RecommendationsSeed => {
+            "Add a seed (artist name, track:<name> or genre:<name>)".to_string()
+        }
+        InputPrompt::RecommendationsValue => app.recommendations_builder.get_value_prompt(),
+        InputPrompt::PlaylistFormText => match &app.playlist_form {
+            Some(form) => format!("Playlist {}", form.selected_row().title().to_lowercase()),
+            None => "Playlist".to_string(),
+        },
+    };
+
     let input_string: String = app.input.iter().collect();
     Paragraph::new([Text::raw(&input_string)].iter())
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Search")
+                .title(&title)
                 .title_style(get_color(highlight_state, app.user_config.theme))
                 .border_style(get_color(highlight_state, app.user_config.theme)),
         )
@@ -137,12 +199,12 @@ where
     let block = Block::default()
         .title("Help")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(app.user_config.theme.inactive))
-        .title_style(Style::default().fg(app.user_config.theme.inactive));
+        .border_style(app.user_config.theme.inactive)
+        .title_style(app.user_config.theme.inactive);
 
     Paragraph::new([Text::raw("Type ?")].iter())
         .block(block)
-        .style(Style::default().fg(app.user_config.theme.inactive))
+        .style(app.user_config.theme.inactive)
         .render(f, chunks[1]);
 }
 
@@ -151,39 +213,80 @@ where
     B: Backend,
 {
     let margin = util::get_main_layout_margin(app);
+    let layout = &app.user_config.layout;
+    let show_search = app.is_block_visible(ActiveBlock::Input);
+
+    let mut constraints = vec![];
+    if layout.playbar_position == PlaybarPosition::Top {
+        constraints.push(Constraint::Length(layout.playbar_height));
+    }
+    if show_search {
+        constraints.push(Constraint::Length(3));
+    }
+    constraints.push(Constraint::Min(1));
+    if layout.playbar_position == PlaybarPosition::Bottom {
+        constraints.push(Constraint::Length(layout.playbar_height));
+    }
+
     let parent_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Length(3),
-                Constraint::Min(1),
-                Constraint::Length(6),
-            ]
-            .as_ref(),
-        )
+        .constraints(constraints)
         .margin(margin)
         .split(f.size());
 
+    let mut chunks = parent_layout.into_iter();
+    let playbar_chunk = match layout.playbar_position {
+        PlaybarPosition::Top => chunks.next(),
+        PlaybarPosition::Bottom => chunks.next_back(),
+    };
+
     // Search input and help
-    draw_input_and_help_box(f, app, parent_layout[0]);
+    if show_search {
+        if let Some(chunk) = chunks.next() {
+            draw_input_and_help_box(f, app, chunk);
+        }
+    }
 
     // Nested main block with potential routes
-    draw_routes(f, app, parent_layout[1]);
+    if let Some(chunk) = chunks.next() {
+        draw_routes(f, app, chunk);
+        if app.playlist_form.is_some() {
+            draw_playlist_form(f, app, chunk);
+        }
+        if app.playlist_picker.is_some() {
+            draw_playlist_picker(f, app, chunk);
+        }
+    }
 
     // Currently playing
-    draw_playbar(f, app, parent_layout[2]);
+    if let Some(chunk) = playbar_chunk {
+        draw_playbar(f, app, chunk);
+    }
 }
 
 pub fn draw_routes<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
 where
     B: Backend,
 {
+    let sidebar_width = if app.is_sidebar_visible() {
+        app.user_config.layout.sidebar_width_percent
+    } else {
+        0
+    };
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(20), Constraint::Percentage(80)].as_ref())
+        .constraints(
+            [
+                Constraint::Percentage(sidebar_width),
+                Constraint::Percentage(100 - sidebar_width),
+            ]
+            .as_ref(),
+        )
         .split(layout_chunk);
 
-    draw_user_block(f, app, chunks[0]);
+    if app.is_sidebar_visible() {
+        draw_user_block(f, app, chunks[0]);
+    }
 
     let current_route = app.get_current_route();
 
@@ -216,11 +319,20 @@ where
             draw_artist_table(f, app, chunks[1]);
         }
         RouteId::Podcasts => {
-            draw_not_implemented_yet(f, app, chunks[1], ActiveBlock::Podcasts, "Podcasts");
+            draw_podcasts(f, app, chunks[1]);
+        }
+        RouteId::PodcastEpisodes => {
+            draw_show_episodes(f, app, chunks[1]);
         }
         RouteId::Recommendations => {
             draw_recommendations_table(f, app, chunks[1]);
         }
+        RouteId::Queue => {
+            draw_queue_table(f, app, chunks[1]);
+        }
+        RouteId::RecommendationsBuilder => {
+            draw_recommendations_builder(f, app, chunks[1]);
+        }
         RouteId::Error => {} // This is handled as a "full screen" route in main.rs
         RouteId::SelectedDevice => {} // This is handled as a "full screen" route in main.rs
         RouteId::Analysis => {} // This is handled as a "full screen" route in main.rs
@@ -278,13 +390,23 @@ pub fn draw_user_block<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
 where
     B: Backend,
 {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-        .split(layout_chunk);
-
-    draw_library_block(f, app, chunks[0]);
-    draw_playlist_block(f, app, chunks[1]);
+    match (
+        app.is_block_visible(ActiveBlock::Library),
+        app.is_block_visible(ActiveBlock::MyPlaylists),
+    ) {
+        (true, true) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+                .split(layout_chunk);
+
+            draw_library_block(f, app, chunks[0]);
+            draw_playlist_block(f, app, chunks[1]);
+        }
+        (true, false) => draw_library_block(f, app, layout_chunk),
+        (false, true) => draw_playlist_block(f, app, layout_chunk),
+        (false, false) => {}
+    }
 }
 
 pub fn draw_search_results<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
@@ -445,31 +567,8 @@ pub fn draw_album_table<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
 where
     B: Backend,
 {
-    let header = TableHeader {
-        id: TableId::Album,
-        items: vec![
-            TableHeaderItem {
-                id: ColumnId::Liked,
-                text: "",
-                width: 2,
-            },
-            TableHeaderItem {
-                text: "#",
-                width: 3,
-                ..Default::default()
-            },
-            TableHeaderItem {
-                id: ColumnId::SongTitle,
-                text: "Title",
-                width: get_percentage_width(layout_chunk.width, 0.80),
-            },
-            TableHeaderItem {
-                text: "Length",
-                width: get_percentage_width(layout_chunk.width, 0.15),
-                ..Default::default()
-            },
-        ],
-    };
+    let columns = &app.user_config.track_tables.album;
+    let header = get_track_table_header(TableId::Album, columns, layout_chunk.width);
 
     let current_route = app.get_current_route();
     let highlight_state = (
@@ -484,14 +583,15 @@ where
                     .tracks
                     .items
                     .iter()
-                    .map(|item| TableItem {
-                        id: item.id.clone().unwrap_or_else(|| "".to_string()),
-                        format: vec![
-                            "".to_string(),
-                            item.track_number.to_string(),
-                            item.name.to_owned(),
-                            millis_to_minutes(u128::from(item.duration_ms)),
-                        ],
+                    .map(|item| {
+                        get_track_table_item(
+                            app,
+                            &TrackRow::from_simplified_track(
+                                item,
+                                Some(&selected_album_simplified.album.name),
+                            ),
+                            columns,
+                        )
                     })
                     .collect::<Vec<TableItem>>(),
                 title: format!(
@@ -510,14 +610,15 @@ where
                     .tracks
                     .items
                     .iter()
-                    .map(|item| TableItem {
-                        id: item.id.clone().unwrap_or_else(|| "".to_string()),
-                        format: vec![
-                            "".to_string(),
-                            item.track_number.to_string(),
-                            item.name.to_owned(),
-                            millis_to_minutes(u128::from(item.duration_ms)),
-                        ],
+                    .map(|item| {
+                        get_track_table_item(
+                            app,
+                            &TrackRow::from_simplified_track(
+                                item,
+                                Some(&selected_album.album.name),
+                            ),
+                            columns,
+                        )
                     })
                     .collect::<Vec<TableItem>>(),
                 title: format!(
@@ -545,21 +646,71 @@ where
 }
 
 pub fn draw_recommendations_table<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let columns = &app.user_config.track_tables.song;
+    let header = get_track_table_header(TableId::Song, columns, layout_chunk.width);
+
+    let current_route = app.get_current_route();
+    let highlight_state = (
+        current_route.active_block == ActiveBlock::TrackTable,
+        current_route.hovered_block == ActiveBlock::TrackTable,
+    );
+
+    let items = app
+        .track_table
+        .tracks
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let added = app.track_table.added.get(i);
+            get_track_table_item(app, &TrackRow::from_full_track(item, added), columns)
+        })
+        .collect::<Vec<TableItem>>();
+    // match RecommendedContext
+    let recommendations_ui = match &app.recommendations_context {
+        Some(RecommendationsContext::Song) => format!(
+            "Recommendations based on Song \'{}\'",
+            &app.recommendations_seed
+        ),
+        Some(RecommendationsContext::Artist) => format!(
+            "Recommendations based on Artist \'{}\'",
+            &app.recommendations_seed
+        ),
+        Some(RecommendationsContext::Builder) => format!(
+            "Recommendations based on {} (tuned)",
+            &app.recommendations_seed
+        ),
+        None => "Recommendations".to_string(),
+    };
+    draw_table(
+        f,
+        app,
+        layout_chunk,
+        (&recommendations_ui[..], &header),
+        &items,
+        app.track_table.selected_index,
+        highlight_state,
+    )
+}
+
+pub fn draw_queue_table<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
 where
     B: Backend,
 {
     let header = TableHeader {
-        id: TableId::Song,
+        id: TableId::Queue,
         items: vec![
             TableHeaderItem {
-                id: ColumnId::Liked,
-                text: "",
-                width: 2,
+                text: "#",
+                width: 4,
+                ..Default::default()
             },
             TableHeaderItem {
-                id: ColumnId::SongTitle,
                 text: "Title",
                 width: get_percentage_width(layout_chunk.width, 0.3),
+                ..Default::default()
             },
             TableHeaderItem {
                 text: "Artist",
@@ -568,7 +719,7 @@ where
             },
             TableHeaderItem {
                 text: "Album",
-                width: get_percentage_width(layout_chunk.width, 0.3),
+                width: get_percentage_width(layout_chunk.width, 0.2),
                 ..Default::default()
             },
             TableHeaderItem {
@@ -581,83 +732,312 @@ where
 
     let current_route = app.get_current_route();
     let highlight_state = (
-        current_route.active_block == ActiveBlock::TrackTable,
-        current_route.hovered_block == ActiveBlock::TrackTable,
+        current_route.active_block == ActiveBlock::Queue,
+        current_route.hovered_block == ActiveBlock::Queue,
     );
 
     let items = app
-        .track_table
+        .queue
         .tracks
         .iter()
-        .map(|item| TableItem {
-            id: item.id.clone().unwrap_or_else(|| "".to_string()),
+        .enumerate()
+        .map(|(i, track)| TableItem {
+            id: track.id.clone().unwrap_or_default(),
             format: vec![
-                "".to_string(),
-                item.name.to_owned(),
-                create_artist_string(&item.artists),
-                item.album.name.to_owned(),
-                millis_to_minutes(u128::from(item.duration_ms)),
+                (i + 1).to_string(),
+                track.name.to_owned(),
+                create_artist_string(&track.artists),
+                track.album.clone().unwrap_or_default(),
+                millis_to_minutes(u128::from(track.duration_ms)),
             ],
         })
         .collect::<Vec<TableItem>>();
-    // match RecommendedContext
-    let recommendations_ui = match &app.recommendations_context {
-        Some(RecommendationsContext::Song) => format!(
-            "Recommendations based on Song \'{}\'",
-            &app.recommendations_seed
-        ),
-        Some(RecommendationsContext::Artist) => format!(
-            "Recommendations based on Artist \'{}\'",
-            &app.recommendations_seed
-        ),
-        None => "Recommendations".to_string(),
+
+    let title = if app.queue.tracks.is_empty() {
+        "Queue (empty)".to_string()
+    } else {
+        format!("Queue ({} tracks)", app.queue.tracks.len())
     };
+
     draw_table(
         f,
         app,
         layout_chunk,
-        (&recommendations_ui[..], &header),
+        (&title, &header),
         &items,
-        app.track_table.selected_index,
+        app.queue.selected_index,
         highlight_state,
     )
 }
 
-pub fn draw_song_table<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+// The middle of `layout_chunk`, where modal popups are drawn
+fn get_popup_area(layout_chunk: Rect, height: u16) -> Rect {
+    let width = min(max(layout_chunk.width * 3 / 5, 50), layout_chunk.width);
+    let height = min(height, layout_chunk.height);
+    Rect::new(
+        layout_chunk.x + (layout_chunk.width - width) / 2,
+        layout_chunk.y + (layout_chunk.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+pub fn draw_playlist_form<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let form = match &app.playlist_form {
+        Some(form) => form,
+        None => return,
+    };
+
+    let area = get_popup_area(layout_chunk, FORM_ROWS.len() as u16 + 5);
+
+    let header = TableHeader {
+        id: TableId::PlaylistForm,
+        items: vec![
+            TableHeaderItem {
+                text: "Field",
+                width: get_percentage_width(area.width, 0.3),
+                ..Default::default()
+            },
+            TableHeaderItem {
+                text: "Value",
+                width: get_percentage_width(area.width, 0.65),
+                ..Default::default()
+            },
+        ],
+    };
+
+    let yes_no = |value: bool| if value { "Yes" } else { "No" }.to_string();
+    let items = FORM_ROWS
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let value = match row {
+                FormRow::Name => form.name.to_owned(),
+                FormRow::Description => form.description.to_owned(),
+                FormRow::Public => yes_no(form.public),
+                FormRow::Collaborative => yes_no(form.collaborative),
+                FormRow::Submit => String::new(),
+            };
+            let title = match row {
+                FormRow::Submit => form.submit_title(),
+                _ => row.title(),
+            };
+            TableItem {
+                id: i.to_string(),
+                format: vec![title.to_string(), value],
+            }
+        })
+        .collect::<Vec<TableItem>>();
+
+    let current_route = app.get_current_route();
+    let highlight_state = (
+        current_route.active_block == ActiveBlock::PlaylistForm,
+        current_route.hovered_block == ActiveBlock::PlaylistForm,
+    );
+    let title = format!("{} (Enter: edit, Esc: cancel)", form.title());
+
+    Clear.render(f, area);
+    draw_table(
+        f,
+        app,
+        area,
+        (&title, &header),
+        &items,
+        form.selected_index,
+        highlight_state,
+    );
+}
+
+pub fn draw_playlist_picker<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
 where
     B: Backend,
 {
+    let picker = match &app.playlist_picker {
+        Some(picker) => picker,
+        None => return,
+    };
+
+    let area = get_popup_area(layout_chunk, layout_chunk.height * 3 / 4);
+    Clear.render(f, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+        .split(area);
+
+    let current_route = app.get_current_route();
+    let is_active = current_route.active_block == ActiveBlock::PlaylistPicker;
+
+    let filter_title = if picker.is_filter_active {
+        "Filter (Enter: done)"
+    } else {
+        "Filter (/ to type)"
+    };
+    let filter_style = get_color(
+        (is_active && picker.is_filter_active, false),
+        app.user_config.theme,
+    );
+    Paragraph::new([Text::raw(&picker.filter)].iter())
+        .style(app.user_config.theme.text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(filter_title)
+                .title_style(filter_style)
+                .border_style(filter_style),
+        )
+        .render(f, chunks[0]);
+
     let header = TableHeader {
-        id: TableId::Song,
+        id: TableId::PlaylistPicker,
         items: vec![
             TableHeaderItem {
-                id: ColumnId::Liked,
                 text: "",
-                width: 2,
+                width: 3,
+                ..Default::default()
             },
             TableHeaderItem {
-                id: ColumnId::SongTitle,
-                text: "Title",
-                width: get_percentage_width(layout_chunk.width, 0.3),
+                text: "Playlist",
+                width: get_percentage_width(area.width, 0.8),
+                ..Default::default()
+            },
+        ],
+    };
+
+    let items = picker
+        .get_filtered()
+        .into_iter()
+        .map(|playlist| TableItem {
+            id: playlist.id.to_owned(),
+            format: vec![
+                if picker.is_chosen(&playlist.id) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                }
+                .to_string(),
+                playlist.name.to_owned(),
+            ],
+        })
+        .collect::<Vec<TableItem>>();
+
+    let title = format!(
+        "Add \"{}\" to (Space: choose, Enter: add, Esc: cancel)",
+        picker.track_name
+    );
+    draw_table(
+        f,
+        app,
+        chunks[1],
+        (&title, &header),
+        &items,
+        picker.selected_index,
+        (is_active && !picker.is_filter_active, false),
+    );
+}
+
+pub fn draw_recommendations_builder<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let header = TableHeader {
+        id: TableId::RecommendationsBuilder,
+        items: vec![
+            TableHeaderItem {
+                text: "Seed / audio feature",
+                width: get_percentage_width(layout_chunk.width, 0.4),
+                ..Default::default()
             },
             TableHeaderItem {
-                text: "Artist",
-                width: get_percentage_width(layout_chunk.width, 0.3),
+                text: "Minimum",
+                width: get_percentage_width(layout_chunk.width, 0.15),
                 ..Default::default()
             },
             TableHeaderItem {
-                text: "Album",
-                width: get_percentage_width(layout_chunk.width, 0.3),
+                text: "Target",
+                width: get_percentage_width(layout_chunk.width, 0.15),
                 ..Default::default()
             },
             TableHeaderItem {
-                text: "Length",
-                width: get_percentage_width(layout_chunk.width, 0.1),
+                text: "Maximum",
+                width: get_percentage_width(layout_chunk.width, 0.15),
                 ..Default::default()
             },
         ],
     };
 
+    let current_route = app.get_current_route();
+    let highlight_state = (
+        current_route.active_block == ActiveBlock::RecommendationsBuilder,
+        current_route.hovered_block == ActiveBlock::RecommendationsBuilder,
+    );
+
+    let builder = &app.recommendations_builder;
+    let items = builder
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let format = match row {
+                BuilderRow::Seed(index) => {
+                    let seed = &builder.seeds[index];
+                    vec![format!("{}: {}", seed.kind, seed.name)]
+                }
+                BuilderRow::AddSeed => vec!["+ Add an artist, track or genre".to_string()],
+                BuilderRow::Tunable(tunable) => {
+                    let tuning = builder.get_tuning(tunable);
+                    let mut format = vec![tunable.title().to_string()];
+                    for bound in &[Bound::Min, Bound::Target, Bound::Max] {
+                        let value = tuning
+                            .get(*bound)
+                            .map_or_else(|| "-".to_string(), |value| tunable.format_value(value));
+                        // Show which of the values Enter and x work on
+                        if i == builder.selected_index && *bound == builder.selected_bound {
+                            format.push(format!("[{}]", value));
+                        } else {
+                            format.push(value);
+                        }
+                    }
+                    format
+                }
+            };
+            TableItem {
+                id: i.to_string(),
+                format,
+            }
+        })
+        .map(|mut item| {
+            item.format.resize(header.items.len(), String::new());
+            item
+        })
+        .collect::<Vec<TableItem>>();
+
+    let title = format!(
+        "Recommendations builder ({}/{} seeds, r: show recommendations)",
+        builder.seeds.len(),
+        MAX_SEEDS
+    );
+
+    draw_table(
+        f,
+        app,
+        layout_chunk,
+        (&title, &header),
+        &items,
+        builder.selected_index,
+        highlight_state,
+    )
+}
+
+pub fn draw_song_table<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let columns = &app.user_config.track_tables.song;
+    let header = get_track_table_header(TableId::Song, columns, layout_chunk.width);
+
     let current_route = app.get_current_route();
     let highlight_state = (
         current_route.active_block == ActiveBlock::TrackTable,
@@ -668,15 +1048,10 @@ where
         .track_table
         .tracks
         .iter()
-        .map(|item| TableItem {
-            id: item.id.clone().unwrap_or_else(|| "".to_string()),
-            format: vec![
-                "".to_string(),
-                item.name.to_owned(),
-                create_artist_string(&item.artists),
-                item.album.name.to_owned(),
-                millis_to_minutes(u128::from(item.duration_ms)),
-            ],
+        .enumerate()
+        .map(|(i, item)| {
+            let added = app.track_table.added.get(i);
+            get_track_table_item(app, &TrackRow::from_full_track(item, added), columns)
         })
         .collect::<Vec<TableItem>>();
 
@@ -723,13 +1098,42 @@ where
                 RepeatState::Context => "All",
             };
 
+            let autoplay_text = if app.is_playing_autoplay() {
+                " | Autoplay"
+            } else {
+                ""
+            };
+
+            let queue_text = if app.queue.tracks.is_empty() {
+                String::new()
+            } else {
+                format!(" | Queue: {}", app.queue.tracks.len())
+            };
+
+            let sleep_timer_text = match (&app.sleep_timer, app.get_sleep_timer_remaining()) {
+                (Some(_), Some(remaining)) => {
+                    format!(" | Sleep: {}", millis_to_minutes(remaining.as_millis()))
+                }
+                (Some(timer), None) => format!(" | Sleep: {}", timer.setting),
+                (None, _) => String::new(),
+            };
+
+            let volume_text = match app.muted_volume {
+                Some(_) => "muted".to_string(),
+                None => format!("{:-2}%", current_playback_context.device.volume_percent),
+            };
+
             let title = format!(
-                "{:-7} ({} | Shuffle: {:-3} | Repeat: {:-5} | Volume: {:-2}%)",
+                "{:-7} ({} | Shuffle: {:-3} | Repeat: {:-5} | Volume: {}{}{}{}{})",
                 play_title,
                 current_playback_context.device.name,
                 shuffle_text,
                 repeat_text,
-                current_playback_context.device.volume_percent
+                volume_text,
+                autoplay_text,
+                queue_text,
+                sleep_timer_text,
+                get_ab_loop_text(app)
             );
 
             let current_route = app.get_current_route();
@@ -757,35 +1161,145 @@ where
             Paragraph::new(
                 [Text::styled(
                     create_artist_string(&track_item.artists),
-                    Style::default().fg(app.user_config.theme.text),
+                    app.user_config.theme.text,
                 )]
                 .iter(),
             )
-            .style(Style::default().fg(app.user_config.theme.text))
+            .style(app.user_config.theme.text)
             .block(
-                Block::default().title(&track_name).title_style(
-                    Style::default()
-                        .fg(app.user_config.theme.selected)
-                        .modifier(Modifier::BOLD),
-                ),
+                Block::default()
+                    .title(&track_name)
+                    .title_style(with_modifier(
+                        app.user_config.theme.selected,
+                        Modifier::BOLD,
+                    )),
             )
             .render(f, chunks[0]);
             let perc = get_track_progress_percentage(app.song_progress_ms, track_item.duration_ms);
 
             Gauge::default()
                 .block(Block::default().title(""))
-                .style(
-                    Style::default()
-                        .fg(app.user_config.theme.playbar_progress)
-                        .bg(app.user_config.theme.playbar_background)
-                        .modifier(Modifier::ITALIC | Modifier::BOLD),
-                )
+                .style(with_modifier(
+                    app.user_config.theme.playbar_progress.bg(app
+                        .user_config
+                        .theme
+                        .playbar_background
+                        .fg),
+                    Modifier::ITALIC | Modifier::BOLD,
+                ))
                 .percent(perc)
                 .label(&display_track_progress(
                     app.song_progress_ms,
                     track_item.duration_ms,
                 ))
                 .render(f, chunks[1]);
+
+            if let Some(region) = &app.ab_loop.region {
+                if region.track_uri == track_item.uri {
+                    LoopRegionMarker {
+                        region,
+                        track_duration_ms: track_item.duration_ms,
+                    }
+                    .render(f, chunks[1]);
+                }
+            }
+        }
+    }
+}
+
+fn get_ab_loop_text(app: &App) -> String {
+    let ab_loop = &app.ab_loop;
+    let region_text = match &ab_loop.region {
+        Some(region) => match region.b_ms {
+            Some(b_ms) => format!(
+                "{}-{}",
+                millis_to_minutes(region.a_ms.into()),
+                millis_to_minutes(b_ms.into())
+            ),
+            None => format!("{}-B?", millis_to_minutes(region.a_ms.into())),
+        },
+        None if ab_loop.snap == Snap::Off && ab_loop.limit.is_none() => return String::new(),
+        None => "off".to_string(),
+    };
+
+    let count_text = match (ab_loop.limit, &ab_loop.region) {
+        (Some(limit), Some(region)) => format!(" {}/{}", region.count, limit),
+        (Some(limit), None) => format!(" x{}", limit),
+        (None, _) => String::new(),
+    };
+
+    let snap_text = if ab_loop.snap == Snap::Off {
+        String::new()
+    } else {
+        format!(", snap: {}", ab_loop.snap)
+    };
+
+    format!(" | Loop: {}{}{}", region_text, count_text, snap_text)
+}
+
+// Underlines the looped part of the progress gauge and highlights A and B
+struct LoopRegionMarker<'a> {
+    region: &'a LoopRegion,
+    track_duration_ms: u32,
+}
+
+impl<'a> Widget for LoopRegionMarker<'a> {
+    fn draw(&mut self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 {
+            return;
+        }
+        let a = get_track_progress_column(self.region.a_ms, self.track_duration_ms, area.width);
+        let b = self.region.b_ms.map_or(a, |b_ms| {
+            get_track_progress_column(b_ms, self.track_duration_ms, area.width)
+        });
+
+        for y in area.top()..area.bottom() {
+            for column in a..=b {
+                let cell = buf.get_mut(area.left() + column, y);
+                let mut modifier = cell.style.modifier | Modifier::UNDERLINED;
+                if column == a || column == b {
+                    modifier |= Modifier::REVERSED;
+                }
+                cell.set_modifier(modifier);
+            }
+        }
+    }
+}
+
+// Shown in the top right corner, over whatever is there
+pub fn draw_notification<B>(f: &mut Frame<B>, app: &App)
+where
+    B: Backend,
+{
+    let message = match app.get_notification() {
+        Some(message) => message,
+        None => return,
+    };
+
+    let size = f.size();
+    let width = min(UnicodeWidthStr::width(message) as u16 + 4, size.width);
+    let area = Rect::new(size.width - width, 0, width, min(3, size.height));
+
+    Clear.render(f, area);
+    Paragraph::new([Text::raw(message)].iter())
+        .style(app.user_config.theme.text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.user_config.theme.active),
+        )
+        .render(f, area);
+}
+
+// Blanks out an area so what's drawn next doesn't mix with what was there
+struct Clear;
+
+impl Widget for Clear {
+    fn draw(&mut self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf.get_mut(x, y).reset();
+            }
         }
     }
 }
@@ -802,7 +1316,7 @@ where
 
     let mut playing_text = vec![
         Text::raw("Api response: "),
-        Text::styled(&app.api_error, Style::default().fg(app.user_config.theme.error_text)),
+        Text::styled(&app.api_error, app.user_config.theme.error_text),
         Text::styled(
             "
 
@@ -811,34 +1325,34 @@ If you are trying to play a track, please check that
     2. Your playback device is active and selected - press `d` to go to device selection menu
     3. If you're using spotifyd as a playback device, your device name must not contain spaces
             ",
-            Style::default().fg(app.user_config.theme.text),
+            app.user_config.theme.text,
         ),
         Text::styled("
 Hint: a playback device must be either an official spotify client or a light weight alternative such as spotifyd
         ",
-        Style::default().fg(app.user_config.theme.hint)),
+        app.user_config.theme.hint),
         Text::styled(
             "\nPress <Esc> to return",
-            Style::default().fg(app.user_config.theme.inactive),
+            app.user_config.theme.inactive,
         ),
     ];
 
     if app.client_config.device_id.is_none() {
         playing_text.push(Text::styled(
             "\nNo playback device is selected - follow point 2 above",
-            Style::default().fg(app.user_config.theme.hint),
+            app.user_config.theme.hint,
         ))
     }
 
     Paragraph::new(playing_text.iter())
         .wrap(true)
-        .style(Style::default().fg(app.user_config.theme.text))
+        .style(app.user_config.theme.text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title("Error")
-                .title_style(Style::default().fg(app.user_config.theme.error_border))
-                .border_style(Style::default().fg(app.user_config.theme.error_border)),
+                .title_style(app.user_config.theme.error_border)
+                .border_style(app.user_config.theme.error_border),
         )
         .render(f, chunks[0]);
 }
@@ -876,10 +1390,7 @@ where
         changelog.replace("\n## [Unreleased]\n", "")
     };
 
-    let top_text = vec![Text::styled(
-        BANNER,
-        Style::default().fg(app.user_config.theme.banner),
-    )];
+    let top_text = vec![Text::styled(BANNER, app.user_config.theme.banner)];
 
     let bottom_text = vec![
         Text::raw("\nPlease report any bugs or missing features to https://github.com/Rigellute/spotify-tui\n\n"),
@@ -888,46 +1399,189 @@ where
 
     // Contains the banner
     Paragraph::new(top_text.iter())
-        .style(Style::default().fg(app.user_config.theme.text))
+        .style(app.user_config.theme.text)
         .block(Block::default())
         .render(f, chunks[0]);
 
     // CHANGELOG
     Paragraph::new(bottom_text.iter())
-        .style(Style::default().fg(app.user_config.theme.text))
+        .style(app.user_config.theme.text)
         .block(Block::default())
         .wrap(true)
         .scroll(app.home_scroll)
         .render(f, chunks[1]);
 }
 
-fn draw_not_implemented_yet<B>(
-    f: &mut Frame<B>,
-    app: &App,
-    layout_chunk: Rect,
-    block: ActiveBlock,
-    title: &str,
-) where
+pub fn draw_podcasts<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
     B: Backend,
 {
+    let header = TableHeader {
+        id: TableId::Podcasts,
+        items: vec![
+            TableHeaderItem {
+                text: "Name",
+                width: get_percentage_width(layout_chunk.width, 2.0 / 5.0),
+                ..Default::default()
+            },
+            TableHeaderItem {
+                text: "Publisher",
+                width: get_percentage_width(layout_chunk.width, 2.0 / 5.0),
+                ..Default::default()
+            },
+            TableHeaderItem {
+                text: "Episodes",
+                width: get_percentage_width(layout_chunk.width, 1.0 / 5.0),
+                ..Default::default()
+            },
+        ],
+    };
+
     let current_route = app.get_current_route();
     let highlight_state = (
-        current_route.active_block == block,
-        current_route.hovered_block == block,
+        current_route.active_block == ActiveBlock::Podcasts,
+        current_route.hovered_block == ActiveBlock::Podcasts,
     );
-    let display_block = Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .title_style(get_color(highlight_state, app.user_config.theme))
-        .border_style(get_color(highlight_state, app.user_config.theme));
 
-    let text = vec![Text::raw("Not implemented yet!")];
+    let items = match app.library.saved_shows.get_results(None) {
+        Some(saved_shows) => saved_shows
+            .items
+            .iter()
+            .map(|saved_show| TableItem {
+                id: saved_show.show.id.to_owned(),
+                format: vec![
+                    saved_show.show.name.to_owned(),
+                    saved_show.show.publisher.to_owned(),
+                    saved_show.show.total_episodes.to_string(),
+                ],
+            })
+            .collect::<Vec<TableItem>>(),
+        None => vec![],
+    };
+
+    draw_table(
+        f,
+        app,
+        layout_chunk,
+        ("Podcasts", &header),
+        &items,
+        app.shows_list_index,
+        highlight_state,
+    );
+}
+
+pub fn draw_show_episodes<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let selected_show = match &app.selected_show {
+        Some(selected_show) => selected_show,
+        None => return,
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(6), Constraint::Length(7)].as_ref())
+        .split(layout_chunk);
+
+    let header = TableHeader {
+        id: TableId::Episodes,
+        items: vec![
+            TableHeaderItem {
+                text: "Date",
+                width: get_percentage_width(layout_chunk.width, 0.15),
+                ..Default::default()
+            },
+            TableHeaderItem {
+                text: "Name",
+                width: get_percentage_width(layout_chunk.width, 0.5),
+                ..Default::default()
+            },
+            TableHeaderItem {
+                text: "Length",
+                width: get_percentage_width(layout_chunk.width, 0.1),
+                ..Default::default()
+            },
+            TableHeaderItem {
+                text: "Progress",
+                width: get_percentage_width(layout_chunk.width, 0.2),
+                ..Default::default()
+            },
+        ],
+    };
+
+    let current_route = app.get_current_route();
+    let highlight_state = (
+        current_route.active_block == ActiveBlock::EpisodeTable,
+        current_route.hovered_block == ActiveBlock::EpisodeTable,
+    );
+
+    let episodes = match selected_show.episodes.get_results(None) {
+        Some(episodes) => &episodes.items[..],
+        None => &[],
+    };
+    let items = episodes
+        .iter()
+        .map(|episode| {
+            let progress = if episode.is_fully_played() {
+                "Played".to_string()
+            } else {
+                match episode.get_resume_position_ms() {
+                    Some(position_ms) => {
+                        format!("At {}", millis_to_minutes(u128::from(position_ms)))
+                    }
+                    None => String::new(),
+                }
+            };
+            TableItem {
+                id: episode.id.to_owned(),
+                format: vec![
+                    episode.release_date.to_owned(),
+                    episode.name.to_owned(),
+                    millis_to_minutes(u128::from(episode.duration_ms)),
+                    progress,
+                ],
+            }
+        })
+        .collect::<Vec<TableItem>>();
 
+    let title = format!(
+        "{} - {}{}",
+        selected_show.show.name,
+        selected_show.show.publisher,
+        if selected_show.is_saved {
+            ""
+        } else {
+            " (not saved)"
+        }
+    );
+
+    draw_table(
+        f,
+        app,
+        chunks[0],
+        (&title, &header),
+        &items,
+        selected_show.selected_index,
+        highlight_state,
+    );
+
+    let description = episodes
+        .get(selected_show.selected_index)
+        .map(|episode| episode.description.as_str())
+        .unwrap_or("");
+    let text = [Text::raw(description)];
     Paragraph::new(text.iter())
-        .style(Style::default().fg(app.user_config.theme.text))
-        .block(display_block)
+        .style(app.user_config.theme.text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Description")
+                .title_style(get_color(highlight_state, app.user_config.theme))
+                .border_style(get_color(highlight_state, app.user_config.theme)),
+        )
         .wrap(true)
-        .render(f, layout_chunk);
+        .render(f, chunks[1]);
 }
 
 fn draw_artist_albums<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
@@ -1010,23 +1664,19 @@ where
 
     let device_instructions = vec![
         "To play tracks, please select a device.",
-        "Use `j/k` or up/down arrow keys to move up and down and <Enter> to select",
+        "Use `j/k` or up/down arrow keys to move up and down and <Enter> to select. Playback moves to the device you select.",
         "Your choice here will be cached so you can jump straight back in when you next open `spotify-tui`.",
-        "You can change the playback device at any time by pressing `d`.",
+        "You can change the playback device at any time by pressing `d`, and press `r` here to refresh the list.",
     ];
 
     Paragraph::new([Text::raw(device_instructions.join("\n"))].iter())
-        .style(Style::default().fg(app.user_config.theme.text))
+        .style(app.user_config.theme.text)
         .wrap(true)
         .block(
             Block::default()
                 .borders(Borders::NONE)
                 .title("Welcome to spotify-tui!")
-                .title_style(
-                    Style::default()
-                        .fg(app.user_config.theme.active)
-                        .modifier(Modifier::BOLD),
-                ),
+                .title_style(with_modifier(app.user_config.theme.active, Modifier::BOLD)),
         )
         .render(f, chunks[0]);
 
@@ -1037,11 +1687,7 @@ where
             if items.devices.is_empty() {
                 no_device_message
             } else {
-                items
-                    .devices
-                    .iter()
-                    .map(|device| device.name.to_owned())
-                    .collect()
+                items.devices.iter().map(display_device).collect()
             }
         }
         None => no_device_message,
@@ -1052,17 +1698,13 @@ where
             Block::default()
                 .title("Devices")
                 .borders(Borders::ALL)
-                .title_style(Style::default().fg(app.user_config.theme.active))
-                .border_style(Style::default().fg(app.user_config.theme.inactive)),
+                .title_style(app.user_config.theme.active)
+                .border_style(app.user_config.theme.inactive),
         )
         .items(&items)
-        .style(Style::default().fg(app.user_config.theme.text))
+        .style(app.user_config.theme.text)
         .select(app.selected_device_index)
-        .highlight_style(
-            Style::default()
-                .fg(app.user_config.theme.active)
-                .modifier(Modifier::BOLD),
-        )
+        .highlight_style(with_modifier(app.user_config.theme.active, Modifier::BOLD))
         .render(f, chunks[1]);
 }
 
@@ -1171,32 +1813,8 @@ pub fn draw_recently_played_table<B>(f: &mut Frame<B>, app: &App, layout_chunk:
 where
     B: Backend,
 {
-    let header = TableHeader {
-        id: TableId::RecentlyPlayed,
-        items: vec![
-            TableHeaderItem {
-                id: ColumnId::Liked,
-                text: "",
-                width: 2,
-            },
-            TableHeaderItem {
-                id: ColumnId::SongTitle,
-                text: "Title",
-                // We need to subtract the fixed value of the previous column
-                width: get_percentage_width(layout_chunk.width, 2.0 / 5.0) - 2,
-            },
-            TableHeaderItem {
-                text: "Artist",
-                width: get_percentage_width(layout_chunk.width, 2.0 / 5.0),
-                ..Default::default()
-            },
-            TableHeaderItem {
-                text: "Length",
-                width: get_percentage_width(layout_chunk.width, 1.0 / 5.0),
-                ..Default::default()
-            },
-        ],
-    };
+    let columns = &app.user_config.track_tables.recently_played;
+    let header = get_track_table_header(TableId::RecentlyPlayed, columns, layout_chunk.width);
 
     if let Some(recently_played) = &app.recently_played.result {
         let current_route = app.get_current_route();
@@ -1211,14 +1829,12 @@ where
         let items = recently_played
             .items
             .iter()
-            .map(|item| TableItem {
-                id: item.track.id.clone().unwrap_or_else(|| "".to_string()),
-                format: vec![
-                    "".to_string(),
-                    item.track.name.to_owned(),
-                    create_artist_string(&item.track.artists),
-                    millis_to_minutes(u128::from(item.track.duration_ms)),
-                ],
+            .map(|item| {
+                get_track_table_item(
+                    app,
+                    &TrackRow::from_simplified_track(&item.track, None),
+                    columns,
+                )
             })
             .collect::<Vec<TableItem>>();
 
@@ -1234,6 +1850,153 @@ where
     };
 }
 
+// Everything a track table column can show, whichever kind of track the table holds
+struct TrackRow<'a> {
+    id: Option<&'a String>,
+    name: &'a str,
+    artists: &'a [SimplifiedArtist],
+    album: Option<&'a str>,
+    duration_ms: u32,
+    popularity: Option<u32>,
+    track_number: u32,
+    explicit: bool,
+    added: Option<&'a TrackAdded>,
+}
+
+impl<'a> TrackRow<'a> {
+    fn from_full_track(track: &'a FullTrack, added: Option<&'a TrackAdded>) -> TrackRow<'a> {
+        TrackRow {
+            id: track.id.as_ref(),
+            name: &track.name,
+            artists: &track.artists,
+            album: Some(&track.album.name),
+            duration_ms: track.duration_ms,
+            popularity: Some(track.popularity),
+            track_number: track.track_number,
+            explicit: track.explicit,
+            added,
+        }
+    }
+
+    fn from_simplified_track(track: &'a SimplifiedTrack, album: Option<&'a str>) -> TrackRow<'a> {
+        TrackRow {
+            id: track.id.as_ref(),
+            name: &track.name,
+            artists: &track.artists,
+            album,
+            duration_ms: track.duration_ms,
+            popularity: None,
+            track_number: track.track_number,
+            explicit: track.explicit,
+            added: None,
+        }
+    }
+}
+
+fn get_track_column_header(column: TrackColumn) -> &'static str {
+    match column {
+        TrackColumn::Title => "Title",
+        TrackColumn::Artists => "Artist",
+        TrackColumn::Album => "Album",
+        TrackColumn::Duration => "Length",
+        TrackColumn::Liked => "",
+        TrackColumn::Popularity => "Popularity",
+        TrackColumn::TrackNumber => "#",
+        TrackColumn::AddedAt => "Added",
+        TrackColumn::AddedBy => "Added by",
+        TrackColumn::Bpm => "BPM",
+        TrackColumn::Key => "Key",
+        TrackColumn::Explicit => "E",
+    }
+}
+
+fn get_track_table_header<'a>(
+    id: TableId,
+    columns: &[TrackColumnConfig],
+    width: u16,
+) -> TableHeader<'a> {
+    let fixed_width: u16 = columns
+        .iter()
+        .map(|config| match config.width {
+            ColumnWidth::Length(length) => length,
+            ColumnWidth::Percent(_) => 0,
+        })
+        .sum();
+    // Leave room for the borders and the spacing between columns
+    let remaining_width = width.saturating_sub(fixed_width).saturating_sub(3);
+
+    TableHeader {
+        id,
+        items: columns
+            .iter()
+            .map(|config| TableHeaderItem {
+                id: match config.column {
+                    TrackColumn::Title => ColumnId::SongTitle,
+                    TrackColumn::Liked => ColumnId::Liked,
+                    _ => ColumnId::None,
+                },
+                text: get_track_column_header(config.column),
+                width: match config.width {
+                    ColumnWidth::Length(length) => length,
+                    ColumnWidth::Percent(percent) => {
+                        (u32::from(remaining_width) * u32::from(percent) / 100) as u16
+                    }
+                },
+            })
+            .collect(),
+    }
+}
+
+fn get_track_table_item(app: &App, row: &TrackRow, columns: &[TrackColumnConfig]) -> TableItem {
+    let audio_features = row
+        .id
+        .and_then(|id| app.track_audio_features.get(id))
+        .and_then(|audio_features| audio_features.as_ref());
+
+    let format = columns
+        .iter()
+        .map(|config| match config.column {
+            TrackColumn::Title => row.name.to_owned(),
+            TrackColumn::Artists => create_artist_string(row.artists),
+            TrackColumn::Album => row.album.unwrap_or("").to_owned(),
+            TrackColumn::Duration => millis_to_minutes(u128::from(row.duration_ms)),
+            // Filled in by `draw_table`
+            TrackColumn::Liked => "".to_string(),
+            TrackColumn::Popularity => row
+                .popularity
+                .map(|popularity| popularity.to_string())
+                .unwrap_or_default(),
+            TrackColumn::TrackNumber => row.track_number.to_string(),
+            TrackColumn::AddedAt => row
+                .added
+                .map(|added| added.at.to_owned())
+                .unwrap_or_default(),
+            TrackColumn::AddedBy => row
+                .added
+                .and_then(|added| added.by.to_owned())
+                .unwrap_or_default(),
+            TrackColumn::Bpm => audio_features
+                .map(|audio_features| format!("{:.0}", audio_features.tempo))
+                .unwrap_or_default(),
+            TrackColumn::Key => audio_features
+                .map(|audio_features| get_key_name(audio_features.key, audio_features.mode))
+                .unwrap_or_default(),
+            TrackColumn::Explicit => {
+                if row.explicit {
+                    "E".to_string()
+                } else {
+                    "".to_string()
+                }
+            }
+        })
+        .collect();
+
+    TableItem {
+        id: row.id.cloned().unwrap_or_else(|| "".to_string()),
+        format,
+    }
+}
+
 fn draw_selectable_list<B, S>(
     f: &mut Frame<B>,
     app: &App,
@@ -1255,9 +2018,12 @@ fn draw_selectable_list<B, S>(
                 .border_style(get_color(highlight_state, app.user_config.theme)),
         )
         .items(items)
-        .style(Style::default().fg(app.user_config.theme.text))
+        .style(app.user_config.theme.text)
         .select(selected_index)
-        .highlight_style(get_color(highlight_state, app.user_config.theme).modifier(Modifier::BOLD))
+        .highlight_style(with_modifier(
+            get_color(highlight_state, app.user_config.theme),
+            Modifier::BOLD,
+        ))
         .render(f, layout_chunk);
 }
 
@@ -1272,7 +2038,10 @@ fn draw_table<B>(
 ) where
     B: Backend,
 {
-    let selected_style = get_color(highlight_state, app.user_config.theme).modifier(Modifier::BOLD);
+    let selected_style = with_modifier(
+        get_color(highlight_state, app.user_config.theme),
+        Modifier::BOLD,
+    );
 
     let track_playing_index = match &app.current_playback_context {
         Some(ctx) => items.iter().position(|t| match &ctx.item {
@@ -1295,7 +2064,7 @@ fn draw_table<B>(
 
     let rows = items.iter().skip(offset).enumerate().map(|(i, item)| {
         let mut formatted_row = item.format.clone();
-        let mut style = Style::default().fg(app.user_config.theme.text); // default styling
+        let mut style = app.user_config.theme.text; // default styling
 
         // if table displays songs
         match header.id {
@@ -1307,9 +2076,7 @@ fn draw_table<B>(
                     {
                         if i == track_playing_offset_index {
                             formatted_row[title_idx] = format!("|> {}", &formatted_row[title_idx]);
-                            style = Style::default()
-                                .fg(app.user_config.theme.active)
-                                .modifier(Modifier::BOLD);
+                            style = with_modifier(app.user_config.theme.active, Modifier::BOLD);
                         }
                     }
                 }
@@ -1343,12 +2110,12 @@ fn draw_table<B>(
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .style(Style::default().fg(app.user_config.theme.text))
+                .style(app.user_config.theme.text)
                 .title(title)
                 .title_style(get_color(highlight_state, app.user_config.theme))
                 .border_style(get_color(highlight_state, app.user_config.theme)),
         )
-        .style(Style::default().fg(app.user_config.theme.text))
+        .style(app.user_config.theme.text)
         .widths(&widths)
         .render(f, layout_chunk);
 }
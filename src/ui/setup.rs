@@ -0,0 +1,198 @@
+use super::util::{display_device, with_modifier};
+use crate::{
+    setup::{SetupStep, SetupWizard, Verification},
+    user_config::Theme,
+};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::Modifier,
+    widgets::{Block, Borders, Paragraph, SelectableList, Text, Widget},
+    Frame,
+};
+
+const DASHBOARD_URL: &str = "https://developer.spotify.com/dashboard/applications";
+// The most devices shown at once in the device step
+const MAX_DEVICE_ROWS: usize = 8;
+
+pub fn draw<B>(f: &mut Frame<B>, wizard: &SetupWizard, theme: &Theme)
+where
+    B: Backend,
+{
+    let input_height = match (wizard.step, &wizard.devices) {
+        (SetupStep::Device, Some(devices)) => devices.len().clamp(1, MAX_DEVICE_ROWS) as u16 + 2,
+        _ => 3,
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(10),
+                Constraint::Length(5),
+                Constraint::Length(input_height),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .margin(2)
+        .split(f.size());
+
+    let heading = with_modifier(theme.active, Modifier::BOLD);
+    let redirect_uri = format!("http://localhost:{}/callback", wizard.get_port());
+    let instructions = [
+        Text::styled("Welcome to spotify-tui!\n\n", heading),
+        Text::styled("1. Go to ", theme.text),
+        Text::styled(DASHBOARD_URL, theme.hint),
+        Text::styled(
+            "\n2. Click `Create a Client ID` and create an app\n",
+            theme.text,
+        ),
+        Text::styled("3. Click `Edit Settings` and add ", theme.text),
+        Text::styled(&redirect_uri, theme.hint),
+        Text::styled(" to the Redirect URIs\n", theme.text),
+        Text::styled(
+            "4. Copy your Client ID and, unless you want to log in with PKCE, your Client Secret\n",
+            theme.text,
+        ),
+    ];
+    Paragraph::new(instructions.iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Setup")
+                .title_style(theme.inactive)
+                .border_style(theme.inactive),
+        )
+        .wrap(true)
+        .render(f, chunks[0]);
+
+    let secret = if wizard.client_secret.is_empty() && wizard.step > SetupStep::ClientSecret {
+        "none, log in with PKCE".to_string()
+    } else {
+        "*".repeat(wizard.client_secret.chars().count())
+    };
+    let port = if wizard.port.is_empty() {
+        wizard.get_port().to_string()
+    } else {
+        wizard.port.clone()
+    };
+    let summary = [
+        Text::styled("Client ID:     ", theme.inactive),
+        Text::styled(format!("{}\n", wizard.client_id), theme.text),
+        Text::styled("Client Secret: ", theme.inactive),
+        Text::styled(format!("{}\n", secret), theme.text),
+        Text::styled("Port:          ", theme.inactive),
+        Text::styled(port, theme.text),
+    ];
+    Paragraph::new(summary.iter())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("client.yml")
+                .title_style(theme.inactive)
+                .border_style(theme.inactive),
+        )
+        .render(f, chunks[1]);
+
+    let (title, input) = match wizard.step {
+        SetupStep::ClientId => ("Enter your Client ID", wizard.client_id.clone()),
+        SetupStep::ClientSecret => (
+            "Enter your Client Secret (leave empty to log in with PKCE)",
+            "*".repeat(wizard.client_secret.chars().count()),
+        ),
+        SetupStep::Port => (
+            "Enter the port of the redirect URI (leave empty for the default)",
+            wizard.port.clone(),
+        ),
+        SetupStep::Verify => ("Checking your credentials", String::new()),
+        SetupStep::Device => ("Pick the device to play on", String::new()),
+    };
+    let (input_text, cursor) = if wizard.step == SetupStep::Verify {
+        (verification_status(&wizard.verification).to_string(), "")
+    } else {
+        (input, " ")
+    };
+    let input_style = match wizard.verification {
+        Verification::Failed(_) if wizard.step == SetupStep::Verify => theme.error_border,
+        _ => theme.active,
+    };
+    let input_block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_style(input_style)
+        .border_style(input_style);
+    match (wizard.step, &wizard.devices) {
+        (SetupStep::Device, Some(devices)) if !devices.is_empty() => {
+            let items: Vec<String> = devices.iter().map(display_device).collect();
+            SelectableList::default()
+                .block(input_block)
+                .items(&items)
+                .style(theme.text)
+                .select(Some(wizard.selected_device_index))
+                .highlight_style(with_modifier(theme.active, Modifier::BOLD))
+                .render(f, chunks[2]);
+        }
+        (SetupStep::Device, devices) => {
+            let status = match devices {
+                Some(_) => "No devices found",
+                None => "Asking Spotify...",
+            };
+            Paragraph::new([Text::styled(status, theme.text)].iter())
+                .block(input_block)
+                .render(f, chunks[2]);
+        }
+        _ => {
+            let input = [
+                Text::styled(input_text, theme.text),
+                Text::styled(cursor, with_modifier(theme.text, Modifier::REVERSED)),
+            ];
+            Paragraph::new(input.iter())
+                .block(input_block)
+                .render(f, chunks[2]);
+        }
+    }
+
+    let hint = match (wizard.step, &wizard.verification) {
+        (SetupStep::Verify, Verification::Pending) => String::new(),
+        (SetupStep::Verify, Verification::Failed(_)) => {
+            "Press <Enter> to correct your credentials, <Esc> to go back or <Ctrl-c> to quit"
+                .to_string()
+        }
+        (SetupStep::Device, _) => "Press <Enter> to save the selected device as the one to play on, <r> to refresh the list after opening Spotify on a device, or <Esc> to skip"
+            .to_string(),
+        (SetupStep::Verify, _) => format!(
+            "Press <Enter> to save your config to {}. You will then log in and pick a default device.",
+            wizard.config_file_path.display()
+        ),
+        _ => "Press <Enter> to continue, <Esc> to go back, <Ctrl-u> to clear or <Ctrl-c> to quit"
+            .to_string(),
+    };
+    let mut messages = Vec::new();
+    if let Some(error) = &wizard.error {
+        messages.push(Text::styled(format!("{}\n\n", error), theme.error_text));
+    }
+    if wizard.step == SetupStep::Verify {
+        match &wizard.verification {
+            Verification::Pending => {}
+            Verification::Failed(message) => {
+                messages.push(Text::styled(format!("{}\n\n", message), theme.error_text))
+            }
+            Verification::Passed(message) | Verification::Warning(message) => {
+                messages.push(Text::styled(format!("{}\n\n", message), theme.text))
+            }
+        }
+    }
+    messages.push(Text::styled(hint, theme.hint));
+    Paragraph::new(messages.iter())
+        .wrap(true)
+        .render(f, chunks[3]);
+}
+
+fn verification_status(verification: &Verification) -> &'static str {
+    match verification {
+        Verification::Pending => "Asking Spotify...",
+        Verification::Passed(_) => "Done",
+        Verification::Warning(_) => "Could not check your credentials",
+        Verification::Failed(_) => "Rejected",
+    }
+}
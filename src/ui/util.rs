@@ -1,10 +1,14 @@
 use super::super::app::{ActiveBlock, App, ArtistBlock, SearchResultBlock};
 use crate::user_config::Theme;
-use rspotify::spotify::model::artist::SimplifiedArtist;
-use tui::style::Style;
+use rspotify::spotify::model::{artist::SimplifiedArtist, device::Device};
+use tui::style::{Modifier, Style};
 
 pub const SMALL_TERMINAL_HEIGHT: u16 = 45;
 
+pub const PITCHES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
 pub fn get_search_results_highlight_state(
     app: &App,
     block_to_match: SearchResultBlock,
@@ -31,12 +35,17 @@ pub fn get_artist_highlight_state(app: &App, block_to_match: ArtistBlock) -> (bo
 
 pub fn get_color((is_active, is_hovered): (bool, bool), theme: Theme) -> Style {
     match (is_active, is_hovered) {
-        (true, _) => Style::default().fg(theme.selected),
-        (false, true) => Style::default().fg(theme.hovered),
-        _ => Style::default().fg(theme.inactive),
+        (true, _) => theme.selected,
+        (false, true) => theme.hovered,
+        _ => theme.inactive,
     }
 }
 
+// Adds to the modifiers of a themed style rather than replacing the ones the user configured
+pub fn with_modifier(style: Style, modifier: Modifier) -> Style {
+    style.modifier(style.modifier | modifier)
+}
+
 pub fn create_artist_string(artists: &[SimplifiedArtist]) -> String {
     artists
         .iter()
@@ -84,6 +93,31 @@ pub fn get_track_progress_percentage(song_progress_ms: u128, track_duration_ms:
     min_perc.max(track_perc) as u16
 }
 
+pub fn display_device(device: &Device) -> String {
+    let mut flags = String::new();
+    if device.is_active {
+        flags.push_str(" | Active");
+    }
+    if device.is_restricted {
+        flags.push_str(" | Restricted");
+    }
+
+    format!(
+        "{} ({:?} | Volume: {}%{})",
+        device.name, device._type, device.volume_percent, flags
+    )
+}
+
+// The column of the progress gauge `position_ms` falls in
+pub fn get_track_progress_column(position_ms: u32, track_duration_ms: u32, width: u16) -> u16 {
+    if track_duration_ms == 0 || width == 0 {
+        return 0;
+    }
+    let position = u64::from(position_ms.min(track_duration_ms));
+    let column = position * u64::from(width) / u64::from(track_duration_ms);
+    (column as u16).min(width - 1)
+}
+
 // Make better use of space on small terminals
 pub fn get_main_layout_margin(app: &App) -> u16 {
     if app.size.height > SMALL_TERMINAL_HEIGHT {
@@ -93,9 +127,28 @@ pub fn get_main_layout_margin(app: &App) -> u16 {
     }
 }
 
+// Spotify gives the key as a pitch class (-1 when unknown) and the mode as 1 for major, 0 for minor
+pub fn get_key_name(key: i32, mode: f32) -> String {
+    match PITCHES.get(key as usize) {
+        Some(pitch) if key >= 0 && mode < 0.5 => format!("{}m", pitch),
+        Some(pitch) if key >= 0 => pitch.to_string(),
+        _ => "".to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rspotify::spotify::senum::DeviceType;
+
+    #[test]
+    fn test_get_key_name() {
+        assert_eq!(get_key_name(0, 1.0), "C");
+        assert_eq!(get_key_name(1, 0.0), "C#m");
+        assert_eq!(get_key_name(11, 1.0), "B");
+        assert_eq!(get_key_name(-1, 1.0), "");
+        assert_eq!(get_key_name(12, 1.0), "");
+    }
 
     #[test]
     fn millis_to_minutes_test() {
@@ -107,6 +160,35 @@ mod tests {
         assert_eq!(millis_to_minutes(60 * 1500), "1:30");
     }
 
+    #[test]
+    fn display_device_test() {
+        let mut device = Device {
+            id: "1".to_string(),
+            is_active: false,
+            is_restricted: false,
+            name: "Kitchen".to_string(),
+            _type: DeviceType::Speaker,
+            volume_percent: 40,
+        };
+        assert_eq!(display_device(&device), "Kitchen (Speaker | Volume: 40%)");
+
+        device.is_active = true;
+        device.is_restricted = true;
+        assert_eq!(
+            display_device(&device),
+            "Kitchen (Speaker | Volume: 40% | Active | Restricted)"
+        );
+    }
+
+    #[test]
+    fn get_track_progress_column_test() {
+        assert_eq!(get_track_progress_column(0, 100_000, 50), 0);
+        assert_eq!(get_track_progress_column(50_000, 100_000, 50), 25);
+        assert_eq!(get_track_progress_column(100_000, 100_000, 50), 49);
+        assert_eq!(get_track_progress_column(200_000, 100_000, 50), 49);
+        assert_eq!(get_track_progress_column(1_000, 0, 50), 0);
+    }
+
     #[test]
     fn display_track_progress_test() {
         assert_eq!(
@@ -1,4 +1,5 @@
 use crate::event::Key;
+use crossterm::event::{KeyCode, KeyModifiers};
 use dirs;
 use failure::err_msg;
 use serde::{Deserialize, Serialize};
@@ -6,108 +7,166 @@ use std::{
     fs,
     path::{Path, PathBuf},
 };
-use tui::style::Color;
+use tui::style::{Color, Modifier, Style};
 
 const FILE_NAME: &str = "config.yml";
 const CONFIG_DIR: &str = ".config";
 const APP_CONFIG_DIR: &str = "spotify-tui";
 
+const THEMES_DIR: &str = "themes";
+
+// Presets that ship with the app, a theme file with the same name in the themes directory
+// takes precedence
+const BUNDLED_THEMES: [(&str, &str); 4] = [
+    ("default", include_str!("../themes/default.yml")),
+    ("high-contrast", include_str!("../themes/high-contrast.yml")),
+    ("colorblind", include_str!("../themes/colorblind.yml")),
+    (
+        "solarized-dark",
+        include_str!("../themes/solarized-dark.yml"),
+    ),
+];
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ColorString {
+    Index(u8),
+    Name(String),
+}
+
+/// A theme item is either just a (foreground) colour or a full style
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ThemeItemString {
+    Color(ColorString),
+    Style(ThemeStyleString),
+}
+
+// Unknown fields are an error, as a misspelled one would otherwise leave an empty style
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeStyleString {
+    pub fg: Option<ColorString>,
+    pub bg: Option<ColorString>,
+    pub modifiers: Option<Vec<String>>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct UserTheme {
-    pub active: Option<String>,
-    pub banner: Option<String>,
-    pub error_border: Option<String>,
-    pub error_text: Option<String>,
-    pub hint: Option<String>,
-    pub hovered: Option<String>,
-    pub inactive: Option<String>,
-    pub playbar_background: Option<String>,
-    pub playbar_progress: Option<String>,
-    pub playbar_text: Option<String>,
-    pub selected: Option<String>,
-    pub text: Option<String>,
+    pub name: Option<String>,
+    pub analysis_bar: Option<ThemeItemString>,
+    pub analysis_bar_text: Option<ThemeItemString>,
+    pub active: Option<ThemeItemString>,
+    pub banner: Option<ThemeItemString>,
+    pub error_border: Option<ThemeItemString>,
+    pub error_text: Option<ThemeItemString>,
+    pub hint: Option<ThemeItemString>,
+    pub hovered: Option<ThemeItemString>,
+    pub inactive: Option<ThemeItemString>,
+    pub playbar_background: Option<ThemeItemString>,
+    pub playbar_progress: Option<ThemeItemString>,
+    pub playbar_text: Option<ThemeItemString>,
+    pub selected: Option<ThemeItemString>,
+    pub text: Option<ThemeItemString>,
 }
 
 #[derive(Copy, Clone, Debug)]
 pub struct Theme {
-    pub analysis_bar: Color,
-    pub analysis_bar_text: Color,
-    pub active: Color,
-    pub banner: Color,
-    pub error_border: Color,
-    pub error_text: Color,
-    pub hint: Color,
-    pub hovered: Color,
-    pub inactive: Color,
-    pub playbar_background: Color,
-    pub playbar_progress: Color,
-    pub playbar_text: Color,
-    pub selected: Color,
-    pub text: Color,
+    pub analysis_bar: Style,
+    pub analysis_bar_text: Style,
+    pub active: Style,
+    pub banner: Style,
+    pub error_border: Style,
+    pub error_text: Style,
+    pub hint: Style,
+    pub hovered: Style,
+    pub inactive: Style,
+    pub playbar_background: Style,
+    pub playbar_progress: Style,
+    pub playbar_text: Style,
+    pub selected: Style,
+    pub text: Style,
 }
 
 impl Default for Theme {
     fn default() -> Self {
         Theme {
-            analysis_bar: Color::LightCyan,
-            analysis_bar_text: Color::Black,
-            active: Color::Cyan,
-            banner: Color::LightCyan,
-            error_border: Color::Red,
-            error_text: Color::LightRed,
-            hint: Color::Yellow,
-            hovered: Color::Magenta,
-            inactive: Color::Gray,
-            playbar_background: Color::Black,
-            playbar_progress: Color::LightCyan,
-            playbar_text: Color::White,
-            selected: Color::LightCyan,
-            text: Color::White,
+            analysis_bar: Style::default().fg(Color::LightCyan),
+            analysis_bar_text: Style::default().fg(Color::Black),
+            active: Style::default().fg(Color::Cyan),
+            banner: Style::default().fg(Color::LightCyan),
+            error_border: Style::default().fg(Color::Red),
+            error_text: Style::default().fg(Color::LightRed),
+            hint: Style::default().fg(Color::Yellow),
+            hovered: Style::default().fg(Color::Magenta),
+            inactive: Style::default().fg(Color::Gray),
+            playbar_background: Style::default().fg(Color::Black),
+            playbar_progress: Style::default().fg(Color::LightCyan),
+            playbar_text: Style::default().fg(Color::White),
+            selected: Style::default().fg(Color::LightCyan),
+            text: Style::default().fg(Color::White),
         }
     }
 }
 
 fn parse_key(key: String) -> Result<Key, failure::Error> {
-    fn get_single_char(string: &str) -> char {
-        match string.chars().nth(0) {
-            Some(c) => c,
-            None => panic!(),
-        }
-    }
-
-    match key.len() {
-        1 => Ok(Key::Char(get_single_char(key.as_str()))),
-        _ => {
-            let sections: Vec<&str> = key.split('-').collect();
+    // `-` is a key in its own right, so `ctrl--` means control and minus
+    let (modifiers, key_name) = match key.rfind('-') {
+        Some(i) if key.ends_with("--") => (&key[..i - 1], "-"),
+        Some(i) if i > 0 && i < key.len() - 1 => (&key[..i], &key[i + 1..]),
+        _ => ("", key.as_str()),
+    };
 
-            if sections.len() > 2 {
+    let mut key_modifiers = KeyModifiers::empty();
+    for modifier in modifiers.split('-').filter(|modifier| !modifier.is_empty()) {
+        key_modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => {
                 return Err(failure::format_err!(
-                    "Shortcut can only have 2 keys, \"{}\" has {}",
-                    key,
-                    sections.len()
-                ));
+                    "The modifier \"{}\" in \"{}\" is unknown.",
+                    modifier,
+                    key
+                ))
             }
+        };
+    }
 
-            match sections[0].to_lowercase().as_str() {
-                "ctrl" => Ok(Key::Ctrl(get_single_char(sections[1]))),
-                "alt" => Ok(Key::Alt(get_single_char(sections[1]))),
-                "left" => Ok(Key::Left),
-                "right" => Ok(Key::Right),
-                "up" => Ok(Key::Up),
-                "down" => Ok(Key::Down),
-                "backspace" | "delete" => Ok(Key::Backspace),
-                "del" => Ok(Key::Delete),
-                "esc" | "escape" => Ok(Key::Esc),
-                "pageup" => Ok(Key::PageUp),
-                "pagedown" => Ok(Key::PageDown),
-                "space" => Ok(Key::Char(' ')),
-                _ => Err(failure::format_err!(
-                    "The key \"{}\" is unknown.",
-                    sections[0]
-                )),
-            }
+    let mut chars = key_name.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) if key_modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyCode::Char(c.to_uppercase().next().unwrap_or(c))
         }
-    }
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match key_name.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "backspace" | "delete" => KeyCode::Backspace,
+            "del" => KeyCode::Delete,
+            "ins" | "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "esc" | "escape" => KeyCode::Esc,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            name => match name
+                .strip_prefix('f')
+                .and_then(|number| number.parse::<u8>().ok())
+            {
+                Some(number) if number <= 12 => KeyCode::F(number),
+                _ => return Err(failure::format_err!("The key \"{}\" is unknown.", key_name)),
+            },
+        },
+    };
+
+    Ok(Key::with_modifiers(code, key_modifiers))
 }
 
 fn check_reserved_keys(key: Key) -> Result<(), failure::Error> {
@@ -128,9 +187,8 @@ fn check_reserved_keys(key: Key) -> Result<(), failure::Error> {
     ];
     for item in reserved.iter() {
         if key == *item {
-            // TODO: Add pretty print for key
             return Err(failure::format_err!(
-                "The key {:?} is reserved and cannot be remapped",
+                "The key \"{}\" is reserved and cannot be remapped",
                 key
             ));
         }
@@ -140,6 +198,7 @@ fn check_reserved_keys(key: Key) -> Result<(), failure::Error> {
 
 pub struct UserConfigPaths {
     pub config_file_path: PathBuf,
+    pub themes_dir_path: PathBuf,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -163,6 +222,22 @@ pub struct KeyBindingsString {
     copy_song_url: Option<String>,
     copy_album_url: Option<String>,
     audio_analysis: Option<String>,
+    toggle_sidebar: Option<String>,
+    add_to_queue: Option<String>,
+    play_next: Option<String>,
+    show_queue: Option<String>,
+    sleep_timer: Option<String>,
+    ab_loop: Option<String>,
+    seek_prompt: Option<String>,
+    next_section: Option<String>,
+    previous_section: Option<String>,
+    toggle_mute: Option<String>,
+    volume_prompt: Option<String>,
+    next_device: Option<String>,
+    recommendations_builder: Option<String>,
+    save_as_playlist: Option<String>,
+    toggle_autoplay: Option<String>,
+    add_to_playlist: Option<String>,
 }
 
 pub struct KeyBindings {
@@ -185,6 +260,22 @@ pub struct KeyBindings {
     pub copy_song_url: Key,
     pub copy_album_url: Key,
     pub audio_analysis: Key,
+    pub toggle_sidebar: Key,
+    pub add_to_queue: Key,
+    pub play_next: Key,
+    pub show_queue: Key,
+    pub sleep_timer: Key,
+    pub ab_loop: Key,
+    pub seek_prompt: Key,
+    pub next_section: Key,
+    pub previous_section: Key,
+    pub toggle_mute: Key,
+    pub volume_prompt: Key,
+    pub next_device: Key,
+    pub recommendations_builder: Key,
+    pub save_as_playlist: Key,
+    pub toggle_autoplay: Key,
+    pub add_to_playlist: Key,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -192,12 +283,127 @@ pub struct BehaviorConfigString {
     pub seek_milliseconds: Option<u32>,
     pub volume_increment: Option<u8>,
     pub tick_rate_milliseconds: Option<u64>,
+    pub preferred_devices: Option<Vec<String>>,
+    pub autoplay: Option<bool>,
 }
 
 pub struct BehaviorConfig {
     pub seek_milliseconds: u32,
     pub volume_increment: u8,
     pub tick_rate_milliseconds: u64,
+    /// Device names or types to play on, in order of preference
+    pub preferred_devices: Vec<String>,
+    /// Whether to play recommendations when an album or playlist ends
+    pub autoplay: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PlaybarPosition {
+    Top,
+    Bottom,
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayoutConfigString {
+    pub sidebar_width_percent: Option<u16>,
+    pub show_library: Option<bool>,
+    pub show_playlists: Option<bool>,
+    pub show_search: Option<bool>,
+    pub playbar_height: Option<u16>,
+    pub playbar_position: Option<String>,
+}
+
+pub struct LayoutConfig {
+    pub sidebar_width_percent: u16,
+    pub show_library: bool,
+    pub show_playlists: bool,
+    pub show_search: bool,
+    pub playbar_height: u16,
+    pub playbar_position: PlaybarPosition,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TrackColumn {
+    Title,
+    Artists,
+    Album,
+    Duration,
+    Liked,
+    Popularity,
+    TrackNumber,
+    AddedAt,
+    AddedBy,
+    Bpm,
+    Key,
+    Explicit,
+}
+
+impl TrackColumn {
+    fn default_width(self) -> ColumnWidth {
+        match self {
+            TrackColumn::Title => ColumnWidth::Percent(30),
+            TrackColumn::Artists => ColumnWidth::Percent(30),
+            TrackColumn::Album => ColumnWidth::Percent(30),
+            TrackColumn::Duration => ColumnWidth::Percent(10),
+            TrackColumn::Liked => ColumnWidth::Length(2),
+            TrackColumn::Popularity => ColumnWidth::Length(10),
+            TrackColumn::TrackNumber => ColumnWidth::Length(3),
+            TrackColumn::AddedAt => ColumnWidth::Length(10),
+            TrackColumn::AddedBy => ColumnWidth::Percent(15),
+            TrackColumn::Bpm => ColumnWidth::Length(5),
+            TrackColumn::Key => ColumnWidth::Length(5),
+            TrackColumn::Explicit => ColumnWidth::Length(3),
+        }
+    }
+}
+
+/// Percentages are of the width left over by the columns with a fixed length
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ColumnWidth {
+    Percent(u16),
+    Length(u16),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TrackColumnConfig {
+    pub column: TrackColumn,
+    pub width: ColumnWidth,
+}
+
+impl TrackColumnConfig {
+    fn new(column: TrackColumn, width: ColumnWidth) -> TrackColumnConfig {
+        TrackColumnConfig { column, width }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnWidthString {
+    Length(u16),
+    Percent(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TrackColumnString {
+    Name(String),
+    Column {
+        column: String,
+        width: Option<ColumnWidthString>,
+    },
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrackTablesConfigString {
+    pub song: Option<Vec<TrackColumnString>>,
+    pub album: Option<Vec<TrackColumnString>>,
+    pub recently_played: Option<Vec<TrackColumnString>>,
+}
+
+pub struct TrackTablesConfig {
+    pub song: Vec<TrackColumnConfig>,
+    pub album: Vec<TrackColumnConfig>,
+    pub recently_played: Vec<TrackColumnConfig>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -205,12 +411,16 @@ pub struct UserConfigString {
     keybindings: Option<KeyBindingsString>,
     behavior: Option<BehaviorConfigString>,
     theme: Option<UserTheme>,
+    layout: Option<LayoutConfigString>,
+    track_tables: Option<TrackTablesConfigString>,
 }
 
 pub struct UserConfig {
     pub keys: KeyBindings,
     pub theme: Theme,
     pub behavior: BehaviorConfig,
+    pub layout: LayoutConfig,
+    pub track_tables: TrackTablesConfig,
 }
 
 impl UserConfig {
@@ -237,11 +447,58 @@ impl UserConfig {
                 copy_song_url: Key::Char('c'),
                 copy_album_url: Key::Char('C'),
                 audio_analysis: Key::Char('v'),
+                toggle_sidebar: Key::Char('b'),
+                add_to_queue: Key::Char('z'),
+                play_next: Key::Char('Z'),
+                show_queue: Key::Char('Q'),
+                sleep_timer: Key::Char('S'),
+                ab_loop: Key::Char('B'),
+                seek_prompt: Key::Char('T'),
+                next_section: Key::Char(']'),
+                previous_section: Key::Char('['),
+                toggle_mute: Key::Char('m'),
+                volume_prompt: Key::Char('V'),
+                next_device: Key::Ctrl('o'),
+                recommendations_builder: Key::Char('R'),
+                save_as_playlist: Key::Char('P'),
+                toggle_autoplay: Key::Char('O'),
+                add_to_playlist: Key::Char('W'),
             },
             behavior: BehaviorConfig {
                 seek_milliseconds: 5 * 1000,
                 volume_increment: 10,
                 tick_rate_milliseconds: 250,
+                preferred_devices: vec![],
+                autoplay: false,
+            },
+            layout: LayoutConfig {
+                sidebar_width_percent: 20,
+                show_library: true,
+                show_playlists: true,
+                show_search: true,
+                playbar_height: 6,
+                playbar_position: PlaybarPosition::Bottom,
+            },
+            track_tables: TrackTablesConfig {
+                song: vec![
+                    TrackColumnConfig::new(TrackColumn::Liked, ColumnWidth::Length(2)),
+                    TrackColumnConfig::new(TrackColumn::Title, ColumnWidth::Percent(30)),
+                    TrackColumnConfig::new(TrackColumn::Artists, ColumnWidth::Percent(30)),
+                    TrackColumnConfig::new(TrackColumn::Album, ColumnWidth::Percent(30)),
+                    TrackColumnConfig::new(TrackColumn::Duration, ColumnWidth::Percent(10)),
+                ],
+                album: vec![
+                    TrackColumnConfig::new(TrackColumn::Liked, ColumnWidth::Length(2)),
+                    TrackColumnConfig::new(TrackColumn::TrackNumber, ColumnWidth::Length(3)),
+                    TrackColumnConfig::new(TrackColumn::Title, ColumnWidth::Percent(80)),
+                    TrackColumnConfig::new(TrackColumn::Duration, ColumnWidth::Percent(15)),
+                ],
+                recently_played: vec![
+                    TrackColumnConfig::new(TrackColumn::Liked, ColumnWidth::Length(2)),
+                    TrackColumnConfig::new(TrackColumn::Title, ColumnWidth::Percent(40)),
+                    TrackColumnConfig::new(TrackColumn::Artists, ColumnWidth::Percent(40)),
+                    TrackColumnConfig::new(TrackColumn::Duration, ColumnWidth::Percent(20)),
+                ],
             },
         }
     }
@@ -265,6 +522,7 @@ impl UserConfig {
 
                 let paths = UserConfigPaths {
                     config_file_path: config_file_path.to_path_buf(),
+                    themes_dir_path: app_config_dir.join(THEMES_DIR),
                 };
 
                 Ok(paths)
@@ -305,19 +563,52 @@ impl UserConfig {
         to_keys!(copy_song_url);
         to_keys!(copy_album_url);
         to_keys!(audio_analysis);
+        to_keys!(toggle_sidebar);
+        to_keys!(add_to_queue);
+        to_keys!(play_next);
+        to_keys!(show_queue);
+        to_keys!(sleep_timer);
+        to_keys!(ab_loop);
+        to_keys!(seek_prompt);
+        to_keys!(next_section);
+        to_keys!(previous_section);
+        to_keys!(toggle_mute);
+        to_keys!(volume_prompt);
+        to_keys!(next_device);
+        to_keys!(recommendations_builder);
+        to_keys!(save_as_playlist);
+        to_keys!(toggle_autoplay);
+        to_keys!(add_to_playlist);
 
         Ok(())
     }
 
-    pub fn load_theme(&mut self, theme: UserTheme) -> Result<(), failure::Error> {
+    pub fn load_theme(
+        &mut self,
+        theme: UserTheme,
+        themes_dir_path: &Path,
+    ) -> Result<(), failure::Error> {
+        if let Some(name) = &theme.name {
+            let preset = load_theme_preset(name, themes_dir_path)?;
+            if preset.name.is_some() {
+                return Err(failure::format_err!(
+                    "The theme \"{}\" cannot itself name another theme",
+                    name
+                ));
+            }
+            self.load_theme(preset, themes_dir_path)?;
+        }
+
         macro_rules! to_theme_item {
             ($name: ident) => {
                 if let Some(theme_item) = theme.$name {
-                    self.theme.$name = parse_theme_item(&theme_item)?;
+                    self.theme.$name = parse_theme_style(theme_item, self.theme.$name)?;
                 }
             };
         };
 
+        to_theme_item!(analysis_bar);
+        to_theme_item!(analysis_bar_text);
         to_theme_item!(active);
         to_theme_item!(banner);
         to_theme_item!(error_border);
@@ -359,6 +650,86 @@ impl UserConfig {
             }
         }
 
+        if let Some(preferred_devices) = behavior_config.preferred_devices {
+            self.behavior.preferred_devices = preferred_devices;
+        }
+
+        if let Some(autoplay) = behavior_config.autoplay {
+            self.behavior.autoplay = autoplay;
+        }
+
+        Ok(())
+    }
+
+    pub fn load_layoutconfig(
+        &mut self,
+        layout_config: LayoutConfigString,
+    ) -> Result<(), failure::Error> {
+        if let Some(sidebar_width_percent) = layout_config.sidebar_width_percent {
+            if sidebar_width_percent == 0 || sidebar_width_percent >= 100 {
+                return Err(failure::format_err!(
+                    "Sidebar width must be between 1 and 99 percent, is {}",
+                    sidebar_width_percent,
+                ));
+            }
+            self.layout.sidebar_width_percent = sidebar_width_percent;
+        }
+
+        if let Some(show_library) = layout_config.show_library {
+            self.layout.show_library = show_library;
+        }
+
+        if let Some(show_playlists) = layout_config.show_playlists {
+            self.layout.show_playlists = show_playlists;
+        }
+
+        if let Some(show_search) = layout_config.show_search {
+            self.layout.show_search = show_search;
+        }
+
+        if let Some(playbar_height) = layout_config.playbar_height {
+            // The borders and margin take four lines, leaving one for the title and the progress
+            if playbar_height < 4 {
+                return Err(failure::format_err!(
+                    "Playbar height must be at least 4, is {}",
+                    playbar_height,
+                ));
+            }
+            self.layout.playbar_height = playbar_height;
+        }
+
+        if let Some(playbar_position) = layout_config.playbar_position {
+            self.layout.playbar_position = match playbar_position.to_lowercase().as_str() {
+                "top" => PlaybarPosition::Top,
+                "bottom" => PlaybarPosition::Bottom,
+                _ => {
+                    return Err(failure::format_err!(
+                        "Playbar position must be \"top\" or \"bottom\", is \"{}\"",
+                        playbar_position,
+                    ))
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    pub fn load_track_tables(
+        &mut self,
+        track_tables: TrackTablesConfigString,
+    ) -> Result<(), failure::Error> {
+        if let Some(columns) = track_tables.song {
+            self.track_tables.song = parse_track_columns(columns)?;
+        }
+
+        if let Some(columns) = track_tables.album {
+            self.track_tables.album = parse_track_columns(columns)?;
+        }
+
+        if let Some(columns) = track_tables.recently_played {
+            self.track_tables.recently_played = parse_track_columns(columns)?;
+        }
+
         Ok(())
     }
 
@@ -381,7 +752,13 @@ impl UserConfig {
                 self.load_behaviorconfig(behavior)?;
             }
             if let Some(theme) = config_yml.theme {
-                self.load_theme(theme)?;
+                self.load_theme(theme, &paths.themes_dir_path)?;
+            }
+            if let Some(layout) = config_yml.layout {
+                self.load_layoutconfig(layout)?;
+            }
+            if let Some(track_tables) = config_yml.track_tables {
+                self.load_track_tables(track_tables)?;
             }
 
             Ok(())
@@ -391,6 +768,154 @@ impl UserConfig {
     }
 }
 
+fn parse_track_columns(
+    columns: Vec<TrackColumnString>,
+) -> Result<Vec<TrackColumnConfig>, failure::Error> {
+    if columns.is_empty() {
+        return Err(err_msg("A track table needs at least one column"));
+    }
+
+    columns
+        .into_iter()
+        .map(|column| {
+            let (name, width) = match column {
+                TrackColumnString::Name(name) => (name, None),
+                TrackColumnString::Column { column, width } => (column, width),
+            };
+
+            let column = parse_track_column(&name)?;
+            let width = match width {
+                Some(width) => parse_column_width(width)?,
+                None => column.default_width(),
+            };
+
+            Ok(TrackColumnConfig { column, width })
+        })
+        .collect()
+}
+
+fn parse_track_column(column: &str) -> Result<TrackColumn, failure::Error> {
+    let column = match column.to_lowercase().replace('-', "_").as_str() {
+        "title" => TrackColumn::Title,
+        "artists" | "artist" => TrackColumn::Artists,
+        "album" => TrackColumn::Album,
+        "duration" | "length" => TrackColumn::Duration,
+        "liked" => TrackColumn::Liked,
+        "popularity" => TrackColumn::Popularity,
+        "track_number" => TrackColumn::TrackNumber,
+        "added_at" => TrackColumn::AddedAt,
+        "added_by" => TrackColumn::AddedBy,
+        "bpm" => TrackColumn::Bpm,
+        "key" => TrackColumn::Key,
+        "explicit" => TrackColumn::Explicit,
+        _ => {
+            return Err(failure::format_err!(
+                "The track table column \"{}\" is unknown",
+                column
+            ))
+        }
+    };
+
+    Ok(column)
+}
+
+fn parse_column_width(width: ColumnWidthString) -> Result<ColumnWidth, failure::Error> {
+    let width = match width {
+        ColumnWidthString::Length(length) => ColumnWidth::Length(length),
+        ColumnWidthString::Percent(percent) => {
+            match percent.trim().trim_end_matches('%').trim().parse::<u16>() {
+                Ok(number) if percent.trim().ends_with('%') && number <= 100 => {
+                    ColumnWidth::Percent(number)
+                }
+                _ => {
+                    return Err(failure::format_err!(
+                        "Column width must be a number of cells or a percentage like \"30%\", is \"{}\"",
+                        percent
+                    ))
+                }
+            }
+        }
+    };
+
+    Ok(width)
+}
+
+fn load_theme_preset(name: &str, themes_dir_path: &Path) -> Result<UserTheme, failure::Error> {
+    let theme_file_path = themes_dir_path.join(format!("{}.yml", name));
+    let theme_string = if theme_file_path.exists() {
+        fs::read_to_string(&theme_file_path)?
+    } else {
+        match BUNDLED_THEMES.iter().find(|(preset, _)| *preset == name) {
+            Some((_, theme_string)) => theme_string.to_string(),
+            None => {
+                return Err(failure::format_err!(
+                    "The theme \"{}\" was not found in {}",
+                    name,
+                    themes_dir_path.display()
+                ))
+            }
+        }
+    };
+
+    // serde fails if file is empty
+    if theme_string.trim().is_empty() {
+        return Ok(Default::default());
+    }
+    Ok(serde_yaml::from_str(&theme_string)?)
+}
+
+fn parse_theme_style(theme_item: ThemeItemString, style: Style) -> Result<Style, failure::Error> {
+    match theme_item {
+        ThemeItemString::Color(color) => Ok(style.fg(parse_color(color)?)),
+        ThemeItemString::Style(theme_style) => {
+            let mut style = style;
+            if let Some(fg) = theme_style.fg {
+                style = style.fg(parse_color(fg)?);
+            }
+            if let Some(bg) = theme_style.bg {
+                style = style.bg(parse_color(bg)?);
+            }
+            if let Some(modifiers) = theme_style.modifiers {
+                let mut modifier = Modifier::empty();
+                for name in modifiers.iter() {
+                    modifier |= parse_modifier(name)?;
+                }
+                style = style.modifier(modifier);
+            }
+            Ok(style)
+        }
+    }
+}
+
+fn parse_color(color: ColorString) -> Result<Color, failure::Error> {
+    match color {
+        ColorString::Index(index) => Ok(Color::Indexed(index)),
+        ColorString::Name(name) => parse_theme_item(&name),
+    }
+}
+
+fn parse_modifier(modifier: &str) -> Result<Modifier, failure::Error> {
+    let modifier = match modifier.to_lowercase().as_str() {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" => Modifier::UNDERLINED,
+        "slow_blink" => Modifier::SLOW_BLINK,
+        "rapid_blink" => Modifier::RAPID_BLINK,
+        "reversed" => Modifier::REVERSED,
+        "hidden" => Modifier::HIDDEN,
+        "crossed_out" => Modifier::CROSSED_OUT,
+        _ => {
+            return Err(failure::format_err!(
+                "The modifier \"{}\" is unknown",
+                modifier
+            ))
+        }
+    };
+
+    Ok(modifier)
+}
+
 fn parse_theme_item(theme_item: &str) -> Result<Color, failure::Error> {
     let color = match theme_item {
         "Reset" => Color::Reset,
@@ -410,6 +935,20 @@ fn parse_theme_item(theme_item: &str) -> Result<Color, failure::Error> {
         "LightMagenta" => Color::LightMagenta,
         "LightCyan" => Color::LightCyan,
         "White" => Color::White,
+        hex if hex.starts_with('#') => {
+            let hex = &hex[1..];
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(failure::format_err!(
+                    "The colour \"{}\" is not of the form #rrggbb",
+                    theme_item
+                ));
+            }
+            Color::Rgb(
+                u8::from_str_radix(&hex[0..2], 16)?,
+                u8::from_str_radix(&hex[2..4], 16)?,
+                u8::from_str_radix(&hex[4..6], 16)?,
+            )
+        }
         _ => {
             let colors = theme_item.split(',').collect::<Vec<&str>>();
             if let (Some(r), Some(g), Some(b)) = (colors.get(0), colors.get(1), colors.get(2)) {
@@ -418,9 +957,10 @@ fn parse_theme_item(theme_item: &str) -> Result<Color, failure::Error> {
                     g.trim().parse::<u8>()?,
                     b.trim().parse::<u8>()?,
                 )
+            } else if let Ok(index) = theme_item.trim().parse::<u8>() {
+                Color::Indexed(index)
             } else {
-                println!("Unexpected color {}", theme_item);
-                Color::Black
+                return Err(failure::format_err!("Unexpected colour \"{}\"", theme_item));
             }
         }
     };
@@ -430,6 +970,8 @@ fn parse_theme_item(theme_item: &str) -> Result<Color, failure::Error> {
 
 #[cfg(test)]
 mod tests {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
     #[test]
     fn test_parse_key() {
         use super::parse_key;
@@ -441,6 +983,58 @@ mod tests {
         assert_eq!(parse_key(String::from("-")).unwrap(), Key::Char('-'));
         assert_eq!(parse_key(String::from("esc")).unwrap(), Key::Esc);
         assert_eq!(parse_key(String::from("del")).unwrap(), Key::Delete);
+        assert_eq!(parse_key(String::from("enter")).unwrap(), Key::Enter);
+        assert_eq!(parse_key(String::from("tab")).unwrap(), Key::Tab);
+        assert_eq!(parse_key(String::from("shift-tab")).unwrap(), Key::BackTab);
+        assert_eq!(parse_key(String::from("home")).unwrap(), Key::Home);
+        assert_eq!(parse_key(String::from("end")).unwrap(), Key::End);
+        assert_eq!(parse_key(String::from("ins")).unwrap(), Key::Ins);
+        assert_eq!(parse_key(String::from("f1")).unwrap(), Key::F1);
+        assert_eq!(parse_key(String::from("F12")).unwrap(), Key::F12);
+        assert_eq!(parse_key(String::from("ctrl--")).unwrap(), Key::Ctrl('-'));
+        assert_eq!(parse_key(String::from("shift-a")).unwrap(), Key::Char('A'));
+        assert_eq!(
+            parse_key(String::from("ctrl-alt-x")).unwrap(),
+            Key::Modified(
+                KeyModifiers::CONTROL | KeyModifiers::ALT,
+                KeyCode::Char('x')
+            )
+        );
+        assert_eq!(
+            parse_key(String::from("shift-up")).unwrap(),
+            Key::Modified(KeyModifiers::SHIFT, KeyCode::Up)
+        );
+        assert!(parse_key(String::from("f13")).is_err());
+        assert!(parse_key(String::from("hyper-x")).is_err());
+        assert!(parse_key(String::from("nope")).is_err());
+    }
+
+    #[test]
+    fn test_parse_key_round_trip() {
+        use super::parse_key;
+        use crate::event::Key;
+
+        let keys = [
+            Key::Char('a'),
+            Key::Char('-'),
+            Key::Char(' '),
+            Key::Ctrl('s'),
+            Key::Alt('-'),
+            Key::BackTab,
+            Key::Delete,
+            Key::Backspace,
+            Key::PageDown,
+            Key::F0,
+            Key::Modified(
+                KeyModifiers::CONTROL | KeyModifiers::ALT,
+                KeyCode::Char('x'),
+            ),
+            Key::Modified(KeyModifiers::CONTROL, KeyCode::BackTab),
+            Key::Modified(KeyModifiers::SHIFT | KeyModifiers::ALT, KeyCode::End),
+        ];
+        for key in keys.iter() {
+            assert_eq!(parse_key(key.to_string()).unwrap(), *key);
+        }
     }
 
     #[test]
@@ -471,6 +1065,143 @@ mod tests {
             parse_theme_item("23, 43, 45").unwrap(),
             Color::Rgb(23, 43, 45)
         );
+        assert_eq!(
+            parse_theme_item("#17ab2D").unwrap(),
+            Color::Rgb(23, 171, 45)
+        );
+        assert_eq!(parse_theme_item("208").unwrap(), Color::Indexed(208));
+        assert!(parse_theme_item("#17ab2").is_err());
+        assert!(parse_theme_item("#17ag2d").is_err());
+        assert!(parse_theme_item("Purple").is_err());
+    }
+
+    #[test]
+    fn test_parse_theme_style() {
+        use super::{parse_theme_style, ThemeItemString};
+        use tui::style::{Color, Modifier, Style};
+
+        let base = Style::default().fg(Color::White).modifier(Modifier::BOLD);
+
+        let item: ThemeItemString = serde_yaml::from_str("208").unwrap();
+        assert_eq!(
+            parse_theme_style(item, base).unwrap(),
+            base.fg(Color::Indexed(208))
+        );
+
+        let item: ThemeItemString =
+            serde_yaml::from_str("{bg: \"#000000\", modifiers: [italic, Underlined]}").unwrap();
+        assert_eq!(
+            parse_theme_style(item, base).unwrap(),
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Rgb(0, 0, 0))
+                .modifier(Modifier::ITALIC | Modifier::UNDERLINED)
+        );
+
+        let item: ThemeItemString = serde_yaml::from_str("{modifiers: [shiny]}").unwrap();
+        assert!(parse_theme_style(item, base).is_err());
+
+        assert!(serde_yaml::from_str::<ThemeItemString>("{foreground: red}").is_err());
+    }
+
+    #[test]
+    fn test_load_theme_presets() {
+        use super::{UserConfig, UserTheme, BUNDLED_THEMES};
+        use std::path::Path;
+        use tui::style::Color;
+
+        let themes_dir_path = Path::new("/nonexistent/spotify-tui/themes");
+        for (name, _) in BUNDLED_THEMES.iter() {
+            let mut user_config = UserConfig::new();
+            let theme = UserTheme {
+                name: Some(name.to_string()),
+                ..Default::default()
+            };
+            assert!(
+                user_config.load_theme(theme, themes_dir_path).is_ok(),
+                "The bundled theme {} should load",
+                name
+            );
+        }
+
+        // Items next to the name override the preset
+        let mut user_config = UserConfig::new();
+        let theme: UserTheme = serde_yaml::from_str("{name: high-contrast, text: Green}").unwrap();
+        user_config.load_theme(theme, themes_dir_path).unwrap();
+        assert_eq!(user_config.theme.text.fg, Color::Green);
+        assert_eq!(user_config.theme.error_border.fg, Color::Rgb(255, 0, 0));
+
+        let theme = UserTheme {
+            name: Some("nope".to_string()),
+            ..Default::default()
+        };
+        assert!(UserConfig::new()
+            .load_theme(theme, themes_dir_path)
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_layoutconfig() {
+        use super::{LayoutConfigString, PlaybarPosition, UserConfig};
+
+        let mut user_config = UserConfig::new();
+        let layout: LayoutConfigString = serde_yaml::from_str(
+            "{sidebar_width_percent: 30, show_search: false, playbar_position: Top}",
+        )
+        .unwrap();
+        user_config.load_layoutconfig(layout).unwrap();
+        assert_eq!(user_config.layout.sidebar_width_percent, 30);
+        assert!(!user_config.layout.show_search);
+        assert!(user_config.layout.show_library);
+        assert_eq!(user_config.layout.playbar_position, PlaybarPosition::Top);
+
+        for invalid in [
+            "{sidebar_width_percent: 100}",
+            "{playbar_height: 3}",
+            "{playbar_position: left}",
+        ]
+        .iter()
+        {
+            let layout: LayoutConfigString = serde_yaml::from_str(invalid).unwrap();
+            assert!(UserConfig::new().load_layoutconfig(layout).is_err());
+        }
+    }
+
+    #[test]
+    fn test_load_track_tables() {
+        use super::{
+            ColumnWidth, TrackColumn, TrackColumnConfig, TrackTablesConfigString, UserConfig,
+        };
+
+        let mut user_config = UserConfig::new();
+        let track_tables: TrackTablesConfigString = serde_yaml::from_str(
+            "song: [liked, {column: title, width: 50%}, {column: added-by, width: 12}, bpm]",
+        )
+        .unwrap();
+        user_config.load_track_tables(track_tables).unwrap();
+        assert_eq!(
+            user_config.track_tables.song,
+            vec![
+                TrackColumnConfig::new(TrackColumn::Liked, ColumnWidth::Length(2)),
+                TrackColumnConfig::new(TrackColumn::Title, ColumnWidth::Percent(50)),
+                TrackColumnConfig::new(TrackColumn::AddedBy, ColumnWidth::Length(12)),
+                TrackColumnConfig::new(TrackColumn::Bpm, ColumnWidth::Length(5)),
+            ]
+        );
+        // Tables that aren't configured keep their columns
+        assert_eq!(user_config.track_tables.album.len(), 4);
+
+        for invalid in [
+            "song: []",
+            "album: [lyrics]",
+            "recently_played: [{column: title, width: 120%}]",
+            "recently_played: [{column: title, width: wide}]",
+        ]
+        .iter()
+        {
+            let track_tables: TrackTablesConfigString = serde_yaml::from_str(invalid).unwrap();
+            assert!(UserConfig::new().load_track_tables(track_tables).is_err());
+        }
     }
 
     #[test]
@@ -0,0 +1,346 @@
+//! Spotify Web API endpoints that rspotify is missing or gets wrong
+
+use crate::podcasts::{SavedShow, SimplifiedEpisode};
+use failure::format_err;
+use reqwest::{blocking::Client, Method};
+use rspotify::spotify::{
+    client::{ApiError, Spotify},
+    model::page::Page,
+};
+use serde_json::{json, Value};
+
+// The most playlist tracks Spotify returns, or adds, at once
+const PLAYLIST_TRACKS_LIMIT: u32 = 100;
+
+/// Moves playback to another device, keeping the position. `play` starts playback there,
+/// otherwise it stays paused or playing as it was.
+// NOTE: rspotify's `transfer_playback` sends `devie_ids`, which Spotify ignores
+pub fn transfer_playback(
+    spotify: &Spotify,
+    device_id: &str,
+    play: bool,
+) -> Result<(), failure::Error> {
+    let body = json!({
+        "device_ids": [device_id],
+        "play": play,
+    });
+    request(spotify, Method::PUT, "me/player", Some(&body))?;
+    Ok(())
+}
+
+/// The genres recommendations can be seeded with
+// NOTE: rspotify has no way of getting these
+pub fn get_recommendation_genres(spotify: &Spotify) -> Result<Vec<String>, failure::Error> {
+    let response = request(
+        spotify,
+        Method::GET,
+        "recommendations/available-genre-seeds",
+        None,
+    )?;
+    let genres = serde_json::from_str::<Value>(&response)?["genres"]
+        .as_array()
+        .map(|genres| {
+            genres
+                .iter()
+                .filter_map(|genre| genre.as_str().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(genres)
+}
+
+/// The description of a playlist, which rspotify leaves out of the playlists it lists
+// NOTE: rspotify's `playlist` fails on playlists without a description
+pub fn get_playlist_description(
+    spotify: &Spotify,
+    playlist_id: &str,
+) -> Result<String, failure::Error> {
+    let path = format!("playlists/{}?fields=description", playlist_id);
+    let response = request(spotify, Method::GET, &path, None)?;
+    let description = serde_json::from_str::<Value>(&response)?["description"]
+        .as_str()
+        .unwrap_or_default()
+        .to_owned();
+    Ok(description)
+}
+
+/// Creates a playlist for the user, returning its id
+// NOTE: rspotify's `user_playlist_create` sends an empty description when there is none
+pub fn create_playlist(
+    spotify: &Spotify,
+    user_id: &str,
+    name: &str,
+    public: bool,
+    description: Option<String>,
+) -> Result<String, failure::Error> {
+    let mut body = json!({
+        "name": name,
+        "public": public,
+    });
+    if let Some(description) = description {
+        body["description"] = json!(description);
+    }
+    let path = format!("users/{}/playlists", user_id);
+    let response = request(spotify, Method::POST, &path, Some(&body))?;
+    serde_json::from_str::<Value>(&response)?["id"]
+        .as_str()
+        .map(str::to_owned)
+        .ok_or_else(|| format_err!("Spotify didn't say which playlist it created"))
+}
+
+/// Adds tracks to the end of a playlist, as many requests as it takes
+// NOTE: rspotify only has the deprecated `users/{user_id}/playlists` endpoint for this
+pub fn add_tracks_to_playlist(
+    spotify: &Spotify,
+    playlist_id: &str,
+    track_uris: &[String],
+) -> Result<(), failure::Error> {
+    let path = format!("playlists/{}/tracks", playlist_id);
+    for batch in track_uris.chunks(PLAYLIST_TRACKS_LIMIT as usize) {
+        request(
+            spotify,
+            Method::POST,
+            &path,
+            Some(&json!({ "uris": batch })),
+        )?;
+    }
+    Ok(())
+}
+
+/// Uris of all the tracks in a playlist. Only the uris are asked for, so this takes fewer
+/// and smaller requests than rspotify's `user_playlist_tracks`.
+pub fn get_playlist_track_uris(
+    spotify: &Spotify,
+    playlist_id: &str,
+) -> Result<Vec<String>, failure::Error> {
+    let mut uris = vec![];
+    let mut offset = 0;
+    loop {
+        let path = format!(
+            "playlists/{}/tracks?fields=items(track(uri)),next&limit={}&offset={}",
+            playlist_id, PLAYLIST_TRACKS_LIMIT, offset
+        );
+        let page = serde_json::from_str::<Value>(&request(spotify, Method::GET, &path, None)?)?;
+        if let Some(items) = page["items"].as_array() {
+            // Tracks that are no longer available have no track
+            uris.extend(
+                items
+                    .iter()
+                    .filter_map(|item| item["track"]["uri"].as_str().map(str::to_owned)),
+            );
+        }
+        if page["next"].is_null() {
+            return Ok(uris);
+        }
+        offset += PLAYLIST_TRACKS_LIMIT;
+    }
+}
+
+/// The shows the user saved to their library
+// NOTE: rspotify has no podcast endpoints
+pub fn get_saved_shows(
+    spotify: &Spotify,
+    limit: u32,
+    offset: u32,
+) -> Result<Page<SavedShow>, failure::Error> {
+    let path = format!("me/shows?limit={}&offset={}", limit, offset);
+    let response = request(spotify, Method::GET, &path, None)?;
+    Ok(serde_json::from_str(&response)?)
+}
+
+/// A page of the episodes of a show, the latest first
+pub fn get_show_episodes(
+    spotify: &Spotify,
+    show_id: &str,
+    limit: u32,
+    offset: u32,
+) -> Result<Page<SimplifiedEpisode>, failure::Error> {
+    // Spotify only returns episodes available in the user's market
+    let path = format!(
+        "shows/{}/episodes?limit={}&offset={}&market=from_token",
+        show_id, limit, offset
+    );
+    let response = request(spotify, Method::GET, &path, None)?;
+    Ok(serde_json::from_str(&response)?)
+}
+
+pub fn save_shows(spotify: &Spotify, show_ids: &[String]) -> Result<(), failure::Error> {
+    let path = format!("me/shows?ids={}", show_ids.join(","));
+    request(spotify, Method::PUT, &path, None)?;
+    Ok(())
+}
+
+pub fn remove_saved_shows(spotify: &Spotify, show_ids: &[String]) -> Result<(), failure::Error> {
+    let path = format!("me/shows?ids={}", show_ids.join(","));
+    request(spotify, Method::DELETE, &path, None)?;
+    Ok(())
+}
+
+fn request(
+    spotify: &Spotify,
+    method: Method,
+    path: &str,
+    body: Option<&Value>,
+) -> Result<String, failure::Error> {
+    let access_token = match (&spotify.access_token, &spotify.client_credentials_manager) {
+        (Some(access_token), _) => access_token.to_owned(),
+        (None, Some(client_credentials)) => client_credentials.get_access_token(),
+        (None, None) => return Err(format_err!("Not logged in to Spotify")),
+    };
+
+    let request = Client::new()
+        // The prefix is the API's url, unless it is pointed at a stand-in
+        .request(method, &format!("{}{}", spotify.prefix, path))
+        .bearer_auth(access_token);
+    let request = match body {
+        Some(body) => request.json(body),
+        None => request,
+    };
+
+    let response = request.send()?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response.text()?);
+    }
+
+    // The message is for showing, rspotify's error underneath it is for telling errors apart
+    let api_error = ApiError::from(&response);
+    let message = get_error_message(&response.text()?, status.as_u16());
+    Err(failure::Error::from(api_error).context(message).into())
+}
+
+/// Whether the request failed with `status`, for errors from here as well as from rspotify
+pub fn has_status(e: &failure::Error, status: u16) -> bool {
+    e.iter_chain()
+        .any(|cause| match cause.downcast_ref::<ApiError>() {
+            Some(ApiError::Other(cause_status)) => *cause_status == status,
+            _ => false,
+        })
+}
+
+// Spotify errors look like `{"error": {"status": 404, "message": "Device not found"}}`
+fn get_error_message(body: &str, status: u16) -> String {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|error| error["error"]["message"].as_str().map(str::to_owned))
+        .map_or_else(
+            || format!("Spotify API request failed with status {}", status),
+            |message| format!("{} (status {})", message, status),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_server;
+
+    #[test]
+    fn test_library_endpoints() {
+        let (spotify, _) = fake_server::start_api(vec![
+            (
+                "GET /me/shows?limit=20&offset=0 ",
+                r#"{
+                    "href": "",
+                    "items": [{
+                        "added_at": "2020-05-01T10:00:00Z",
+                        "show": {
+                            "id": "show1",
+                            "name": "The Show",
+                            "publisher": "Someone",
+                            "description": "About things",
+                            "uri": "spotify:show:show1",
+                            "total_episodes": 12
+                        }
+                    }],
+                    "limit": 20,
+                    "next": null,
+                    "offset": 0,
+                    "previous": null,
+                    "total": 1
+                }"#,
+            ),
+            (
+                "GET /shows/show1/episodes?limit=20&offset=0&market=from_token ",
+                r#"{
+                    "href": "",
+                    "items": [{
+                        "id": "episode1",
+                        "name": "First",
+                        "description": "It starts",
+                        "release_date": "2020-04-01",
+                        "duration_ms": 1800000,
+                        "uri": "spotify:episode:episode1",
+                        "resume_point": {"fully_played": false, "resume_position_ms": 60000}
+                    }],
+                    "limit": 20,
+                    "next": null,
+                    "offset": 0,
+                    "previous": null,
+                    "total": 1
+                }"#,
+            ),
+            ("PUT /me/shows?ids=show1,show2 ", ""),
+            ("DELETE /me/shows?ids=show1 ", ""),
+            (
+                "GET /playlists/playlist1?fields=description ",
+                r#"{"description": "Songs"}"#,
+            ),
+            (
+                "GET /playlists/playlist1/tracks?fields=items(track(uri)),next&limit=100&offset=0 ",
+                r#"{
+                    "items": [{"track": {"uri": "spotify:track:1"}}, {"track": null}],
+                    "next": "more"
+                }"#,
+            ),
+            (
+                "GET /playlists/playlist1/tracks?fields=items(track(uri)),next&limit=100&offset=100 ",
+                r#"{"items": [{"track": {"uri": "spotify:track:2"}}], "next": null}"#,
+            ),
+            (
+                "GET /playlists/playlist2?fields=description ",
+                r#"{"description": null}"#,
+            ),
+        ]);
+
+        let shows = get_saved_shows(&spotify, 20, 0).unwrap();
+        assert_eq!(shows.items[0].show.name, "The Show");
+        assert_eq!(shows.items[0].show.total_episodes, 12);
+
+        let episodes = get_show_episodes(&spotify, "show1", 20, 0).unwrap();
+        assert_eq!(episodes.items[0].get_resume_position_ms(), Some(60000));
+
+        let show_ids = vec!["show1".to_string(), "show2".to_string()];
+        assert!(save_shows(&spotify, &show_ids).is_ok());
+        assert!(remove_saved_shows(&spotify, &show_ids[..1]).is_ok());
+
+        assert_eq!(
+            get_playlist_description(&spotify, "playlist1").unwrap(),
+            "Songs"
+        );
+        assert_eq!(get_playlist_description(&spotify, "playlist2").unwrap(), "");
+        assert_eq!(
+            get_playlist_track_uris(&spotify, "playlist1").unwrap(),
+            vec!["spotify:track:1", "spotify:track:2"]
+        );
+
+        let error = get_show_episodes(&spotify, "missing", 20, 0).unwrap_err();
+        assert_eq!(error.to_string(), "Not found (status 404)");
+        assert!(has_status(&error, 404));
+        assert!(!has_status(&error, 500));
+    }
+
+    #[test]
+    fn test_get_error_message() {
+        assert_eq!(
+            get_error_message(
+                r#"{"error": {"status": 404, "message": "Device not found"}}"#,
+                404
+            ),
+            "Device not found (status 404)"
+        );
+        assert_eq!(
+            get_error_message("<html>Bad gateway</html>", 502),
+            "Spotify API request failed with status 502"
+        );
+    }
+}